@@ -0,0 +1,131 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::config::RiskConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AlertLevel {
+    Warning,
+    Critical,
+}
+
+/// Something an operator needs to act on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub level: AlertLevel,
+    pub title: String,
+    pub message: String,
+    pub position_id: Option<String>,
+    pub raised_at: DateTime<Utc>,
+}
+
+impl Alert {
+    pub fn warning(title: &str, message: String, position_id: Option<String>) -> Self {
+        Self {
+            level: AlertLevel::Warning,
+            title: title.to_string(),
+            message,
+            position_id,
+            raised_at: Utc::now(),
+        }
+    }
+
+    pub fn critical(title: &str, message: String, position_id: Option<String>) -> Self {
+        Self {
+            level: AlertLevel::Critical,
+            title: title.to_string(),
+            message,
+            position_id,
+            raised_at: Utc::now(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn send(&self, alert: &Alert) -> Result<()>;
+}
+
+/// Writes alerts to the log only
+pub struct LogAlertSink;
+
+#[async_trait]
+impl AlertSink for LogAlertSink {
+    async fn send(&self, alert: &Alert) -> Result<()> {
+        match alert.level {
+            AlertLevel::Warning => tracing::warn!("ALERT {}: {}", alert.title, alert.message),
+            AlertLevel::Critical => tracing::error!("ALERT {}: {}", alert.title, alert.message),
+        }
+        Ok(())
+    }
+}
+
+/// Logs alerts and POSTs them as JSON to a webhook (Slack-compatible `text` included)
+pub struct WebhookAlertSink {
+    client: Client,
+    url: String,
+}
+
+impl WebhookAlertSink {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookAlertSink {
+    async fn send(&self, alert: &Alert) -> Result<()> {
+        LogAlertSink.send(alert).await?;
+
+        let mut body = serde_json::to_value(alert)?;
+        body["text"] = serde_json::Value::String(format!("[{:?}] {}: {}", alert.level, alert.title, alert.message));
+
+        self.client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Webhook sink if `alert_webhook_url` is set, log-only otherwise
+pub fn sink_from_config(config: &RiskConfig) -> Box<dyn AlertSink> {
+    match config.alert_webhook_url.as_deref() {
+        Some(url) if !url.is_empty() => Box::new(WebhookAlertSink::new(url)),
+        _ => Box::new(LogAlertSink),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_webhook_posts_alert() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "level": "Critical",
+                "title": "Unwind failed",
+                "position_id": "p1"
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let sink = WebhookAlertSink::new(&server.uri());
+        let alert = Alert::critical("Unwind failed", "naked btc leg".to_string(), Some("p1".to_string()));
+        sink.send(&alert).await.unwrap();
+    }
+}
@@ -136,37 +136,4 @@ pub struct PredyxOrderBook {
     pub orders: serde_json::Value,
 }
 
-// Ordinals marketplace integration (placeholder)
-pub struct OrdinalsMarketplace {
-    endpoint: String,
-    wallet_address: String,
-}
-
-impl OrdinalsMarketplace {
-    pub fn new(endpoint: String, wallet_address: String) -> Self {
-        Self {
-            endpoint,
-            wallet_address,
-        }
-    }
-
-    pub async fn fetch_markets(&self) -> Result<Vec<Market>> {
-        warn!("Ordinals marketplace not implemented yet - returning empty markets");
-
-        // TODO: Implement actual API calls
-        // - Connect to Gamma API or other Ordinals marketplace
-        // - Fetch available prediction markets
-        // - Convert to our Market format
-
-        Ok(vec![])
-    }
-
-    pub async fn fetch_odds(&self, _market_id: &str) -> Result<Vec<MarketOdds>> {
-        warn!("Ordinals odds not implemented yet");
-        Ok(vec![])
-    }
-
-    pub fn is_configured(&self) -> bool {
-        !self.endpoint.is_empty() && !self.wallet_address.is_empty()
-    }
-}
+// Ordinals marketplace lives in `crate::api::ordinals`
@@ -1,3 +1,4 @@
+pub mod ordinals;
 pub mod polymarket;
 
 use async_trait::async_trait;
@@ -5,6 +6,7 @@ use anyhow::Result;
 
 pub use crate::api::polymarket::PolymarketClient;
 pub use crate::models::{Market, MarketOdds};
+use crate::models::polymarket::{OrderBook, PriceLevel};
 
 #[async_trait]
 pub trait MarketClient: Send + Sync {
@@ -12,3 +14,43 @@ pub trait MarketClient: Send + Sync {
     async fn fetch_odds(&self, market_id: &str) -> Result<Vec<MarketOdds>>;
     fn is_configured(&self) -> bool;
 }
+
+/// Live order book for one outcome of a market
+#[async_trait]
+pub trait OrderBookSource: Send + Sync {
+    async fn order_book(&self, market_id: &str, outcome: &str) -> Result<OrderBook>;
+}
+
+/// Book synthesized from quoted odds for venues that publish no depth:
+/// a single level at the quote on each side with a fixed size
+pub struct QuoteBookSource {
+    client: Box<dyn MarketClient>,
+    depth: rust_decimal::Decimal,
+}
+
+impl QuoteBookSource {
+    pub fn new(client: Box<dyn MarketClient>, depth: rust_decimal::Decimal) -> Self {
+        Self { client, depth }
+    }
+}
+
+#[async_trait]
+impl OrderBookSource for QuoteBookSource {
+    async fn order_book(&self, market_id: &str, outcome: &str) -> Result<OrderBook> {
+        let odds = self.client.fetch_odds(market_id).await?;
+        let quote = odds
+            .iter()
+            .find(|o| o.outcome.eq_ignore_ascii_case(outcome))
+            .ok_or_else(|| anyhow::anyhow!("No quote for {} {}", market_id, outcome))?;
+
+        let level = PriceLevel {
+            price: quote.odds,
+            size: self.depth,
+        };
+        Ok(OrderBook {
+            outcome: outcome.to_string(),
+            bids: vec![level.clone()],
+            asks: vec![level],
+        })
+    }
+}
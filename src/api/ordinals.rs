@@ -0,0 +1,413 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::api::{MarketClient, OrderBookSource};
+use crate::models::polymarket::{OrderBook, PriceLevel};
+use crate::models::{Market, MarketOdds, MarketSource};
+use crate::resolver::ResolutionStatus;
+
+/// Inscription metadata as returned by `ord server` with `Accept: application/json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inscription {
+    pub id: String,
+    pub number: i64,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// `<txid>:<vout>:<offset>` of the inscribed sat
+    pub satpoint: String,
+    /// Value in sats of the output holding the inscription
+    #[serde(default)]
+    pub value: Option<u64>,
+}
+
+impl Inscription {
+    /// Outpoint (`<txid>:<vout>`) currently holding the inscription
+    pub fn outpoint(&self) -> Result<String> {
+        let mut parts = self.satpoint.rsplitn(2, ':');
+        let _offset = parts.next();
+        parts
+            .next()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Invalid satpoint: {}", self.satpoint))
+    }
+}
+
+/// Output details from `ord server`, used to keep inscribed sats out of coin selection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrdOutput {
+    pub value: u64,
+    #[serde(default)]
+    pub inscriptions: Vec<String>,
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChildrenPage {
+    ids: Vec<String>,
+    #[serde(default)]
+    more: bool,
+}
+
+/// Market state carried in the content of a market inscription.
+/// Markets are children of a configured parent inscription; a newer child
+/// with the same `market_id` supersedes earlier ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrdMarketState {
+    pub market_id: String,
+    pub question: String,
+    pub outcomes: Vec<String>,
+    #[serde(default)]
+    pub end_time: Option<DateTime<Utc>>,
+    #[serde(default = "default_market_status")]
+    pub status: String,
+    /// Sats paid per winning outcome inscription at resolution
+    pub payout_sats: u64,
+    /// Set once `status` is "resolved"
+    #[serde(default)]
+    pub winning_outcome: Option<String>,
+    #[serde(default)]
+    pub offers: Vec<OutcomeOffer>,
+    /// Number of the inscription this state was read from
+    #[serde(skip)]
+    pub inscription_number: i64,
+}
+
+/// An outcome inscription offered for sale with a seller-signed
+/// `SIGHASH_SINGLE|ANYONECANPAY` PSBT
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutcomeOffer {
+    pub outcome: String,
+    pub inscription_id: String,
+    pub price_sats: u64,
+    pub psbt: String,
+}
+
+fn default_market_status() -> String {
+    "open".to_string()
+}
+
+impl OrdMarketState {
+    pub fn resolution(&self) -> ResolutionStatus {
+        match (self.status.as_str(), &self.winning_outcome) {
+            ("resolved", Some(outcome)) => ResolutionStatus::Resolved { winning_outcome: outcome.clone() },
+            ("disputed", _) => ResolutionStatus::Disputed,
+            _ => ResolutionStatus::Open,
+        }
+    }
+
+    /// Implied probability of an offer: price over payout
+    pub fn offer_odds(&self, offer: &OutcomeOffer) -> Decimal {
+        if self.payout_sats == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(offer.price_sats) / Decimal::from(self.payout_sats)
+    }
+
+    /// Cheapest open offer for an outcome
+    pub fn best_offer(&self, outcome: &str) -> Option<&OutcomeOffer> {
+        self.offers
+            .iter()
+            .filter(|o| o.outcome.eq_ignore_ascii_case(outcome))
+            .min_by_key(|o| o.price_sats)
+    }
+}
+
+/// HTTP client for an `ord server` instance
+pub struct OrdClient {
+    client: Client,
+    base_url: String,
+}
+
+impl OrdClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .header("Accept", "application/json")
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn get_inscription(&self, id: &str) -> Result<Inscription> {
+        self.get_json(&format!("/inscription/{}", id)).await
+    }
+
+    pub async fn get_content(&self, id: &str) -> Result<Vec<u8>> {
+        let response = self
+            .client
+            .get(format!("{}/content/{}", self.base_url, id))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    pub async fn get_output(&self, outpoint: &str) -> Result<OrdOutput> {
+        self.get_json(&format!("/output/{}", outpoint)).await
+    }
+
+    /// All child inscription ids of a parent, following pagination
+    pub async fn get_children(&self, parent: &str) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        let mut page = 0;
+
+        loop {
+            let response: ChildrenPage = self
+                .get_json(&format!("/r/children/{}/{}", parent, page))
+                .await?;
+            ids.extend(response.ids);
+
+            if !response.more {
+                return Ok(ids);
+            }
+            page += 1;
+        }
+    }
+
+    /// Read a market state from an inscription's content
+    pub async fn get_market_state(&self, id: &str) -> Result<OrdMarketState> {
+        let inscription = self.get_inscription(id).await?;
+        let content = self.get_content(id).await?;
+        let mut state: OrdMarketState = serde_json::from_slice(&content)
+            .map_err(|e| anyhow!("Inscription {} is not a market: {}", id, e))?;
+        state.inscription_number = inscription.number;
+        Ok(state)
+    }
+}
+
+/// Ordinals marketplace: prediction markets published as inscriptions
+pub struct OrdinalsMarketplace {
+    client: OrdClient,
+    endpoint: String,
+    parent_inscription: String,
+}
+
+impl OrdinalsMarketplace {
+    pub fn new(endpoint: String, parent_inscription: String) -> Self {
+        Self {
+            client: OrdClient::new(&endpoint),
+            endpoint,
+            parent_inscription,
+        }
+    }
+
+    pub fn client(&self) -> &OrdClient {
+        &self.client
+    }
+
+    /// Latest state of every market under the parent inscription
+    pub async fn fetch_market_states(&self) -> Result<Vec<OrdMarketState>> {
+        let children = self.client.get_children(&self.parent_inscription).await?;
+        let mut latest: HashMap<String, OrdMarketState> = HashMap::new();
+
+        for id in children {
+            let state = match self.client.get_market_state(&id).await {
+                Ok(state) => state,
+                Err(e) => {
+                    warn!("Skipping inscription {}: {}", id, e);
+                    continue;
+                }
+            };
+
+            let is_newer = latest
+                .get(&state.market_id)
+                .map(|current| state.inscription_number > current.inscription_number)
+                .unwrap_or(true);
+            if is_newer {
+                latest.insert(state.market_id.clone(), state);
+            }
+        }
+
+        let mut states: Vec<OrdMarketState> = latest.into_values().collect();
+        states.sort_by(|a, b| a.market_id.cmp(&b.market_id));
+        Ok(states)
+    }
+
+    pub async fn fetch_market_state(&self, market_id: &str) -> Result<OrdMarketState> {
+        self.fetch_market_states()
+            .await?
+            .into_iter()
+            .find(|s| s.market_id == market_id)
+            .ok_or_else(|| anyhow!("Ordinals market not found: {}", market_id))
+    }
+}
+
+#[async_trait]
+impl MarketClient for OrdinalsMarketplace {
+    async fn fetch_markets(&self) -> Result<Vec<Market>> {
+        let markets: Vec<Market> = self
+            .fetch_market_states()
+            .await?
+            .into_iter()
+            .filter(|s| s.status == "open")
+            .map(|s| Market {
+                id: s.market_id,
+                question: s.question,
+                description: None,
+                outcomes: s.outcomes,
+                end_time: s.end_time,
+                volume: None,
+                liquidity: None,
+            })
+            .collect();
+
+        info!("Fetched {} markets from ord server", markets.len());
+        Ok(markets)
+    }
+
+    async fn fetch_odds(&self, market_id: &str) -> Result<Vec<MarketOdds>> {
+        let state = self.fetch_market_state(market_id).await?;
+
+        Ok(state
+            .outcomes
+            .iter()
+            .filter_map(|outcome| {
+                state.best_offer(outcome).map(|offer| MarketOdds {
+                    market_id: state.market_id.clone(),
+                    outcome: outcome.clone(),
+                    odds: state.offer_odds(offer),
+                    source: MarketSource::BitcoinPredictionMarket,
+                    timestamp: Utc::now(),
+                    resolves_at: state.end_time,
+                })
+            })
+            .collect())
+    }
+
+    fn is_configured(&self) -> bool {
+        !self.endpoint.is_empty() && !self.parent_inscription.is_empty()
+    }
+}
+
+/// Each open offer is one inscription for sale; there are no bids
+#[async_trait]
+impl OrderBookSource for OrdinalsMarketplace {
+    async fn order_book(&self, market_id: &str, outcome: &str) -> Result<OrderBook> {
+        let state = self.fetch_market_state(market_id).await?;
+
+        let mut asks: Vec<PriceLevel> = state
+            .offers
+            .iter()
+            .filter(|o| o.outcome.eq_ignore_ascii_case(outcome))
+            .map(|o| PriceLevel {
+                price: state.offer_odds(o),
+                size: Decimal::ONE,
+            })
+            .collect();
+        asks.sort_by_key(|level| level.price);
+
+        Ok(OrderBook {
+            outcome: outcome.to_string(),
+            bids: vec![],
+            asks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const PARENT: &str = "parenti0";
+
+    async fn mount_market(server: &MockServer, id: &str, number: i64, state: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(path(format!("/inscription/{}", id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": id,
+                "number": number,
+                "satpoint": format!("{}:0:0", "ab".repeat(32)),
+                "value": 546
+            })))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/content/{}", id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(state))
+            .mount(server)
+            .await;
+    }
+
+    fn market_json(price_yes: u64) -> serde_json::Value {
+        serde_json::json!({
+            "market_id": "btc-100k",
+            "question": "Will BTC hit 100k?",
+            "outcomes": ["Yes", "No"],
+            "payout_sats": 100000,
+            "offers": [
+                { "outcome": "Yes", "inscription_id": "yes1i0", "price_sats": price_yes + 5000, "psbt": "" },
+                { "outcome": "Yes", "inscription_id": "yes2i0", "price_sats": price_yes, "psbt": "" },
+                { "outcome": "No", "inscription_id": "no1i0", "price_sats": 40000, "psbt": "" }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_inscription_outpoint() {
+        let inscription = Inscription {
+            id: "xi0".to_string(),
+            number: 1,
+            address: None,
+            content_type: None,
+            satpoint: "abcd:1:0".to_string(),
+            value: Some(546),
+        };
+        assert_eq!(inscription.outpoint().unwrap(), "abcd:1");
+    }
+
+    #[tokio::test]
+    async fn test_latest_market_state_wins() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/r/children/{}/0", PARENT)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ids": ["m1i0", "junki0"], "more": true, "page": 0
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/r/children/{}/1", PARENT)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ids": ["m2i0"], "more": false, "page": 1
+            })))
+            .mount(&server)
+            .await;
+        mount_market(&server, "m1i0", 10, market_json(70000)).await;
+        mount_market(&server, "m2i0", 12, market_json(60000)).await;
+        mount_market(&server, "junki0", 11, serde_json::json!({ "hello": "world" })).await;
+
+        let marketplace = OrdinalsMarketplace::new(server.uri(), PARENT.to_string());
+        let markets = marketplace.fetch_markets().await.unwrap();
+        assert_eq!(markets.len(), 1);
+        assert_eq!(markets[0].outcomes, vec!["Yes", "No"]);
+
+        let odds = marketplace.fetch_odds("btc-100k").await.unwrap();
+        let yes = odds.iter().find(|o| o.outcome == "Yes").unwrap();
+        assert_eq!(yes.odds, Decimal::from_str("0.6").unwrap());
+
+        let state = marketplace.fetch_market_state("btc-100k").await.unwrap();
+        assert_eq!(state.best_offer("yes").unwrap().inscription_id, "yes2i0");
+    }
+}
@@ -1,12 +1,15 @@
 use anyhow::Result;
 use reqwest::Client;
 use async_trait::async_trait;
-use std::str::FromStr;
 
-use crate::api::MarketClient;
-use crate::models::{Market, MarketOdds, MarketSource};
+use crate::api::{MarketClient, OrderBookSource};
+use crate::models::polymarket::{OrderBook, PriceLevel};
+use crate::models::{Market, MarketOdds};
+use crate::resolver::ResolutionStatus;
+use rust_decimal::Decimal;
 
 const GAMMA_API_BASE: &str = "https://gamma-api.polymarket.com";
+const CLOB_API_BASE: &str = "https://clob.polymarket.com";
 
 pub struct PolymarketClient {
     client: Client,
@@ -20,6 +23,12 @@ impl PolymarketClient {
     }
 }
 
+impl Default for PolymarketClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl MarketClient for PolymarketClient {
     async fn fetch_markets(&self) -> Result<Vec<Market>> {
@@ -82,3 +91,184 @@ impl MarketClient for PolymarketClient {
         true
     }
 }
+
+#[derive(Debug, serde::Deserialize)]
+struct ClobBook {
+    #[serde(default)]
+    bids: Vec<PriceLevel>,
+    #[serde(default)]
+    asks: Vec<PriceLevel>,
+}
+
+/// Order books from the Polymarket CLOB, resolving outcome token ids via Gamma
+pub struct PolymarketBookSource {
+    client: Client,
+    gamma_url: String,
+    clob_url: String,
+}
+
+impl PolymarketBookSource {
+    pub fn new() -> Self {
+        Self::with_urls(GAMMA_API_BASE, CLOB_API_BASE)
+    }
+
+    pub fn with_urls(gamma_url: &str, clob_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            gamma_url: gamma_url.trim_end_matches('/').to_string(),
+            clob_url: clob_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn market(&self, market_id: &str) -> Result<serde_json::Value> {
+        Ok(self
+            .client
+            .get(format!("{}/markets/{}", self.gamma_url, market_id))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// CLOB token id of a market outcome
+    pub async fn token_id(&self, market_id: &str, outcome: &str) -> Result<String> {
+        let market = self.market(market_id).await?;
+        let outcomes = string_list(&market, "outcomes");
+        let token_ids = string_list(&market, "clobTokenIds");
+        outcomes
+            .iter()
+            .position(|o| o.eq_ignore_ascii_case(outcome))
+            .and_then(|i| token_ids.get(i).cloned())
+            .ok_or_else(|| anyhow::anyhow!("No CLOB token for {} {}", market_id, outcome))
+    }
+
+    /// Resolution state from Gamma: disputed while the UMA proposal is
+    /// challenged, resolved once the market is closed with a winning price of 1
+    pub async fn resolution(&self, market_id: &str) -> Result<ResolutionStatus> {
+        let market = self.market(market_id).await?;
+        if market.get("umaResolutionStatus").and_then(|v| v.as_str()) == Some("disputed") {
+            return Ok(ResolutionStatus::Disputed);
+        }
+        if !market.get("closed").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return Ok(ResolutionStatus::Open);
+        }
+
+        let prices = string_list(&market, "outcomePrices");
+        let winner = string_list(&market, "outcomes")
+            .into_iter()
+            .zip(prices)
+            .find(|(_, price)| price.parse::<Decimal>().is_ok_and(|p| p == Decimal::ONE));
+        Ok(match winner {
+            Some((outcome, _)) => ResolutionStatus::Resolved { winning_outcome: outcome },
+            // Closed but not yet settled on-chain
+            None => ResolutionStatus::Open,
+        })
+    }
+
+    /// CTF condition id of a market, needed to redeem its tokens
+    pub async fn condition_id(&self, market_id: &str) -> Result<String> {
+        self.market(market_id)
+            .await?
+            .get("conditionId")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("No condition id for market {}", market_id))
+    }
+}
+
+/// Gamma encodes some arrays as JSON strings
+fn string_list(market: &serde_json::Value, key: &str) -> Vec<String> {
+    match market.get(key) {
+        Some(serde_json::Value::String(s)) => serde_json::from_str(s).unwrap_or_default(),
+        Some(value) => serde_json::from_value(value.clone()).unwrap_or_default(),
+        None => vec![],
+    }
+}
+
+impl Default for PolymarketBookSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl OrderBookSource for PolymarketBookSource {
+    async fn order_book(&self, market_id: &str, outcome: &str) -> Result<OrderBook> {
+        let token_id = self.token_id(market_id, outcome).await?;
+        let book: ClobBook = self
+            .client
+            .get(format!("{}/book", self.clob_url))
+            .query(&[("token_id", token_id.as_str())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(OrderBook {
+            outcome: outcome.to_string(),
+            bids: book.bids,
+            asks: book.asks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_order_book_resolves_clob_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/markets/123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "123",
+                "outcomes": "[\"Yes\", \"No\"]",
+                "clobTokenIds": "[\"111\", \"222\"]"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .and(query_param("token_id", "222"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "asset_id": "222",
+                "bids": [{ "price": "0.40", "size": "150" }],
+                "asks": [{ "price": "0.42", "size": "80" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let source = PolymarketBookSource::with_urls(&server.uri(), &server.uri());
+        let book = source.order_book("123", "no").await.unwrap();
+
+        assert_eq!(book.asks[0].price, rust_decimal::Decimal::new(42, 2));
+        assert_eq!(book.bids[0].size, rust_decimal::Decimal::from(150));
+    }
+
+    #[tokio::test]
+    async fn test_resolution_reads_winning_price() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/markets/123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "123",
+                "closed": true,
+                "outcomes": "[\"Yes\", \"No\"]",
+                "outcomePrices": "[\"0\", \"1\"]",
+                "umaResolutionStatus": "resolved"
+            })))
+            .mount(&server)
+            .await;
+
+        let source = PolymarketBookSource::with_urls(&server.uri(), &server.uri());
+        assert_eq!(
+            source.resolution("123").await.unwrap(),
+            ResolutionStatus::Resolved { winning_outcome: "No".to_string() }
+        );
+    }
+}
@@ -0,0 +1,725 @@
+use anyhow::Result;
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{broadcast, oneshot};
+use tokio::time::Duration;
+
+use crate::api::ordinals::OrdinalsMarketplace;
+use crate::api::polymarket::PolymarketBookSource;
+use crate::api::OrderBookSource;
+use crate::config::{Config, TradingConfig};
+use crate::models::ArbitrageOpportunity;
+use crate::trader::{Leg, TradeConfig};
+
+/// Signed webhook requests older than this are rejected as replays
+const WEBHOOK_MAX_AGE_SECS: i64 = 300;
+
+/// One leg as shown to the approver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegQuote {
+    pub leg: Leg,
+    pub market_id: String,
+    pub outcome: String,
+    /// Odds at detection
+    pub quoted: Decimal,
+    /// Worst price the order may fill at
+    pub limit: Decimal,
+    /// Most the leg can cost, `limit * size`
+    pub notional: Decimal,
+}
+
+/// A trade waiting for an operator's decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    /// The trade's idempotency key
+    pub id: String,
+    pub legs: Vec<LegQuote>,
+    pub size: Decimal,
+    /// Edge at the quoted odds, in USD
+    pub gross_edge: Decimal,
+    /// Estimated fees plus slippage allowance up to the limits, in USD
+    pub costs: Decimal,
+    pub net_edge: Decimal,
+    pub confidence: f64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ApprovalRequest {
+    pub fn new(
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+        fee_rate: Decimal,
+        timeout: Duration,
+    ) -> Self {
+        let size = config.size;
+        let legs: Vec<LegQuote> = [
+            (Leg::Polymarket, &opportunity.polymarket_odds),
+            (Leg::Btc, &opportunity.btc_market_odds),
+        ]
+        .into_iter()
+        .map(|(leg, odds)| {
+            let limit = config.limit_price(odds.odds);
+            LegQuote {
+                leg,
+                market_id: odds.market_id.clone(),
+                outcome: odds.outcome.clone(),
+                quoted: odds.odds,
+                limit,
+                notional: limit * size,
+            }
+        })
+        .collect();
+
+        let slippage: Decimal = legs.iter().map(|l| (l.limit - l.quoted) * size).sum();
+        let fees: Decimal = legs.iter().map(|l| l.notional * fee_rate).sum();
+        let gross_edge = opportunity.implied_profit * size;
+        let costs = slippage + fees;
+        let created_at = Utc::now();
+
+        Self {
+            id: config.idempotency_key.clone(),
+            legs,
+            size,
+            gross_edge,
+            costs,
+            net_edge: gross_edge - costs,
+            confidence: opportunity.confidence,
+            created_at,
+            expires_at: created_at + chrono::Duration::from_std(timeout).unwrap_or_default(),
+        }
+    }
+
+    /// Multi-line description for terminals and chat
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("Trade {} awaiting approval (size {})", self.id, self.size)];
+        for leg in &self.legs {
+            lines.push(format!(
+                "  {} {} {}: quoted {}, limit {}, up to ${}",
+                leg.leg,
+                leg.market_id,
+                leg.outcome,
+                leg.quoted,
+                leg.limit,
+                leg.notional.round_dp(2)
+            ));
+        }
+        lines.push(format!(
+            "  gross edge ${}, costs ${}, net edge ${}, confidence {:.2}",
+            self.gross_edge.round_dp(2),
+            self.costs.round_dp(2),
+            self.net_edge.round_dp(2),
+            self.confidence
+        ));
+        lines.push(format!(
+            "  expires {}; reply \"approve {}\" or \"reject {} <reason>\"",
+            self.expires_at.format("%H:%M:%S UTC"),
+            self.id,
+            self.id
+        ));
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ApprovalDecision {
+    Approved,
+    Rejected(String),
+    /// Timed out or the prices moved beyond tolerance
+    Expired(String),
+}
+
+impl std::fmt::Display for ApprovalDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Approved => write!(f, "approved"),
+            Self::Rejected(reason) => write!(f, "rejected: {}", reason),
+            Self::Expired(reason) => write!(f, "expired: {}", reason),
+        }
+    }
+}
+
+/// Re-reads order books to detect prices that moved past a request's limits
+pub struct PriceGuard {
+    books: HashMap<Leg, Box<dyn OrderBookSource>>,
+}
+
+impl PriceGuard {
+    pub fn new() -> Self {
+        Self { books: HashMap::new() }
+    }
+
+    pub fn with_source(mut self, leg: Leg, books: Box<dyn OrderBookSource>) -> Self {
+        self.books.insert(leg, books);
+        self
+    }
+
+    /// Books for the Polymarket leg and, when an ord server is configured, the Ordinals leg
+    pub fn from_config(config: &Config) -> Self {
+        let guard = Self::new().with_source(Leg::Polymarket, Box::new(PolymarketBookSource::new()));
+        let ordinals = config.bitcoin.ordinals.as_ref().filter(|_| config.bitcoin.protocol == "ordinals");
+        match ordinals.and_then(|o| o.endpoint.clone().zip(o.parent_inscription.clone())) {
+            Some((endpoint, parent)) => {
+                guard.with_source(Leg::Btc, Box::new(OrdinalsMarketplace::new(endpoint, parent)))
+            }
+            None => guard,
+        }
+    }
+
+    /// Why the request can no longer be filled within its limits, if it can't
+    pub async fn moved(&self, request: &ApprovalRequest) -> Result<Option<String>> {
+        for quote in &request.legs {
+            let Some(books) = self.books.get(&quote.leg) else {
+                continue;
+            };
+            let book = books.order_book(&quote.market_id, &quote.outcome).await?;
+            match book.asks.iter().map(|level| level.price).min() {
+                Some(ask) if ask > quote.limit => {
+                    return Ok(Some(format!("{} ask {} is above limit {}", quote.leg, ask, quote.limit)));
+                }
+                Some(_) => {}
+                None => return Ok(Some(format!("{} has no asks", quote.leg))),
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Default for PriceGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct PendingApproval {
+    request: ApprovalRequest,
+    respond: oneshot::Sender<ApprovalDecision>,
+}
+
+/// Trades waiting for human approval. `request` blocks until an operator
+/// decides through any channel, the request times out, or prices move past
+/// its limits.
+pub struct ApprovalQueue {
+    pending: Mutex<HashMap<String, PendingApproval>>,
+    announce: broadcast::Sender<ApprovalRequest>,
+    timeout: Duration,
+    recheck_interval: Duration,
+    fee_rate: Decimal,
+    guard: Option<PriceGuard>,
+}
+
+impl ApprovalQueue {
+    pub fn new(timeout: Duration, fee_rate: Decimal) -> Self {
+        let (announce, _) = broadcast::channel(64);
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            announce,
+            timeout,
+            recheck_interval: Duration::from_secs(5),
+            fee_rate,
+            guard: None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            Duration::from_secs(config.trading.approval_timeout_secs),
+            config.trading.estimated_fee_rate,
+        )
+        .with_price_guard(PriceGuard::from_config(config))
+    }
+
+    pub fn with_price_guard(mut self, guard: PriceGuard) -> Self {
+        self.guard = Some(guard);
+        self
+    }
+
+    pub fn with_recheck_interval(mut self, interval: Duration) -> Self {
+        self.recheck_interval = interval;
+        self
+    }
+
+    /// New requests, for channels that push them to an operator
+    pub fn subscribe(&self) -> broadcast::Receiver<ApprovalRequest> {
+        self.announce.subscribe()
+    }
+
+    pub fn pending(&self) -> Vec<ApprovalRequest> {
+        let pending = self.pending.lock().expect("approval queue lock poisoned");
+        let mut requests: Vec<_> = pending.values().map(|p| p.request.clone()).collect();
+        requests.sort_by_key(|r| r.created_at);
+        requests
+    }
+
+    /// Record an operator's decision on a pending request
+    pub fn decide(&self, id: &str, decision: ApprovalDecision) -> Result<()> {
+        let pending = self
+            .pending
+            .lock()
+            .expect("approval queue lock poisoned")
+            .remove(id)
+            .ok_or_else(|| anyhow::anyhow!("No pending approval {}", id))?;
+
+        tracing::info!("Trade {} {}", id, decision);
+        // The requester may have given up on it already
+        let _ = pending.respond.send(decision);
+        Ok(())
+    }
+
+    /// Queue a trade and wait for its decision
+    pub async fn request(&self, opportunity: &ArbitrageOpportunity, config: &TradeConfig) -> ApprovalDecision {
+        let request = ApprovalRequest::new(opportunity, config, self.fee_rate, self.timeout);
+        let id = request.id.clone();
+        let (respond, mut decision) = oneshot::channel();
+
+        self.pending
+            .lock()
+            .expect("approval queue lock poisoned")
+            .insert(id.clone(), PendingApproval { request: request.clone(), respond });
+        tracing::info!("{}", request.summary());
+        let _ = self.announce.send(request.clone());
+
+        let deadline = tokio::time::sleep(self.timeout);
+        tokio::pin!(deadline);
+        let mut recheck =
+            tokio::time::interval_at(tokio::time::Instant::now() + self.recheck_interval, self.recheck_interval);
+
+        let decision = loop {
+            tokio::select! {
+                decision = &mut decision => {
+                    break decision.unwrap_or_else(|_| ApprovalDecision::Rejected("approval dropped".to_string()));
+                }
+                _ = &mut deadline => {
+                    break ApprovalDecision::Expired(format!("no decision within {:?}", self.timeout));
+                }
+                _ = recheck.tick() => {
+                    if let Some(reason) = self.price_moved(&request, false).await {
+                        break ApprovalDecision::Expired(reason);
+                    }
+                }
+            }
+        };
+        self.pending.lock().expect("approval queue lock poisoned").remove(&id);
+
+        // Prices may have moved since the last check
+        let decision = match decision {
+            ApprovalDecision::Approved => match self.price_moved(&request, true).await {
+                Some(reason) => ApprovalDecision::Expired(reason),
+                None => ApprovalDecision::Approved,
+            },
+            other => other,
+        };
+        if decision != ApprovalDecision::Approved {
+            tracing::warn!("Trade {} {}", id, decision);
+        }
+        decision
+    }
+
+    /// Reason to expire the request. A failed book read only expires it when
+    /// `strict`, i.e. right before execution.
+    async fn price_moved(&self, request: &ApprovalRequest, strict: bool) -> Option<String> {
+        let guard = self.guard.as_ref()?;
+        match guard.moved(request).await {
+            Ok(reason) => reason,
+            Err(e) if strict => Some(format!("could not re-check prices: {}", e)),
+            Err(e) => {
+                tracing::warn!("Failed to re-check prices for {}: {}", request.id, e);
+                None
+            }
+        }
+    }
+}
+
+/// `approve <id>` or `reject <id> [reason]`, as typed in a terminal or chat
+pub fn parse_command(text: &str) -> Result<(String, ApprovalDecision)> {
+    let mut words = text.split_whitespace();
+    let verb = words.next().unwrap_or_default().to_lowercase();
+    let id = words
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Expected \"approve <id>\" or \"reject <id> [reason]\""))?
+        .to_string();
+    let reason = words.collect::<Vec<_>>().join(" ");
+
+    match verb.as_str() {
+        "approve" | "yes" => Ok((id, ApprovalDecision::Approved)),
+        "reject" | "no" => Ok((
+            id,
+            ApprovalDecision::Rejected(if reason.is_empty() { "rejected by operator".to_string() } else { reason }),
+        )),
+        other => Err(anyhow::anyhow!("Unknown approval command: {}", other)),
+    }
+}
+
+/// Print new requests to stdout and read decisions from stdin
+pub async fn run_terminal(queue: Arc<ApprovalQueue>) -> Result<()> {
+    let mut requests = queue.subscribe();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Ok(request) => println!("{}", request.summary()),
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    return Ok(());
+                };
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                if line == "list" {
+                    for request in queue.pending() {
+                        println!("{}", request.summary());
+                    }
+                    continue;
+                }
+                if let Err(e) = parse_command(line).and_then(|(id, decision)| queue.decide(&id, decision)) {
+                    println!("{}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Start the approval channels listed in `approval_channels`
+pub fn spawn_channels(queue: Arc<ApprovalQueue>, config: &TradingConfig) {
+    for channel in &config.approval_channels {
+        match channel.as_str() {
+            "terminal" => {
+                let queue = queue.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = run_terminal(queue).await {
+                        tracing::error!("Terminal approvals stopped: {}", e);
+                    }
+                });
+            }
+            "http" => {
+                let queue = queue.clone();
+                let addr = config.approval_listen_addr.clone();
+                let secret = config.approval_webhook_secret.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_http(queue, &addr, secret).await {
+                        tracing::error!("Approval endpoint stopped: {}", e);
+                    }
+                });
+            }
+            other => tracing::warn!("Unknown approval channel: {}", other),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct HttpState {
+    queue: Arc<ApprovalQueue>,
+    webhook_secret: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatCommand {
+    text: String,
+}
+
+/// Local approval API:
+/// `GET /approvals`, `POST /approvals/{id}/approve`, `POST /approvals/{id}/reject`
+/// (body: optional reason) and, with a secret, a signed chat-command webhook at
+/// `POST /approvals/webhook` taking `{"text": "approve <id>"}`. With a secret
+/// set, every `POST` must carry the webhook signature headers.
+pub fn router(queue: Arc<ApprovalQueue>, webhook_secret: Option<String>) -> Router {
+    let mut router = Router::new()
+        .route("/approvals", get(list_approvals))
+        .route("/approvals/:id/approve", post(approve))
+        .route("/approvals/:id/reject", post(reject));
+    if webhook_secret.as_deref().is_some_and(|s| !s.is_empty()) {
+        router = router.route("/approvals/webhook", post(chat_command));
+    }
+    router.with_state(HttpState { queue, webhook_secret })
+}
+
+pub async fn serve_http(queue: Arc<ApprovalQueue>, addr: &str, webhook_secret: Option<String>) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Approval endpoint listening on {}", listener.local_addr()?);
+    axum::serve(listener, router(queue, webhook_secret)).await?;
+    Ok(())
+}
+
+async fn list_approvals(State(state): State<HttpState>) -> Json<Vec<ApprovalRequest>> {
+    Json(state.queue.pending())
+}
+
+async fn approve(State(state): State<HttpState>, Path(id): Path<String>, headers: HeaderMap, body: Bytes) -> Response {
+    if let Some(denied) = unauthorized(&state, &headers, &body) {
+        return denied;
+    }
+    decide(&state.queue, &id, ApprovalDecision::Approved)
+}
+
+async fn reject(State(state): State<HttpState>, Path(id): Path<String>, headers: HeaderMap, body: Bytes) -> Response {
+    if let Some(denied) = unauthorized(&state, &headers, &body) {
+        return denied;
+    }
+    let reason = String::from_utf8_lossy(&body).trim().to_string();
+    let reason = if reason.is_empty() { "rejected by operator".to_string() } else { reason };
+    decide(&state.queue, &id, ApprovalDecision::Rejected(reason))
+}
+
+async fn chat_command(State(state): State<HttpState>, headers: HeaderMap, body: Bytes) -> Response {
+    if let Some(denied) = unauthorized(&state, &headers, &body) {
+        return denied;
+    }
+
+    let command = match serde_json::from_slice::<ChatCommand>(&body)
+        .map_err(anyhow::Error::from)
+        .and_then(|c| parse_command(&c.text))
+    {
+        Ok(command) => command,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+    decide(&state.queue, &command.0, command.1)
+}
+
+/// The 401 for a state-changing call that fails the signature check, when a
+/// webhook secret is set
+fn unauthorized(state: &HttpState, headers: &HeaderMap, body: &[u8]) -> Option<Response> {
+    let secret = state.webhook_secret.as_deref().filter(|s| !s.is_empty())?;
+    let e = verify_signature(secret, headers, body, Utc::now()).err()?;
+    tracing::warn!("Rejected unsigned approval call: {}", e);
+    Some((StatusCode::UNAUTHORIZED, e.to_string()).into_response())
+}
+
+fn decide(queue: &ApprovalQueue, id: &str, decision: ApprovalDecision) -> Response {
+    match queue.decide(id, decision.clone()) {
+        Ok(()) => Json(serde_json::json!({ "id": id, "decision": decision.to_string() })).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, e.to_string()).into_response(),
+    }
+}
+
+/// HMAC-SHA256 of `"{timestamp}.{body}"`, hex encoded
+pub fn sign_webhook(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Check the `X-Timestamp` and `X-Signature` headers of a webhook call
+fn verify_signature(secret: &str, headers: &HeaderMap, body: &[u8], now: DateTime<Utc>) -> Result<()> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| anyhow::anyhow!("Missing {} header", name))
+    };
+    let timestamp: i64 = header("X-Timestamp")?.parse()?;
+    if (now.timestamp() - timestamp).abs() > WEBHOOK_MAX_AGE_SECS {
+        return Err(anyhow::anyhow!("Stale webhook timestamp {}", timestamp));
+    }
+
+    let signature = hex::decode(header("X-Signature")?.trim_start_matches("sha256="))?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&signature)
+        .map_err(|_| anyhow::anyhow!("Bad webhook signature"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::polymarket::{OrderBook, PriceLevel};
+    use crate::models::{MarketOdds, MarketSource};
+    use crate::trader::TimeInForce;
+    use async_trait::async_trait;
+
+    struct FixedAsk(Decimal);
+
+    #[async_trait]
+    impl OrderBookSource for FixedAsk {
+        async fn order_book(&self, _market_id: &str, outcome: &str) -> Result<OrderBook> {
+            Ok(OrderBook {
+                outcome: outcome.to_string(),
+                bids: vec![],
+                asks: vec![PriceLevel { price: self.0, size: Decimal::from(1000) }],
+            })
+        }
+    }
+
+    fn opportunity() -> ArbitrageOpportunity {
+        let odds = |source, price| MarketOdds {
+            market_id: "m1".to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(price, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket, 45),
+            odds(MarketSource::BitcoinPredictionMarket, 50),
+            Decimal::new(5, 2),
+            0.9,
+        )
+    }
+
+    fn trade_config() -> TradeConfig {
+        TradeConfig {
+            size: Decimal::from(100),
+            max_size: Decimal::from(1000),
+            limit_tolerance: Decimal::new(2, 2),
+            time_in_force: TimeInForce::ImmediateOrCancel,
+            polymarket_timeout: Duration::from_secs(1),
+            btc_timeout: Duration::from_secs(1),
+            dry_run: false,
+            idempotency_key: "t1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_request_shows_edge_net_of_costs() {
+        let request = ApprovalRequest::new(&opportunity(), &trade_config(), Decimal::new(1, 2), Duration::from_secs(60));
+
+        // Limits 0.459 and 0.51: $0.90 + $1.00 slippage, fees 1% of $45.90 + $51
+        assert_eq!(request.gross_edge, Decimal::from(5));
+        assert_eq!(request.costs, Decimal::new(28690, 4));
+        assert_eq!(request.net_edge, Decimal::new(21310, 4));
+        assert!(request.summary().contains("approve t1"));
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("approve t1").unwrap(), ("t1".to_string(), ApprovalDecision::Approved));
+        assert_eq!(
+            parse_command("reject t1 too thin").unwrap(),
+            ("t1".to_string(), ApprovalDecision::Rejected("too thin".to_string()))
+        );
+        assert!(parse_command("approve").is_err());
+        assert!(parse_command("maybe t1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_approval_unblocks_request() {
+        let queue = Arc::new(
+            ApprovalQueue::new(Duration::from_secs(5), Decimal::ZERO)
+                .with_price_guard(PriceGuard::new().with_source(Leg::Btc, Box::new(FixedAsk(Decimal::new(50, 2))))),
+        );
+        let mut announced = queue.subscribe();
+
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.request(&opportunity(), &trade_config()).await })
+        };
+        let request = announced.recv().await.unwrap();
+        assert_eq!(queue.pending().len(), 1);
+
+        queue.decide(&request.id, ApprovalDecision::Approved).unwrap();
+        assert_eq!(waiter.await.unwrap(), ApprovalDecision::Approved);
+        assert!(queue.pending().is_empty());
+        assert!(queue.decide(&request.id, ApprovalDecision::Approved).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_request_expires_when_price_moves() {
+        // The BTC ask moved from 0.50 to 0.60, past the 0.51 limit
+        let queue = ApprovalQueue::new(Duration::from_secs(5), Decimal::ZERO)
+            .with_price_guard(PriceGuard::new().with_source(Leg::Btc, Box::new(FixedAsk(Decimal::new(60, 2)))))
+            .with_recheck_interval(Duration::from_millis(10));
+
+        match queue.request(&opportunity(), &trade_config()).await {
+            ApprovalDecision::Expired(reason) => assert!(reason.contains("above limit")),
+            other => panic!("expected expiry, got {:?}", other),
+        }
+        assert!(queue.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_signed_webhook_approves() {
+        let queue = Arc::new(ApprovalQueue::new(Duration::from_secs(5), Decimal::ZERO));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/approvals/webhook", listener.local_addr().unwrap());
+        let app = router(queue.clone(), Some("s3cret".to_string()));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.request(&opportunity(), &trade_config()).await })
+        };
+        while queue.pending().is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let body = br#"{"text": "approve t1"}"#.to_vec();
+        let now = Utc::now().timestamp();
+        let client = reqwest::Client::new();
+        let forged = client
+            .post(&url)
+            .header("X-Timestamp", now.to_string())
+            .header("X-Signature", sign_webhook("wrong", now, &body))
+            .body(body.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(forged.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let signed = client
+            .post(&url)
+            .header("X-Timestamp", now.to_string())
+            .header("X-Signature", sign_webhook("s3cret", now, &body))
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert!(signed.status().is_success());
+        assert_eq!(waiter.await.unwrap(), ApprovalDecision::Approved);
+    }
+
+    #[tokio::test]
+    async fn test_secret_requires_signed_approve_and_reject() {
+        let queue = Arc::new(ApprovalQueue::new(Duration::from_secs(5), Decimal::ZERO));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}/approvals/t1", listener.local_addr().unwrap());
+        let app = router(queue.clone(), Some("s3cret".to_string()));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.request(&opportunity(), &trade_config()).await })
+        };
+        while queue.pending().is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let client = reqwest::Client::new();
+        for action in ["approve", "reject"] {
+            let unsigned = client.post(format!("{}/{}", base, action)).send().await.unwrap();
+            assert_eq!(unsigned.status(), reqwest::StatusCode::UNAUTHORIZED);
+        }
+        assert_eq!(queue.pending().len(), 1);
+
+        let body = b"spread closed".to_vec();
+        let now = Utc::now().timestamp();
+        let signed = client
+            .post(format!("{}/reject", base))
+            .header("X-Timestamp", now.to_string())
+            .header("X-Signature", sign_webhook("s3cret", now, &body))
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert!(signed.status().is_success());
+        assert_eq!(waiter.await.unwrap(), ApprovalDecision::Rejected("spread closed".to_string()));
+    }
+}
@@ -0,0 +1,173 @@
+use sha3::{Digest, Keccak256};
+
+use super::address::EvmAddress;
+
+/// ABI argument for contract calls
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbiToken {
+    Address(EvmAddress),
+    Uint(u128),
+    Bool(bool),
+    FixedBytes32([u8; 32]),
+    Bytes(Vec<u8>),
+    String(String),
+    /// `uint256[]`
+    UintArray(Vec<u128>),
+}
+
+impl AbiToken {
+    fn is_dynamic(&self) -> bool {
+        matches!(self, Self::Bytes(_) | Self::String(_) | Self::UintArray(_))
+    }
+
+    fn head_word(&self) -> [u8; 32] {
+        match self {
+            Self::Address(address) => address.to_word(),
+            Self::Uint(value) => uint_word(*value),
+            Self::Bool(value) => uint_word(*value as u128),
+            Self::FixedBytes32(bytes) => *bytes,
+            Self::Bytes(_) | Self::String(_) | Self::UintArray(_) => unreachable!("dynamic tokens have no inline head"),
+        }
+    }
+
+    fn tail(&self) -> Vec<u8> {
+        let data: &[u8] = match self {
+            Self::Bytes(bytes) => bytes,
+            Self::String(s) => s.as_bytes(),
+            Self::UintArray(values) => {
+                let mut out = uint_word(values.len() as u128).to_vec();
+                out.extend(values.iter().flat_map(|v| uint_word(*v)));
+                return out;
+            }
+            _ => return Vec::new(),
+        };
+
+        let mut out = uint_word(data.len() as u128).to_vec();
+        out.extend_from_slice(data);
+        let padding = (32 - data.len() % 32) % 32;
+        out.extend(std::iter::repeat_n(0u8, padding));
+        out
+    }
+}
+
+/// 4-byte selector for a canonical signature like `transfer(address,uint256)`
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Encode a tuple of arguments with head/tail layout
+pub fn encode(tokens: &[AbiToken]) -> Vec<u8> {
+    let head_len = tokens.len() * 32;
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend_from_slice(&uint_word((head_len + tail.len()) as u128));
+            tail.extend(token.tail());
+        } else {
+            head.extend_from_slice(&token.head_word());
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+/// Calldata: selector followed by encoded arguments
+pub fn encode_call(signature: &str, tokens: &[AbiToken]) -> Vec<u8> {
+    let mut data = selector(signature).to_vec();
+    data.extend(encode(tokens));
+    data
+}
+
+/// Decode a single uint256 return value, failing if it exceeds u128
+pub fn decode_uint(data: &[u8]) -> Option<u128> {
+    if data.len() < 32 || data[..16].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&data[16..32]);
+    Some(u128::from_be_bytes(bytes))
+}
+
+/// uint256 word for a decimal string that may not fit in u128, such as a
+/// CTF position id. `None` if it isn't a decimal number below 2^256.
+pub fn uint256_from_decimal(value: &str) -> Option<[u8; 32]> {
+    if value.is_empty() {
+        return None;
+    }
+    let mut word = [0u8; 32];
+    for c in value.chars() {
+        let mut carry = c.to_digit(10)?;
+        for byte in word.iter_mut().rev() {
+            let v = *byte as u32 * 10 + carry;
+            *byte = (v & 0xff) as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(word)
+}
+
+fn uint_word(value: u128) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_selectors() {
+        assert_eq!(hex::encode(selector("transfer(address,uint256)")), "a9059cbb");
+        assert_eq!(hex::encode(selector("balanceOf(address)")), "70a08231");
+        assert_eq!(hex::encode(selector("approve(address,uint256)")), "095ea7b3");
+    }
+
+    #[test]
+    fn test_encode_static_and_dynamic() {
+        // Example from the Solidity ABI spec: f(uint,string) with (0x123, "Hello, world!")
+        let encoded = encode(&[
+            AbiToken::Uint(0x123),
+            AbiToken::String("Hello, world!".to_string()),
+        ]);
+        assert_eq!(encoded.len(), 32 * 4);
+        assert_eq!(decode_uint(&encoded[..32]), Some(0x123));
+        assert_eq!(decode_uint(&encoded[32..64]), Some(64));
+        assert_eq!(decode_uint(&encoded[64..96]), Some(13));
+        assert_eq!(&encoded[96..109], b"Hello, world!");
+        assert!(encoded[109..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_encode_uint_array() {
+        let encoded = encode(&[AbiToken::UintArray(vec![1, 2]), AbiToken::Uint(7)]);
+        assert_eq!(encoded.len(), 32 * 5);
+        assert_eq!(decode_uint(&encoded[..32]), Some(64));
+        assert_eq!(decode_uint(&encoded[64..96]), Some(2));
+        assert_eq!(decode_uint(&encoded[128..160]), Some(2));
+    }
+
+    #[test]
+    fn test_uint256_from_decimal() {
+        assert_eq!(uint256_from_decimal("291"), Some(uint_word(0x123)));
+        // 2^128 overflows u128 but not uint256
+        let word = uint256_from_decimal("340282366920938463463374607431768211456").unwrap();
+        assert_eq!(word[15], 1);
+        assert!(word.iter().enumerate().all(|(i, b)| i == 15 || *b == 0));
+        assert_eq!(uint256_from_decimal("12a"), None);
+        assert_eq!(uint256_from_decimal(""), None);
+    }
+
+    #[test]
+    fn test_decode_uint_overflow() {
+        assert_eq!(decode_uint(&[0xff; 32]), None);
+        assert_eq!(decode_uint(&[0u8; 8]), None);
+    }
+}
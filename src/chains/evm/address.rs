@@ -0,0 +1,104 @@
+use anyhow::{anyhow, Result};
+use sha3::{Digest, Keccak256};
+
+/// 20-byte EVM account address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EvmAddress(pub [u8; 20]);
+
+impl EvmAddress {
+    /// Parse a hex address. Mixed-case input must carry a valid checksum,
+    /// either EIP-55 or EIP-1191 for `checksum_chain_id`.
+    pub fn parse(s: &str, checksum_chain_id: Option<u64>) -> Result<Self> {
+        let hex_part = s.trim().trim_start_matches("0x").trim_start_matches("0X");
+        if hex_part.len() != 40 {
+            return Err(anyhow!("Invalid EVM address length: {}", s));
+        }
+
+        let bytes = hex::decode(hex_part).map_err(|e| anyhow!("Invalid EVM address {}: {}", s, e))?;
+        let mut raw = [0u8; 20];
+        raw.copy_from_slice(&bytes);
+        let address = Self(raw);
+
+        let is_mixed_case = hex_part.chars().any(|c| c.is_ascii_uppercase())
+            && hex_part.chars().any(|c| c.is_ascii_lowercase());
+
+        if is_mixed_case && address.to_checksum(checksum_chain_id)[2..] != *hex_part {
+            return Err(anyhow!("Invalid EVM address checksum: {}", s));
+        }
+
+        Ok(address)
+    }
+
+    /// Checksummed representation: EIP-55 when `chain_id` is `None`,
+    /// EIP-1191 (chain id mixed into the hash) otherwise
+    pub fn to_checksum(&self, chain_id: Option<u64>) -> String {
+        let lower = hex::encode(self.0);
+        let hash_input = match chain_id {
+            Some(id) => format!("{}0x{}", id, lower),
+            None => lower.clone(),
+        };
+        let hash = Keccak256::digest(hash_input.as_bytes());
+
+        let checksummed: String = lower
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+                if c.is_ascii_alphabetic() && nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+
+        format!("0x{}", checksummed)
+    }
+
+    /// Left-padded 32-byte ABI word
+    pub fn to_word(&self) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(&self.0);
+        word
+    }
+}
+
+impl std::fmt::Display for EvmAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eip55_checksum() {
+        let address = EvmAddress::parse("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", None).unwrap();
+        assert_eq!(
+            address.to_checksum(None),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        );
+        assert!(EvmAddress::parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", None).is_ok());
+        assert!(EvmAddress::parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD", None).is_err());
+    }
+
+    #[test]
+    fn test_eip1191_checksum() {
+        let address = EvmAddress::parse("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", None).unwrap();
+        let rsk = address.to_checksum(Some(30));
+        assert_eq!(rsk, "0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD");
+        assert_ne!(rsk, address.to_checksum(None));
+
+        // An RSK-checksummed address is only valid under the RSK chain id
+        assert!(EvmAddress::parse(&rsk, Some(30)).is_ok());
+        assert!(EvmAddress::parse(&rsk, None).is_err());
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        assert!(EvmAddress::parse("0x1234", None).is_err());
+        assert!(EvmAddress::parse("0xzz00000000000000000000000000000000000000", None).is_err());
+    }
+}
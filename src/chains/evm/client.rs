@@ -0,0 +1,299 @@
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::debug;
+
+use super::address::EvmAddress;
+
+/// Longest a transaction is polled for a receipt before giving up
+pub const RECEIPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Mined transaction receipt
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxReceipt {
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub gas_used: u128,
+    pub success: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawReceipt {
+    transaction_hash: String,
+    block_number: Option<String>,
+    gas_used: String,
+    status: Option<String>,
+}
+
+/// Ethereum JSON-RPC client shared by all EVM chains
+pub struct EvmRpcClient {
+    client: Client,
+    url: String,
+    next_id: AtomicU64,
+    poll_interval: Duration,
+    receipt_timeout: Duration,
+}
+
+impl EvmRpcClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.to_string(),
+            next_id: AtomicU64::new(1),
+            poll_interval: Duration::from_secs(5),
+            receipt_timeout: RECEIPT_TIMEOUT,
+        }
+    }
+
+    pub fn with_polling(mut self, poll_interval: Duration, receipt_timeout: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self.receipt_timeout = receipt_timeout;
+        self
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<Option<T>> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+
+        let response: RpcResponse<T> = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.error {
+            return Err(anyhow!("{} failed ({}): {}", method, error.code, error.message));
+        }
+
+        Ok(response.result)
+    }
+
+    async fn call_required<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T> {
+        self.call(method, params)
+            .await?
+            .ok_or_else(|| anyhow!("{} returned no result", method))
+    }
+
+    async fn call_quantity(&self, method: &str, params: serde_json::Value) -> Result<u128> {
+        let value: String = self.call_required(method, params).await?;
+        parse_quantity(&value)
+    }
+
+    pub async fn chain_id(&self) -> Result<u64> {
+        Ok(self.call_quantity("eth_chainId", serde_json::json!([])).await? as u64)
+    }
+
+    pub async fn get_transaction_count(&self, address: &EvmAddress) -> Result<u64> {
+        let params = serde_json::json!([address.to_string(), "pending"]);
+        Ok(self.call_quantity("eth_getTransactionCount", params).await? as u64)
+    }
+
+    pub async fn gas_price(&self) -> Result<u128> {
+        self.call_quantity("eth_gasPrice", serde_json::json!([])).await
+    }
+
+    pub async fn estimate_gas(
+        &self,
+        from: &EvmAddress,
+        to: &EvmAddress,
+        value: u128,
+        data: &[u8],
+    ) -> Result<u64> {
+        let params = serde_json::json!([{
+            "from": from.to_string(),
+            "to": to.to_string(),
+            "value": format!("0x{:x}", value),
+            "data": format!("0x{}", hex::encode(data)),
+        }]);
+        Ok(self.call_quantity("eth_estimateGas", params).await? as u64)
+    }
+
+    /// Native balance in wei
+    pub async fn get_balance(&self, address: &EvmAddress) -> Result<u128> {
+        let params = serde_json::json!([address.to_string(), "latest"]);
+        self.call_quantity("eth_getBalance", params).await
+    }
+
+    /// Read-only contract call
+    pub async fn eth_call(&self, to: &EvmAddress, data: &[u8]) -> Result<Vec<u8>> {
+        let params = serde_json::json!([
+            { "to": to.to_string(), "data": format!("0x{}", hex::encode(data)) },
+            "latest"
+        ]);
+        let result: String = self.call_required("eth_call", params).await?;
+        hex::decode(result.trim_start_matches("0x"))
+            .map_err(|e| anyhow!("Invalid eth_call result: {}", e))
+    }
+
+    pub async fn send_raw_transaction(&self, raw: &[u8]) -> Result<String> {
+        let params = serde_json::json!([format!("0x{}", hex::encode(raw))]);
+        self.call_required("eth_sendRawTransaction", params).await
+    }
+
+    pub async fn get_receipt(&self, tx_hash: &str) -> Result<Option<TxReceipt>> {
+        let raw: Option<RawReceipt> = self
+            .call("eth_getTransactionReceipt", serde_json::json!([tx_hash]))
+            .await?;
+
+        let Some(raw) = raw else { return Ok(None) };
+        let Some(block_number) = raw.block_number else { return Ok(None) };
+
+        Ok(Some(TxReceipt {
+            tx_hash: raw.transaction_hash,
+            block_number: parse_quantity(&block_number)? as u64,
+            gas_used: parse_quantity(&raw.gas_used)?,
+            // Pre-Byzantium receipts carry no status; treat them as successful
+            success: raw.status.map(|s| s == "0x1").unwrap_or(true),
+        }))
+    }
+
+    /// Poll for the receipt until it is mined or the timeout elapses
+    pub async fn wait_for_receipt(&self, tx_hash: &str) -> Result<Option<TxReceipt>> {
+        let deadline = Instant::now() + self.receipt_timeout;
+
+        loop {
+            if let Some(receipt) = self.get_receipt(tx_hash).await? {
+                return Ok(Some(receipt));
+            }
+
+            if Instant::now() + self.poll_interval > deadline {
+                return Ok(None);
+            }
+
+            debug!("Waiting for receipt of {}", tx_hash);
+            sleep(self.poll_interval).await;
+        }
+    }
+}
+
+/// Parse a JSON-RPC hex quantity such as `0x1a`
+pub fn parse_quantity(value: &str) -> Result<u128> {
+    let digits = value.trim_start_matches("0x");
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u128::from_str_radix(digits, 16).map_err(|e| anyhow!("Invalid hex quantity {}: {}", value, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_rpc(server: &MockServer, rpc_method: &str, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": rpc_method })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("0x0").unwrap(), 0);
+        assert_eq!(parse_quantity("0x1e").unwrap(), 30);
+        assert!(parse_quantity("0xzz").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_balance_and_chain_id() {
+        let server = MockServer::start().await;
+        mock_rpc(&server, "eth_chainId", serde_json::json!("0x1f")).await;
+        mock_rpc(&server, "eth_getBalance", serde_json::json!("0xde0b6b3a7640000")).await;
+
+        let client = EvmRpcClient::new(&server.uri());
+        assert_eq!(client.chain_id().await.unwrap(), 31);
+        assert_eq!(
+            client.get_balance(&EvmAddress([1; 20])).await.unwrap(),
+            1_000_000_000_000_000_000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_is_surfaced() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1,
+                "error": { "code": -32010, "message": "nonce too low" }
+            })))
+            .mount(&server)
+            .await;
+
+        let err = EvmRpcClient::new(&server.uri())
+            .send_raw_transaction(&[0xf8])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("nonce too low"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_receipt() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": "eth_getTransactionReceipt" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": null
+            })))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        mock_rpc(
+            &server,
+            "eth_getTransactionReceipt",
+            serde_json::json!({
+                "transactionHash": "0xabc",
+                "blockNumber": "0x10",
+                "gasUsed": "0x5208",
+                "status": "0x0"
+            }),
+        )
+        .await;
+
+        let receipt = EvmRpcClient::new(&server.uri())
+            .with_polling(Duration::from_millis(10), Duration::from_secs(2))
+            .wait_for_receipt("0xabc")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(receipt.block_number, 16);
+        assert_eq!(receipt.gas_used, 21_000);
+        assert!(!receipt.success);
+    }
+}
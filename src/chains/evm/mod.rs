@@ -0,0 +1,303 @@
+pub mod abi;
+pub mod address;
+pub mod client;
+pub mod rlp;
+pub mod transaction;
+
+use anyhow::{anyhow, Result};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use tracing::info;
+
+pub use abi::AbiToken;
+pub use address::EvmAddress;
+pub use client::{EvmRpcClient, TxReceipt};
+pub use transaction::{EvmSigner, LegacyTransaction};
+
+/// Chain parameters that differ between EVM networks
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvmChain {
+    pub name: &'static str,
+    pub chain_id: u64,
+    pub native_symbol: &'static str,
+    /// Whether addresses use the EIP-1191 chain-specific checksum
+    pub eip1191_checksum: bool,
+    /// Percentage added on top of `eth_estimateGas`
+    pub gas_limit_margin_pct: u64,
+}
+
+impl EvmChain {
+    pub fn polygon() -> Self {
+        Self {
+            name: "polygon",
+            chain_id: 137,
+            native_symbol: "POL",
+            eip1191_checksum: false,
+            gas_limit_margin_pct: 20,
+        }
+    }
+
+    pub fn polygon_amoy() -> Self {
+        Self {
+            name: "amoy",
+            chain_id: 80002,
+            ..Self::polygon()
+        }
+    }
+
+    pub fn rsk_mainnet() -> Self {
+        Self {
+            name: "rsk",
+            chain_id: 30,
+            native_symbol: "RBTC",
+            eip1191_checksum: true,
+            gas_limit_margin_pct: 10,
+        }
+    }
+
+    pub fn rsk_testnet() -> Self {
+        Self {
+            name: "rsk-testnet",
+            chain_id: 31,
+            ..Self::rsk_mainnet()
+        }
+    }
+
+    pub fn polygon_from_network(network: &str) -> Result<Self> {
+        match network {
+            "polygon" | "mainnet" => Ok(Self::polygon()),
+            "amoy" | "testnet" => Ok(Self::polygon_amoy()),
+            other => Err(anyhow!("Unknown Polygon network: {}", other)),
+        }
+    }
+
+    /// Chain id to mix into address checksums, if the chain uses EIP-1191
+    pub fn checksum_chain_id(&self) -> Option<u64> {
+        self.eip1191_checksum.then_some(self.chain_id)
+    }
+
+    pub fn parse_address(&self, s: &str) -> Result<EvmAddress> {
+        EvmAddress::parse(s, self.checksum_chain_id())
+    }
+
+    pub fn format_address(&self, address: &EvmAddress) -> String {
+        address.to_checksum(self.checksum_chain_id())
+    }
+}
+
+/// Signing wallet bound to one EVM chain, shared by the Polygon and RSK legs
+pub struct EvmWallet {
+    chain: EvmChain,
+    client: EvmRpcClient,
+    signer: EvmSigner,
+}
+
+impl EvmWallet {
+    pub fn new(chain: EvmChain, rpc_url: &str, private_key: &str) -> Result<Self> {
+        Ok(Self {
+            chain,
+            client: EvmRpcClient::new(rpc_url),
+            signer: EvmSigner::from_hex(private_key)?,
+        })
+    }
+
+    pub fn with_client(mut self, client: EvmRpcClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn chain(&self) -> &EvmChain {
+        &self.chain
+    }
+
+    pub fn address(&self) -> EvmAddress {
+        self.signer.address()
+    }
+
+    pub fn client(&self) -> &EvmRpcClient {
+        &self.client
+    }
+
+    /// Build, sign and broadcast a contract call, returning the tx hash.
+    /// Nonce, gas price and gas limit come from the node.
+    pub async fn send_call(&self, to: &EvmAddress, value: u128, data: Vec<u8>) -> Result<String> {
+        let node_chain_id = self.client.chain_id().await?;
+        if node_chain_id != self.chain.chain_id {
+            return Err(anyhow!(
+                "RPC node is on chain {} but wallet is configured for {} ({})",
+                node_chain_id,
+                self.chain.name,
+                self.chain.chain_id
+            ));
+        }
+
+        let from = self.address();
+        let nonce = self.client.get_transaction_count(&from).await?;
+        let gas_price = self.client.gas_price().await?;
+        let estimate = self.client.estimate_gas(&from, to, value, &data).await?;
+        let gas_limit = estimate + estimate * self.chain.gas_limit_margin_pct / 100;
+
+        let tx = LegacyTransaction {
+            nonce,
+            gas_price,
+            gas_limit,
+            to: Some(*to),
+            value,
+            data,
+            chain_id: self.chain.chain_id,
+        };
+
+        let tx_hash = self.client.send_raw_transaction(&tx.sign(&self.signer)?).await?;
+        info!(
+            "Sent {} tx {} from {} (nonce {}, gas {} @ {} wei)",
+            self.chain.name,
+            tx_hash,
+            self.chain.format_address(&from),
+            nonce,
+            gas_limit,
+            gas_price
+        );
+        Ok(tx_hash)
+    }
+
+    pub async fn wait_for_receipt(&self, tx_hash: &str) -> Result<Option<TxReceipt>> {
+        self.client.wait_for_receipt(tx_hash).await
+    }
+
+    /// Native balance in whole units (18 decimals)
+    pub async fn native_balance(&self) -> Result<Decimal> {
+        from_base_units(self.client.get_balance(&self.address()).await?, 18)
+    }
+
+    pub async fn erc20_balance(&self, token: &EvmAddress, decimals: u32) -> Result<Decimal> {
+        let data = abi::encode_call("balanceOf(address)", &[AbiToken::Address(self.address())]);
+        let result = self.client.eth_call(token, &data).await?;
+        let raw = abi::decode_uint(&result)
+            .ok_or_else(|| anyhow!("Unexpected balanceOf result: 0x{}", hex::encode(&result)))?;
+        from_base_units(raw, decimals)
+    }
+
+    /// ERC-1155 balance of token `id` (a uint256 word) held by the wallet
+    pub async fn erc1155_balance(&self, token: &EvmAddress, id: [u8; 32], decimals: u32) -> Result<Decimal> {
+        let data = abi::encode_call(
+            "balanceOf(address,uint256)",
+            &[AbiToken::Address(self.address()), AbiToken::FixedBytes32(id)],
+        );
+        let result = self.client.eth_call(token, &data).await?;
+        let raw = abi::decode_uint(&result)
+            .ok_or_else(|| anyhow!("Unexpected balanceOf result: 0x{}", hex::encode(&result)))?;
+        from_base_units(raw, decimals)
+    }
+
+    pub async fn erc20_approve(
+        &self,
+        token: &EvmAddress,
+        spender: &EvmAddress,
+        amount: Decimal,
+        decimals: u32,
+    ) -> Result<String> {
+        let data = abi::encode_call(
+            "approve(address,uint256)",
+            &[AbiToken::Address(*spender), AbiToken::Uint(to_base_units(amount, decimals)?)],
+        );
+        self.send_call(token, 0, data).await
+    }
+}
+
+pub fn to_base_units(amount: Decimal, decimals: u32) -> Result<u128> {
+    (amount * Decimal::from(10u64.pow(decimals)))
+        .trunc()
+        .to_u128()
+        .ok_or_else(|| anyhow!("Amount out of range: {}", amount))
+}
+
+pub fn from_base_units(raw: u128, decimals: u32) -> Result<Decimal> {
+    let raw = i128::try_from(raw).map_err(|_| anyhow!("Balance out of range"))?;
+    // Decimal holds 96-bit mantissas; reduce precision for very large balances
+    let mut value = raw;
+    let mut scale = decimals;
+    while value.unsigned_abs() >= (1u128 << 96) && scale > 0 {
+        value /= 10;
+        scale -= 1;
+    }
+    Decimal::try_from_i128_with_scale(value, scale).map_err(|e| anyhow!("Balance out of range: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_rpc(server: &MockServer, rpc_method: &str, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": rpc_method })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_base_unit_conversion() {
+        assert_eq!(to_base_units(Decimal::from_str("1.5").unwrap(), 6).unwrap(), 1_500_000);
+        assert_eq!(
+            from_base_units(1_500_000, 6).unwrap(),
+            Decimal::from_str("1.5").unwrap()
+        );
+        assert_eq!(
+            from_base_units(2_000_000_000_000_000_000, 18).unwrap(),
+            Decimal::from(2)
+        );
+    }
+
+    #[test]
+    fn test_chain_checksums() {
+        let rsk = EvmChain::rsk_mainnet();
+        let polygon = EvmChain::polygon();
+        let address = EvmAddress::parse("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", None).unwrap();
+        assert_ne!(rsk.format_address(&address), polygon.format_address(&address));
+        assert!(rsk.parse_address(&rsk.format_address(&address)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_send_call_rejects_wrong_chain() {
+        let server = MockServer::start().await;
+        mock_rpc(&server, "eth_chainId", serde_json::json!("0x89")).await;
+
+        let wallet = EvmWallet::new(EvmChain::rsk_mainnet(), &server.uri(), &"46".repeat(32)).unwrap();
+        let err = wallet.send_call(&EvmAddress([2; 20]), 0, vec![]).await.unwrap_err();
+        assert!(err.to_string().contains("chain 137"));
+    }
+
+    #[tokio::test]
+    async fn test_send_call_and_erc20_balance() {
+        let server = MockServer::start().await;
+        mock_rpc(&server, "eth_chainId", serde_json::json!("0x89")).await;
+        mock_rpc(&server, "eth_getTransactionCount", serde_json::json!("0x4")).await;
+        mock_rpc(&server, "eth_gasPrice", serde_json::json!("0x6fc23ac00")).await;
+        mock_rpc(&server, "eth_estimateGas", serde_json::json!("0xc350")).await;
+        mock_rpc(&server, "eth_sendRawTransaction", serde_json::json!("0xfeed")).await;
+        mock_rpc(
+            &server,
+            "eth_call",
+            serde_json::json!(format!("0x{:064x}", 12_340_000u64)),
+        )
+        .await;
+
+        let wallet = EvmWallet::new(EvmChain::polygon(), &server.uri(), &"46".repeat(32)).unwrap();
+        let token = EvmAddress([3; 20]);
+
+        let tx_hash = wallet
+            .erc20_approve(&token, &EvmAddress([4; 20]), Decimal::from(100), 6)
+            .await
+            .unwrap();
+        assert_eq!(tx_hash, "0xfeed");
+        assert_eq!(
+            wallet.erc20_balance(&token, 6).await.unwrap(),
+            Decimal::from_str("12.34").unwrap()
+        );
+    }
+}
@@ -0,0 +1,81 @@
+/// Minimal RLP encoder for transaction serialization
+#[derive(Debug, Clone, PartialEq)]
+pub enum RlpItem {
+    Bytes(Vec<u8>),
+    List(Vec<RlpItem>),
+}
+
+impl RlpItem {
+    /// Integers are encoded big-endian without leading zeros (zero is empty)
+    pub fn uint(value: u128) -> Self {
+        Self::Bytes(trim_leading_zeros(&value.to_be_bytes()).to_vec())
+    }
+
+    pub fn bytes(value: &[u8]) -> Self {
+        Self::Bytes(value.to_vec())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::Bytes(bytes) if bytes.len() == 1 && bytes[0] < 0x80 => bytes.clone(),
+            Self::Bytes(bytes) => {
+                let mut out = encode_length(bytes.len(), 0x80);
+                out.extend_from_slice(bytes);
+                out
+            }
+            Self::List(items) => {
+                let body: Vec<u8> = items.iter().flat_map(|item| item.encode()).collect();
+                let mut out = encode_length(body.len(), 0xc0);
+                out.extend(body);
+                out
+            }
+        }
+    }
+}
+
+fn encode_length(len: usize, offset: u8) -> Vec<u8> {
+    if len <= 55 {
+        vec![offset + len as u8]
+    } else {
+        let len_bytes = trim_leading_zeros(&(len as u64).to_be_bytes()).to_vec();
+        let mut out = vec![offset + 55 + len_bytes.len() as u8];
+        out.extend(len_bytes);
+        out
+    }
+}
+
+pub fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_scalars() {
+        assert_eq!(RlpItem::uint(0).encode(), vec![0x80]);
+        assert_eq!(RlpItem::uint(15).encode(), vec![0x0f]);
+        assert_eq!(RlpItem::uint(1024).encode(), vec![0x82, 0x04, 0x00]);
+        assert_eq!(RlpItem::bytes(b"dog").encode(), vec![0x83, b'd', b'o', b'g']);
+    }
+
+    #[test]
+    fn test_encode_lists() {
+        let list = RlpItem::List(vec![RlpItem::bytes(b"cat"), RlpItem::bytes(b"dog")]);
+        assert_eq!(
+            list.encode(),
+            vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g']
+        );
+        assert_eq!(RlpItem::List(vec![]).encode(), vec![0xc0]);
+    }
+
+    #[test]
+    fn test_encode_long_string() {
+        let data = vec![b'a'; 56];
+        let encoded = RlpItem::bytes(&data).encode();
+        assert_eq!(&encoded[..2], &[0xb8, 56]);
+        assert_eq!(encoded.len(), 58);
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::{anyhow, Result};
+use k256::ecdsa::SigningKey;
+use sha3::{Digest, Keccak256};
+
+use super::address::EvmAddress;
+use super::rlp::{trim_leading_zeros, RlpItem};
+
+/// secp256k1 key for signing EVM transactions on any chain
+pub struct EvmSigner {
+    key: SigningKey,
+}
+
+impl EvmSigner {
+    pub fn from_hex(private_key: &str) -> Result<Self> {
+        let bytes = hex::decode(private_key.trim().trim_start_matches("0x"))
+            .map_err(|e| anyhow!("Invalid EVM private key hex: {}", e))?;
+        let key = SigningKey::from_slice(&bytes)
+            .map_err(|e| anyhow!("Invalid EVM private key: {}", e))?;
+        Ok(Self { key })
+    }
+
+    pub fn address(&self) -> EvmAddress {
+        let point = self.key.verifying_key().to_encoded_point(false);
+        // Uncompressed point without the 0x04 prefix
+        let hash = Keccak256::digest(&point.as_bytes()[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+        EvmAddress(address)
+    }
+
+    fn sign_hash(&self, hash: &[u8]) -> Result<(u8, [u8; 32], [u8; 32])> {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(hash)
+            .map_err(|e| anyhow!("Failed to sign EVM transaction: {}", e))?;
+
+        let bytes = signature.to_bytes();
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&bytes[..32]);
+        s.copy_from_slice(&bytes[32..]);
+        Ok((recovery_id.to_byte(), r, s))
+    }
+}
+
+/// Legacy (type 0) transaction with EIP-155 replay protection.
+/// RSK has no EIP-1559 support, and Polygon accepts legacy transactions too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegacyTransaction {
+    pub nonce: u64,
+    pub gas_price: u128,
+    pub gas_limit: u64,
+    pub to: Option<EvmAddress>,
+    pub value: u128,
+    pub data: Vec<u8>,
+    pub chain_id: u64,
+}
+
+impl LegacyTransaction {
+    fn base_fields(&self) -> Vec<RlpItem> {
+        vec![
+            RlpItem::uint(self.nonce as u128),
+            RlpItem::uint(self.gas_price),
+            RlpItem::uint(self.gas_limit as u128),
+            match &self.to {
+                Some(to) => RlpItem::bytes(&to.0),
+                None => RlpItem::bytes(&[]),
+            },
+            RlpItem::uint(self.value),
+            RlpItem::bytes(&self.data),
+        ]
+    }
+
+    /// Hash committed to by the signature: keccak(rlp([..., chain_id, 0, 0]))
+    pub fn signing_hash(&self) -> [u8; 32] {
+        let mut fields = self.base_fields();
+        fields.push(RlpItem::uint(self.chain_id as u128));
+        fields.push(RlpItem::uint(0));
+        fields.push(RlpItem::uint(0));
+        keccak(&RlpItem::List(fields).encode())
+    }
+
+    /// Sign and return the raw transaction bytes ready for `eth_sendRawTransaction`
+    pub fn sign(&self, signer: &EvmSigner) -> Result<Vec<u8>> {
+        let (recovery_id, r, s) = signer.sign_hash(&self.signing_hash())?;
+        let v = recovery_id as u128 + self.chain_id as u128 * 2 + 35;
+
+        let mut fields = self.base_fields();
+        fields.push(RlpItem::uint(v));
+        fields.push(RlpItem::bytes(trim_leading_zeros(&r)));
+        fields.push(RlpItem::bytes(trim_leading_zeros(&s)));
+        Ok(RlpItem::List(fields).encode())
+    }
+}
+
+pub fn keccak(data: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&Keccak256::digest(data));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example transaction from EIP-155
+    fn eip155_example() -> LegacyTransaction {
+        LegacyTransaction {
+            nonce: 9,
+            gas_price: 20_000_000_000,
+            gas_limit: 21_000,
+            to: Some(EvmAddress([0x35; 20])),
+            value: 1_000_000_000_000_000_000,
+            data: vec![],
+            chain_id: 1,
+        }
+    }
+
+    #[test]
+    fn test_eip155_signing_hash() {
+        assert_eq!(
+            hex::encode(eip155_example().signing_hash()),
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+    }
+
+    #[test]
+    fn test_eip155_signed_transaction() {
+        let signer = EvmSigner::from_hex(&"46".repeat(32)).unwrap();
+        let raw = eip155_example().sign(&signer).unwrap();
+        assert_eq!(
+            hex::encode(raw),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+    }
+
+    #[test]
+    fn test_signer_address() {
+        let signer = EvmSigner::from_hex(&"46".repeat(32)).unwrap();
+        assert_eq!(
+            signer.address().to_string(),
+            "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"
+        );
+    }
+}
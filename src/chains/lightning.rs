@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use reqwest::{Certificate, Client, RequestBuilder};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::chains::liquid::OutcomeTokenHolding;
+use crate::config::LightningConfig;
+use crate::resolver::ResolutionStatus;
+
+#[derive(Debug, Deserialize)]
+struct MarketStatus {
+    status: String,
+    #[serde(default)]
+    winning_outcome: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClaimResponse {
+    payment_hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketPosition {
+    market_id: String,
+    outcome: String,
+    shares: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndAmount {
+    sat: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndChannelBalance {
+    local_balance: LndAmount,
+}
+
+/// LND node, reached over its REST API
+pub struct LndClient {
+    client: Client,
+    base_url: String,
+    /// Hex-encoded macaroon sent with each request
+    macaroon: Option<String>,
+}
+
+impl LndClient {
+    pub fn new(base_url: &str, macaroon: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            macaroon,
+        }
+    }
+
+    /// Trusts the node's TLS certificate and reads its macaroon from the
+    /// configured paths. An endpoint without a scheme is taken as https.
+    pub fn from_config(config: &LightningConfig) -> Result<Self> {
+        let endpoint = config
+            .endpoint
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("LND endpoint not configured"))?;
+        let base_url = if endpoint.contains("://") { endpoint.clone() } else { format!("https://{}", endpoint) };
+
+        let mut client = Client::builder();
+        if let Some(path) = &config.cert_path {
+            let pem = std::fs::read(path).with_context(|| format!("Failed to read LND certificate {}", path))?;
+            client = client.add_root_certificate(Certificate::from_pem(&pem)?);
+        }
+        let macaroon = config
+            .macaroon_path
+            .as_ref()
+            .map(|path| std::fs::read(path).with_context(|| format!("Failed to read LND macaroon {}", path)))
+            .transpose()?
+            .map(hex::encode);
+
+        Ok(Self {
+            client: client.build()?,
+            macaroon,
+            ..Self::new(&base_url, None)
+        })
+    }
+
+    /// Sats the node can spend from its channels
+    pub async fn channel_balance(&self) -> Result<Decimal> {
+        let mut request = self.client.get(format!("{}/v1/balance/channels", self.base_url));
+        if let Some(macaroon) = &self.macaroon {
+            request = request.header("Grpc-Metadata-macaroon", macaroon);
+        }
+        let balance: LndChannelBalance = request.send().await?.error_for_status()?.json().await?;
+        Ok(balance.local_balance.sat)
+    }
+}
+
+/// Lightning prediction market (Predyx). Winning shares are claimed from
+/// the market, which pays them out over Lightning to the account.
+pub struct LightningMarket {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl LightningMarket {
+    pub fn new(base_url: &str, api_key: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+
+    pub fn from_config(config: &LightningConfig) -> Self {
+        Self::new(&config.predyx_url, config.predyx_api_key.clone())
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.header("X-API-Key", key),
+            None => request,
+        }
+    }
+
+    pub async fn resolution(&self, market_id: &str) -> Result<ResolutionStatus> {
+        let market: MarketStatus = self
+            .authorized(self.client.get(format!("{}/markets/{}", self.base_url, market_id)))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(match (market.status.as_str(), market.winning_outcome) {
+            ("resolved", Some(outcome)) => ResolutionStatus::Resolved { winning_outcome: outcome },
+            ("disputed", _) => ResolutionStatus::Disputed,
+            _ => ResolutionStatus::Open,
+        })
+    }
+
+    /// Shares the account holds, as reported by the market
+    pub async fn positions(&self) -> Result<Vec<OutcomeTokenHolding>> {
+        let positions: Vec<MarketPosition> = self
+            .authorized(self.client.get(format!("{}/positions", self.base_url)))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(positions
+            .into_iter()
+            .filter(|p| p.shares > Decimal::ZERO)
+            .map(|p| OutcomeTokenHolding {
+                asset_id: format!("predyx:{}:{}", p.market_id, p.outcome),
+                market_id: p.market_id,
+                outcome: p.outcome,
+                quantity: p.shares,
+            })
+            .collect())
+    }
+
+    /// Claim the payout of a resolved market, returning its payment hash
+    pub async fn claim_payout(&self, market_id: &str) -> Result<String> {
+        let claim: ClaimResponse = self
+            .authorized(self.client.post(format!("{}/markets/{}/claim", self.base_url, market_id)))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(claim.payment_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_resolution_and_claim() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/markets/m1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "resolved",
+                "winning_outcome": "Yes"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/markets/m1/claim"))
+            .and(header("X-API-Key", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "payment_hash": "ab12" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let market = LightningMarket::new(&server.uri(), Some("key".to_string()));
+        assert_eq!(
+            market.resolution("m1").await.unwrap(),
+            ResolutionStatus::Resolved { winning_outcome: "Yes".to_string() }
+        );
+        assert_eq!(market.claim_payout("m1").await.unwrap(), "ab12");
+    }
+
+    #[tokio::test]
+    async fn test_lnd_channel_balance() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/balance/channels"))
+            .and(header("Grpc-Metadata-macaroon", "0201"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "balance": "150000",
+                "local_balance": { "sat": "150000", "msat": "150000000" },
+                "remote_balance": { "sat": "50000", "msat": "50000000" }
+            })))
+            .mount(&server)
+            .await;
+
+        let lnd = LndClient::new(&server.uri(), Some("0201".to_string()));
+        assert_eq!(lnd.channel_balance().await.unwrap(), Decimal::from(150_000));
+    }
+
+    #[tokio::test]
+    async fn test_positions_skip_empty() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/positions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "market_id": "m1", "outcome": "No", "shares": 250 },
+                { "market_id": "m2", "outcome": "Yes", "shares": 0 }
+            ])))
+            .mount(&server)
+            .await;
+
+        let holdings = LightningMarket::new(&server.uri(), None).positions().await.unwrap();
+        assert_eq!(holdings.len(), 1);
+        assert_eq!((holdings[0].market_id.as_str(), holdings[0].quantity), ("m1", Decimal::from(250)));
+    }
+}
@@ -0,0 +1,448 @@
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info};
+
+use crate::config::LiquidConfig;
+
+/// Policy asset (L-BTC) on Liquid mainnet
+pub const LBTC_ASSET_ID: &str = "6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d";
+
+/// Longest a broadcast is polled for confirmations before returning
+pub const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Elements reports balances of the policy asset under this label
+const POLICY_ASSET_LABEL: &str = "bitcoin";
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FundedTransaction {
+    hex: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignedTransaction {
+    hex: String,
+    complete: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddressInfo {
+    #[serde(default)]
+    confidential_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WalletTransaction {
+    confirmations: i64,
+}
+
+/// Balance of one Liquid asset held by the wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetBalance {
+    pub asset_id: String,
+    pub amount: Decimal,
+}
+
+/// Outcome token issued on Liquid, resolved to the market it belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutcomeTokenHolding {
+    pub asset_id: String,
+    pub market_id: String,
+    pub outcome: String,
+    pub quantity: Decimal,
+}
+
+/// JSON-RPC client for an Elements node wallet
+pub struct ElementsRpcClient {
+    client: Client,
+    url: String,
+    user: Option<String>,
+    password: Option<String>,
+    poll_interval: Duration,
+    confirmation_timeout: Duration,
+}
+
+impl ElementsRpcClient {
+    pub fn new(url: &str, user: Option<String>, password: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.trim_end_matches('/').to_string(),
+            user,
+            password,
+            poll_interval: Duration::from_secs(15),
+            // One-minute blocks; allow plenty of room for the required depth
+            confirmation_timeout: CONFIRMATION_TIMEOUT,
+        }
+    }
+
+    pub fn with_polling(mut self, poll_interval: Duration, confirmation_timeout: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self.confirmation_timeout = confirmation_timeout;
+        self
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T> {
+        let body = serde_json::json!({
+            "jsonrpc": "1.0",
+            "id": "polyarb",
+            "method": method,
+            "params": params,
+        });
+
+        let mut request = self.client.post(&self.url).json(&body);
+        if let Some(user) = &self.user {
+            request = request.basic_auth(user, self.password.as_ref());
+        }
+
+        // bitcoind-style nodes return HTTP 500 with a JSON error body
+        let response: RpcResponse<T> = request.send().await?.json().await?;
+
+        if let Some(error) = response.error {
+            return Err(anyhow!("{} failed ({}): {}", method, error.code, error.message));
+        }
+
+        response
+            .result
+            .ok_or_else(|| anyhow!("{} returned no result", method))
+    }
+
+    /// Trusted balances per asset id (the policy asset is reported as L-BTC)
+    pub async fn get_balances(&self) -> Result<Vec<AssetBalance>> {
+        let raw: HashMap<String, f64> = self.call("getbalance", serde_json::json!([])).await?;
+
+        let mut balances: Vec<AssetBalance> = raw
+            .into_iter()
+            .map(|(asset, amount)| AssetBalance {
+                asset_id: if asset == POLICY_ASSET_LABEL {
+                    LBTC_ASSET_ID.to_string()
+                } else {
+                    asset
+                },
+                amount: from_btc_f64(amount),
+            })
+            .collect();
+
+        balances.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+        Ok(balances)
+    }
+
+    pub async fn is_confidential_address(&self, address: &str) -> Result<bool> {
+        let info: AddressInfo = self
+            .call("getaddressinfo", serde_json::json!([address]))
+            .await?;
+        Ok(info.confidential_key.is_some_and(|k| !k.is_empty()))
+    }
+
+    /// Build, fund, blind, sign and broadcast a confidential payment of
+    /// `amount` units of `asset_id` to a confidential address.
+    pub async fn send_confidential(&self, address: &str, asset_id: &str, amount: Decimal) -> Result<String> {
+        if !self.is_confidential_address(address).await? {
+            return Err(anyhow!("Refusing to pay unconfidential Liquid address {}", address));
+        }
+
+        let amount = amount
+            .round_dp(8)
+            .to_f64()
+            .ok_or_else(|| anyhow!("Invalid Liquid amount: {}", amount))?;
+
+        let raw: String = self
+            .call(
+                "createrawtransaction",
+                serde_json::json!([[], [{ address: amount, "asset": asset_id }]]),
+            )
+            .await?;
+
+        let funded: FundedTransaction = self.call("fundrawtransaction", serde_json::json!([raw])).await?;
+        let blinded: String = self.call("blindrawtransaction", serde_json::json!([funded.hex])).await?;
+        let signed: SignedTransaction = self
+            .call("signrawtransactionwithwallet", serde_json::json!([blinded]))
+            .await?;
+
+        if !signed.complete {
+            return Err(anyhow!("Elements wallet could not fully sign the transaction"));
+        }
+
+        let txid: String = self.call("sendrawtransaction", serde_json::json!([signed.hex])).await?;
+        info!("Broadcast Liquid tx {} paying {} of {} to {}", txid, amount, asset_id, address);
+        Ok(txid)
+    }
+
+    pub async fn get_confirmations(&self, txid: &str) -> Result<u64> {
+        let tx: WalletTransaction = self.call("gettransaction", serde_json::json!([txid])).await?;
+        // Negative confirmations mean the transaction conflicts with the chain
+        if tx.confirmations < 0 {
+            return Err(anyhow!("Liquid tx {} conflicted ({} confirmations)", txid, tx.confirmations));
+        }
+        Ok(tx.confirmations as u64)
+    }
+
+    /// Poll until `target` confirmations are reached; returns the last count seen
+    pub async fn wait_for_confirmations(&self, txid: &str, target: u64) -> Result<u64> {
+        let deadline = Instant::now() + self.confirmation_timeout;
+
+        loop {
+            let confirmations = self.get_confirmations(txid).await?;
+            if confirmations >= target || Instant::now() + self.poll_interval > deadline {
+                return Ok(confirmations);
+            }
+
+            debug!("Liquid tx {} has {}/{} confirmations", txid, confirmations, target);
+            sleep(self.poll_interval).await;
+        }
+    }
+}
+
+/// Liquid prediction market settled by paying collateral to the market's
+/// confidential settlement address, which issues outcome tokens in return
+pub struct LiquidMarket {
+    client: ElementsRpcClient,
+    settlement_address: String,
+    collateral_asset: String,
+    outcome_assets: Vec<crate::config::LiquidOutcomeAsset>,
+    min_confirmations: u64,
+}
+
+impl LiquidMarket {
+    pub fn from_config(config: &LiquidConfig) -> Result<Self> {
+        let rpc_url = config
+            .rpc_url
+            .as_ref()
+            .ok_or_else(|| anyhow!("Liquid RPC URL not configured"))?;
+        let url = match &config.liquid_wallet {
+            Some(wallet) => format!("{}/wallet/{}", rpc_url.trim_end_matches('/'), wallet),
+            None => rpc_url.clone(),
+        };
+
+        Ok(Self {
+            client: ElementsRpcClient::new(
+                &url,
+                config.liquid_rpc_user.clone(),
+                config.liquid_rpc_password.clone(),
+            ),
+            settlement_address: config
+                .settlement_address
+                .clone()
+                .ok_or_else(|| anyhow!("Liquid settlement address not configured"))?,
+            collateral_asset: config
+                .collateral_asset
+                .clone()
+                .ok_or_else(|| anyhow!("Liquid collateral asset not configured"))?,
+            outcome_assets: config.outcome_assets.clone(),
+            min_confirmations: config.min_confirmations,
+        })
+    }
+
+    pub fn with_client(mut self, client: ElementsRpcClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Pay `amount` of the collateral asset (not shares) for a position and
+    /// wait for the configured depth. Returns the txid and the confirmations reached.
+    pub async fn settle(&self, amount: Decimal) -> Result<(String, u64)> {
+        let txid = self
+            .client
+            .send_confidential(&self.settlement_address, &self.collateral_asset, amount)
+            .await?;
+        let confirmations = self
+            .client
+            .wait_for_confirmations(&txid, self.min_confirmations)
+            .await?;
+        Ok((txid, confirmations))
+    }
+
+    pub fn min_confirmations(&self) -> u64 {
+        self.min_confirmations
+    }
+
+    pub async fn balances(&self) -> Result<Vec<AssetBalance>> {
+        self.client.get_balances().await
+    }
+
+    pub async fn lbtc_balance(&self) -> Result<Decimal> {
+        Ok(self
+            .balances()
+            .await?
+            .into_iter()
+            .find(|b| b.asset_id == LBTC_ASSET_ID)
+            .map(|b| b.amount)
+            .unwrap_or(Decimal::ZERO))
+    }
+
+    /// Held balances of configured outcome tokens
+    pub async fn outcome_holdings(&self) -> Result<Vec<OutcomeTokenHolding>> {
+        let balances = self.balances().await?;
+
+        Ok(self
+            .outcome_assets
+            .iter()
+            .filter_map(|asset| {
+                balances
+                    .iter()
+                    .find(|b| b.asset_id == asset.asset_id && b.amount > Decimal::ZERO)
+                    .map(|b| OutcomeTokenHolding {
+                        asset_id: asset.asset_id.clone(),
+                        market_id: asset.market_id.clone(),
+                        outcome: asset.outcome.clone(),
+                        quantity: b.amount,
+                    })
+            })
+            .collect())
+    }
+}
+
+fn from_btc_f64(amount: f64) -> Decimal {
+    Decimal::try_from(amount).unwrap_or(Decimal::ZERO).round_dp(8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LiquidOutcomeAsset;
+    use std::str::FromStr;
+    use wiremock::matchers::{body_partial_json, header_exists, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const OUTCOME_ASSET: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+    fn test_config(url: &str) -> LiquidConfig {
+        LiquidConfig {
+            rpc_url: Some(url.to_string()),
+            private_key: None,
+            liquid_rpc_user: Some("user".to_string()),
+            liquid_rpc_password: Some("pass".to_string()),
+            liquid_wallet: None,
+            settlement_address: Some("lq1qqsettlement".to_string()),
+            collateral_asset: Some(LBTC_ASSET_ID.to_string()),
+            outcome_assets: vec![LiquidOutcomeAsset {
+                asset_id: OUTCOME_ASSET.to_string(),
+                market_id: "btc-100k".to_string(),
+                outcome: "Yes".to_string(),
+            }],
+            min_confirmations: 2,
+        }
+    }
+
+    async fn mock_rpc(server: &MockServer, rpc_method: &str, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(header_exists("authorization"))
+            .and(body_partial_json(serde_json::json!({ "method": rpc_method })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "result": result, "error": null, "id": "polyarb"
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn fast_market(server: &MockServer) -> LiquidMarket {
+        LiquidMarket::from_config(&test_config(&server.uri()))
+            .unwrap()
+            .with_client(
+                ElementsRpcClient::new(&server.uri(), Some("user".into()), Some("pass".into()))
+                    .with_polling(Duration::from_millis(10), Duration::from_secs(2)),
+            )
+    }
+
+    #[tokio::test]
+    async fn test_balances_and_outcome_holdings() {
+        let server = MockServer::start().await;
+        mock_rpc(
+            &server,
+            "getbalance",
+            serde_json::json!({ "bitcoin": 0.015, OUTCOME_ASSET: 250.0 }),
+        )
+        .await;
+
+        let market = fast_market(&server);
+        assert_eq!(market.lbtc_balance().await.unwrap(), Decimal::from_str("0.015").unwrap());
+
+        let holdings = market.outcome_holdings().await.unwrap();
+        assert_eq!(holdings.len(), 1);
+        assert_eq!(holdings[0].market_id, "btc-100k");
+        assert_eq!(holdings[0].quantity, Decimal::from(250));
+    }
+
+    #[tokio::test]
+    async fn test_settle_blinds_signs_and_waits() {
+        let server = MockServer::start().await;
+        mock_rpc(&server, "getaddressinfo", serde_json::json!({ "confidential_key": "02ab" })).await;
+        mock_rpc(&server, "createrawtransaction", serde_json::json!("raw")).await;
+        mock_rpc(&server, "fundrawtransaction", serde_json::json!({ "hex": "funded", "fee": 0.0000025 })).await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "method": "blindrawtransaction", "params": ["funded"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "result": "blinded", "error": null, "id": "polyarb"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        mock_rpc(
+            &server,
+            "signrawtransactionwithwallet",
+            serde_json::json!({ "hex": "signed", "complete": true }),
+        )
+        .await;
+        mock_rpc(&server, "sendrawtransaction", serde_json::json!("liquidtxid")).await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": "gettransaction" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "result": { "confirmations": 0 }, "error": null, "id": "polyarb"
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        mock_rpc(&server, "gettransaction", serde_json::json!({ "confirmations": 2 })).await;
+
+        let (txid, confirmations) = fast_market(&server)
+            .settle(Decimal::from_str("0.001").unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(txid, "liquidtxid");
+        assert_eq!(confirmations, 2);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_unconfidential_address() {
+        let server = MockServer::start().await;
+        mock_rpc(&server, "getaddressinfo", serde_json::json!({ "confidential_key": "" })).await;
+
+        let err = fast_market(&server).settle(Decimal::ONE).await.unwrap_err();
+        assert!(err.to_string().contains("unconfidential"));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_is_surfaced() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500).set_body_json(serde_json::json!({
+                "result": null,
+                "error": { "code": -6, "message": "Insufficient funds" },
+                "id": "polyarb"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = fast_market(&server).balances().await.unwrap_err();
+        assert!(err.to_string().contains("Insufficient funds"));
+    }
+}
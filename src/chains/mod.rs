@@ -0,0 +1,6 @@
+pub mod evm;
+pub mod lightning;
+pub mod liquid;
+pub mod ordinals;
+pub mod rsk;
+pub mod stacks;
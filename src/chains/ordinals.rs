@@ -0,0 +1,617 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use bitcoin::psbt::{Input as PsbtInput, Psbt, PsbtSighashType};
+use bitcoin::sighash::{EcdsaSighashType, TapSighashType};
+use bitcoin::transaction::{Transaction, TxIn, TxOut};
+use bitcoin::{Address, Amount, Network, OutPoint, ScriptBuf, Sequence, Witness};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::str::FromStr;
+use tracing::info;
+
+use crate::api::ordinals::{OrdClient, OutcomeOffer};
+use crate::config::OrdinalsConfig;
+
+/// Outputs below this are non-standard
+const DUST_SATS: u64 = 546;
+
+// Conservative per-item vsize estimates used for fee calculation
+const TX_OVERHEAD_VB: u64 = 11;
+const INPUT_VB: u64 = 68;
+const OUTPUT_VB: u64 = 43;
+
+/// Spendable output owned by the wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utxo {
+    pub outpoint: OutPoint,
+    pub value: u64,
+}
+
+/// Signs PSBT inputs outside this process (hardware wallet, HSM, `ord wallet` …)
+#[async_trait]
+pub trait PsbtSigner: Send + Sync {
+    /// Sign and finalize the listed inputs, returning the updated PSBT
+    async fn sign(&self, psbt: Psbt, inputs: &[usize]) -> Result<Psbt>;
+}
+
+/// Signer reached over HTTP: `POST {"psbt", "sign_inputs", "finalize"}` → `{"psbt"}`
+pub struct HttpPsbtSigner {
+    client: Client,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignerResponse {
+    psbt: String,
+}
+
+impl HttpPsbtSigner {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl PsbtSigner for HttpPsbtSigner {
+    async fn sign(&self, psbt: Psbt, inputs: &[usize]) -> Result<Psbt> {
+        let body = serde_json::json!({
+            "psbt": psbt.to_string(),
+            "sign_inputs": inputs,
+            "finalize": true,
+        });
+
+        let response: SignerResponse = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Psbt::from_str(&response.psbt).map_err(|e| anyhow!("Signer returned invalid PSBT: {}", e))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraUtxo {
+    txid: String,
+    vout: u32,
+    value: u64,
+}
+
+/// Esplora-compatible API used for UTXO lookup and broadcast
+pub struct EsploraClient {
+    client: Client,
+    base_url: String,
+}
+
+impl EsploraClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub async fn get_utxos(&self, address: &Address) -> Result<Vec<Utxo>> {
+        let utxos: Vec<EsploraUtxo> = self
+            .client
+            .get(format!("{}/address/{}/utxo", self.base_url, address))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        utxos
+            .into_iter()
+            .map(|u| {
+                Ok(Utxo {
+                    outpoint: OutPoint::from_str(&format!("{}:{}", u.txid, u.vout))?,
+                    value: u.value,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn broadcast(&self, tx: &Transaction) -> Result<String> {
+        let response = self
+            .client
+            .post(format!("{}/tx", self.base_url))
+            .body(bitcoin::consensus::encode::serialize_hex(tx))
+            .send()
+            .await?;
+
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(anyhow!("Broadcast rejected ({}): {}", status, text));
+        }
+        Ok(text.trim().to_string())
+    }
+}
+
+fn estimate_fee(fee_rate: u64, inputs: usize, outputs: usize) -> u64 {
+    fee_rate * (TX_OVERHEAD_VB + INPUT_VB * inputs as u64 + OUTPUT_VB * outputs as u64)
+}
+
+/// Validate a seller offer: one signed input holding the inscription paired
+/// with one output paying the seller
+fn check_offer(offer: &Psbt) -> Result<()> {
+    if offer.unsigned_tx.input.len() != 1 || offer.unsigned_tx.output.len() != 1 {
+        return Err(anyhow!("Offer PSBT must have exactly one input and one output"));
+    }
+
+    let input = &offer.inputs[0];
+    if input.witness_utxo.is_none() {
+        return Err(anyhow!("Offer PSBT input is missing its witness UTXO"));
+    }
+
+    let signed = input.final_script_witness.is_some()
+        || input.final_script_sig.is_some()
+        || input.tap_key_sig.is_some()
+        || !input.partial_sigs.is_empty();
+    if !signed {
+        return Err(anyhow!("Offer PSBT input is not signed by the seller"));
+    }
+
+    Ok(())
+}
+
+/// Build the PSBT that buys an offered inscription.
+///
+/// Layout (ordinal theory assigns sats first-in-first-out):
+/// - inputs:  `[padding, seller inscription, payment...]`
+/// - outputs: `[buyer (padding + postage), seller payment, change]`
+///
+/// The padding input pushes the inscribed sat into output 0, and the seller's
+/// `SINGLE|ANYONECANPAY` input stays paired with its payment at index 1.
+pub fn build_buy_psbt(
+    offer: &Psbt,
+    padding: &Utxo,
+    payments: &[Utxo],
+    buyer_script: &ScriptBuf,
+    fee_rate: u64,
+) -> Result<Psbt> {
+    check_offer(offer)?;
+
+    let seller_in = offer.unsigned_tx.input[0].clone();
+    let seller_out = offer.unsigned_tx.output[0].clone();
+    let postage = offer.inputs[0]
+        .witness_utxo
+        .as_ref()
+        .map(|u| u.value.to_sat())
+        .unwrap_or_default();
+
+    let payment_total: u64 = payments.iter().map(|u| u.value).sum();
+    let price = seller_out.value.to_sat();
+
+    let mut outputs = vec![
+        TxOut {
+            value: Amount::from_sat(padding.value + postage),
+            script_pubkey: buyer_script.clone(),
+        },
+        seller_out,
+    ];
+
+    let input_count = 2 + payments.len();
+    let fee_with_change = estimate_fee(fee_rate, input_count, 3);
+    let fee_without_change = estimate_fee(fee_rate, input_count, 2);
+
+    if payment_total >= price + fee_with_change + DUST_SATS {
+        outputs.push(TxOut {
+            value: Amount::from_sat(payment_total - price - fee_with_change),
+            script_pubkey: buyer_script.clone(),
+        });
+    } else if payment_total < price + fee_without_change {
+        return Err(anyhow!(
+            "Insufficient funds: need {} sats, have {}",
+            price + fee_without_change,
+            payment_total
+        ));
+    }
+
+    let mut inputs = vec![
+        TxIn {
+            previous_output: padding.outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        },
+        seller_in,
+    ];
+    inputs.extend(payments.iter().map(|u| TxIn {
+        previous_output: u.outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+    }));
+
+    // Version and lock time are committed to by the seller's signature
+    let tx = Transaction {
+        version: offer.unsigned_tx.version,
+        lock_time: offer.unsigned_tx.lock_time,
+        input: inputs,
+        output: outputs,
+    };
+
+    let mut psbt = Psbt::from_unsigned_tx(tx)?;
+    let wallet_input = |value: u64| PsbtInput {
+        witness_utxo: Some(TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: buyer_script.clone(),
+        }),
+        ..Default::default()
+    };
+
+    psbt.inputs[0] = wallet_input(padding.value);
+    psbt.inputs[1] = offer.inputs[0].clone();
+    for (i, utxo) in payments.iter().enumerate() {
+        psbt.inputs[2 + i] = wallet_input(utxo.value);
+    }
+
+    Ok(psbt)
+}
+
+/// Build a sell offer for an inscription: its UTXO paired with a payment to
+/// the seller, to be signed `SIGHASH_SINGLE|ANYONECANPAY`
+pub fn build_sell_psbt(inscription_utxo: &Utxo, seller_script: &ScriptBuf, price_sats: u64) -> Result<Psbt> {
+    if price_sats < DUST_SATS {
+        return Err(anyhow!("Price {} sats is below dust", price_sats));
+    }
+
+    let tx = Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: bitcoin::absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: inscription_utxo.outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(price_sats),
+            script_pubkey: seller_script.clone(),
+        }],
+    };
+
+    let sighash_type = if seller_script.is_p2tr() {
+        PsbtSighashType::from(TapSighashType::SinglePlusAnyoneCanPay)
+    } else {
+        PsbtSighashType::from(EcdsaSighashType::SinglePlusAnyoneCanPay)
+    };
+
+    let mut psbt = Psbt::from_unsigned_tx(tx)?;
+    psbt.inputs[0] = PsbtInput {
+        witness_utxo: Some(TxOut {
+            value: Amount::from_sat(inscription_utxo.value),
+            script_pubkey: seller_script.clone(),
+        }),
+        sighash_type: Some(sighash_type),
+        ..Default::default()
+    };
+
+    Ok(psbt)
+}
+
+/// Buys and sells outcome inscriptions from the configured wallet
+pub struct OrdinalsTrader {
+    ord: OrdClient,
+    esplora: EsploraClient,
+    signer: Box<dyn PsbtSigner>,
+    address: Address,
+    fee_rate: u64,
+    offer_url: Option<String>,
+    client: Client,
+}
+
+impl OrdinalsTrader {
+    pub fn from_config(config: &OrdinalsConfig) -> Result<Self> {
+        let network = Network::from_str(&config.network)
+            .map_err(|e| anyhow!("Invalid Bitcoin network {}: {}", config.network, e))?;
+        let address = config
+            .address
+            .as_ref()
+            .ok_or_else(|| anyhow!("Ordinals wallet address not configured"))?;
+        let address = Address::from_str(address)?.require_network(network)?;
+        let endpoint = config
+            .endpoint
+            .as_ref()
+            .ok_or_else(|| anyhow!("ord server endpoint not configured"))?;
+        let signer_url = config
+            .signer_url
+            .as_ref()
+            .ok_or_else(|| anyhow!("Ordinals PSBT signer not configured"))?;
+
+        Ok(Self {
+            ord: OrdClient::new(endpoint),
+            esplora: EsploraClient::new(&config.esplora_url),
+            signer: Box::new(HttpPsbtSigner::new(signer_url)),
+            address,
+            fee_rate: config.fee_rate,
+            offer_url: config.offer_url.clone(),
+            client: Client::new(),
+        })
+    }
+
+    pub fn with_signer(mut self, signer: Box<dyn PsbtSigner>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// Wallet UTXOs that carry no inscriptions, smallest first
+    async fn clean_utxos(&self) -> Result<Vec<Utxo>> {
+        let mut clean = Vec::new();
+        for utxo in self.esplora.get_utxos(&self.address).await? {
+            let output = self.ord.get_output(&utxo.outpoint.to_string()).await?;
+            if output.inscriptions.is_empty() {
+                clean.push(utxo);
+            }
+        }
+        clean.sort_by_key(|u| u.value);
+        Ok(clean)
+    }
+
+    /// Spendable BTC: the wallet's outputs that carry no inscriptions
+    pub async fn balance(&self) -> Result<Decimal> {
+        let sats: u64 = self.clean_utxos().await?.iter().map(|u| u.value).sum();
+        Ok(Decimal::from(sats) / Decimal::from(100_000_000))
+    }
+
+    /// Buy an offered outcome inscription. Returns the broadcast txid.
+    pub async fn buy(&self, offer: &OutcomeOffer) -> Result<String> {
+        let offer_psbt = Psbt::from_str(&offer.psbt)
+            .map_err(|e| anyhow!("Invalid offer PSBT for {}: {}", offer.inscription_id, e))?;
+        check_offer(&offer_psbt)?;
+
+        // The offer must spend the output that holds the inscription at offset 0
+        let inscription = self.ord.get_inscription(&offer.inscription_id).await?;
+        let offered_outpoint = offer_psbt.unsigned_tx.input[0].previous_output.to_string();
+        if inscription.outpoint()? != offered_outpoint || !inscription.satpoint.ends_with(":0") {
+            return Err(anyhow!(
+                "Offer for {} does not spend the inscription at {}",
+                offer.inscription_id,
+                inscription.satpoint
+            ));
+        }
+
+        let mut utxos = self.clean_utxos().await?;
+        if utxos.is_empty() {
+            return Err(anyhow!("No spendable UTXOs for {}", self.address));
+        }
+        let padding = utxos.remove(0);
+
+        // Largest first until the price and fee are covered
+        let price = offer.price_sats;
+        let mut payments = Vec::new();
+        let mut total = 0;
+        for utxo in utxos.into_iter().rev() {
+            if total >= price + estimate_fee(self.fee_rate, 2 + payments.len(), 3) {
+                break;
+            }
+            total += utxo.value;
+            payments.push(utxo);
+        }
+
+        let script = self.address.script_pubkey();
+        let psbt = build_buy_psbt(&offer_psbt, &padding, &payments, &script, self.fee_rate)?;
+
+        let wallet_inputs: Vec<usize> = (0..psbt.inputs.len()).filter(|i| *i != 1).collect();
+        let signed = self.signer.sign(psbt, &wallet_inputs).await?;
+        let tx = signed
+            .extract_tx()
+            .map_err(|e| anyhow!("Could not extract signed transaction: {}", e))?;
+
+        let txid = self.esplora.broadcast(&tx).await?;
+        info!("Bought inscription {} for {} sats in {}", offer.inscription_id, price, txid);
+        Ok(txid)
+    }
+
+    /// Create and sign a sell offer for an inscription we hold, publishing it
+    /// to the marketplace if an offer endpoint is configured.
+    /// Returns the signed offer PSBT (base64).
+    pub async fn sell(&self, inscription_id: &str, price_sats: u64) -> Result<String> {
+        let inscription = self.ord.get_inscription(inscription_id).await?;
+        let outpoint = OutPoint::from_str(&inscription.outpoint()?)?;
+        let output = self.ord.get_output(&outpoint.to_string()).await?;
+
+        if output.address.as_deref() != Some(self.address.to_string().as_str()) {
+            return Err(anyhow!("Inscription {} is not held by {}", inscription_id, self.address));
+        }
+
+        let utxo = Utxo { outpoint, value: output.value };
+        let psbt = build_sell_psbt(&utxo, &self.address.script_pubkey(), price_sats)?;
+        let signed = self.signer.sign(psbt, &[0]).await?;
+        let encoded = signed.to_string();
+
+        if let Some(url) = &self.offer_url {
+            self.client
+                .post(url)
+                .json(&serde_json::json!({
+                    "inscription_id": inscription_id,
+                    "price_sats": price_sats,
+                    "psbt": encoded,
+                }))
+                .send()
+                .await?
+                .error_for_status()?;
+            info!("Published sell offer for {} at {} sats", inscription_id, price_sats);
+        }
+
+        Ok(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash;
+    use bitcoin::Txid;
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn outpoint(n: u8) -> OutPoint {
+        OutPoint {
+            txid: Txid::from_byte_array([n; 32]),
+            vout: 0,
+        }
+    }
+
+    fn script(n: u8) -> ScriptBuf {
+        // P2WPKH with a recognisable program
+        let mut bytes = vec![0x00, 0x14];
+        bytes.extend_from_slice(&[n; 20]);
+        ScriptBuf::from_bytes(bytes)
+    }
+
+    fn signed_offer(price: u64) -> Psbt {
+        let mut offer = build_sell_psbt(&Utxo { outpoint: outpoint(9), value: 546 }, &script(9), price).unwrap();
+        offer.inputs[0].final_script_witness = Some(Witness::from_slice(&[vec![1u8; 71], vec![2u8; 33]]));
+        offer
+    }
+
+    /// Test signer that finalizes the requested inputs with dummy witnesses
+    struct RecordingSigner {
+        signed: Arc<Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl PsbtSigner for RecordingSigner {
+        async fn sign(&self, mut psbt: Psbt, inputs: &[usize]) -> Result<Psbt> {
+            for i in inputs {
+                psbt.inputs[*i].final_script_witness =
+                    Some(Witness::from_slice(&[vec![3u8; 71], vec![4u8; 33]]));
+            }
+            self.signed.lock().unwrap().extend_from_slice(inputs);
+            Ok(psbt)
+        }
+    }
+
+    #[test]
+    fn test_buy_psbt_layout_moves_inscription_to_buyer() {
+        let offer = signed_offer(60_000);
+        let padding = Utxo { outpoint: outpoint(1), value: 600 };
+        let payments = vec![Utxo { outpoint: outpoint(2), value: 100_000 }];
+
+        let psbt = build_buy_psbt(&offer, &padding, &payments, &script(1), 5).unwrap();
+        let tx = &psbt.unsigned_tx;
+
+        assert_eq!(tx.input[1].previous_output, outpoint(9));
+        assert_eq!(tx.output[1].value.to_sat(), 60_000);
+        assert_eq!(tx.output[1].script_pubkey, script(9));
+
+        // Inscribed sat sits at offset 600 of the inputs, inside output 0
+        assert_eq!(tx.output[0].value.to_sat(), 600 + 546);
+        assert_eq!(tx.output[0].script_pubkey, script(1));
+
+        let fee = estimate_fee(5, 3, 3);
+        assert_eq!(tx.output[2].value.to_sat(), 100_000 - 60_000 - fee);
+        assert!(psbt.inputs[1].final_script_witness.is_some());
+    }
+
+    #[test]
+    fn test_buy_psbt_insufficient_funds() {
+        let offer = signed_offer(60_000);
+        let padding = Utxo { outpoint: outpoint(1), value: 600 };
+        let payments = vec![Utxo { outpoint: outpoint(2), value: 50_000 }];
+        assert!(build_buy_psbt(&offer, &padding, &payments, &script(1), 5).is_err());
+    }
+
+    #[test]
+    fn test_unsigned_offer_rejected() {
+        let offer = build_sell_psbt(&Utxo { outpoint: outpoint(9), value: 546 }, &script(9), 60_000).unwrap();
+        let padding = Utxo { outpoint: outpoint(1), value: 600 };
+        assert!(build_buy_psbt(&offer, &padding, &[], &script(1), 5).is_err());
+    }
+
+    #[test]
+    fn test_sell_psbt_uses_single_anyonecanpay() {
+        let psbt = build_sell_psbt(&Utxo { outpoint: outpoint(9), value: 546 }, &script(9), 75_000).unwrap();
+        assert_eq!(
+            psbt.inputs[0].sighash_type,
+            Some(PsbtSighashType::from(EcdsaSighashType::SinglePlusAnyoneCanPay))
+        );
+        assert_eq!(psbt.unsigned_tx.output[0].value.to_sat(), 75_000);
+    }
+
+    #[tokio::test]
+    async fn test_buy_signs_wallet_inputs_and_broadcasts() {
+        let server = MockServer::start().await;
+        let address = Address::from_script(&script(1), Network::Regtest).unwrap().to_string();
+        let offer = signed_offer(60_000);
+        let seller_outpoint = offer.unsigned_tx.input[0].previous_output;
+
+        Mock::given(method("GET"))
+            .and(path("/inscription/yesi0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "yesi0", "number": 5, "satpoint": format!("{}:0", seller_outpoint)
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/address/{}/utxo", address)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "txid": "11".repeat(32), "vout": 0, "value": 600 },
+                { "txid": "22".repeat(32), "vout": 1, "value": 90000 },
+                { "txid": "33".repeat(32), "vout": 0, "value": 10000 }
+            ])))
+            .mount(&server)
+            .await;
+        for (txid, vout, inscriptions) in [
+            ("11".repeat(32), 0, vec![]),
+            ("22".repeat(32), 1, vec![]),
+            ("33".repeat(32), 0, vec!["otheri0"]),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!("/output/{}:{}", txid, vout)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "value": 0, "inscriptions": inscriptions
+                })))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/tx"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("buytxid"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = OrdinalsConfig {
+            address: Some(address.clone()),
+            endpoint: Some(server.uri()),
+            parent_inscription: Some("parenti0".to_string()),
+            esplora_url: server.uri(),
+            signer_url: Some(format!("{}/sign", server.uri())),
+            offer_url: None,
+            network: "regtest".to_string(),
+            fee_rate: 2,
+        };
+        let signed = Arc::new(Mutex::new(vec![]));
+        let trader = OrdinalsTrader::from_config(&config)
+            .unwrap()
+            .with_signer(Box::new(RecordingSigner { signed: signed.clone() }));
+        assert_eq!(trader.balance().await.unwrap(), Decimal::new(90_600, 8));
+
+        let txid = trader
+            .buy(&OutcomeOffer {
+                outcome: "Yes".to_string(),
+                inscription_id: "yesi0".to_string(),
+                price_sats: 60_000,
+                psbt: offer.to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(txid, "buytxid");
+        // Padding (0) and payment (2) are ours; the seller's input (1) is not
+        assert_eq!(*signed.lock().unwrap(), vec![0, 2]);
+    }
+}
@@ -0,0 +1,218 @@
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+
+use crate::chains::evm::{self, abi, AbiToken, EvmAddress, EvmChain, EvmWallet, TxReceipt};
+use crate::config::RskConfig;
+
+/// Prediction-market contract on RSK, collateralized by an ERC-20 token
+/// (e.g. DOC) and called as
+/// `buyOutcome(string marketId, string outcome, uint256 amount, uint256 maxPrice)`.
+///
+/// `amount` is in collateral base units and `maxPrice` is scaled by 10^18.
+/// Gas is paid in RBTC.
+pub struct RskMarket {
+    wallet: EvmWallet,
+    contract: EvmAddress,
+    collateral: EvmAddress,
+    collateral_decimals: u32,
+    function_signature: String,
+}
+
+/// Outcome of a market buy: the buy tx hash and its receipt, if mined in time
+#[derive(Debug, Clone)]
+pub struct RskTradeOutcome {
+    pub tx_hash: String,
+    pub receipt: Option<TxReceipt>,
+}
+
+impl RskMarket {
+    pub fn from_config(config: &RskConfig) -> Result<Self> {
+        let chain = match config.rsk_network.as_str() {
+            "mainnet" => EvmChain::rsk_mainnet(),
+            "testnet" => EvmChain::rsk_testnet(),
+            other => return Err(anyhow!("Unknown RSK network: {}", other)),
+        };
+
+        let rpc_url = config
+            .rpc_url
+            .as_ref()
+            .ok_or_else(|| anyhow!("RSK RPC URL not configured"))?;
+        let private_key = config
+            .private_key
+            .as_ref()
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| anyhow!("RSK private key not configured"))?;
+        let contract = config
+            .market_contract
+            .as_ref()
+            .ok_or_else(|| anyhow!("RSK market contract not configured"))?;
+        let collateral = config
+            .collateral_token
+            .as_ref()
+            .ok_or_else(|| anyhow!("RSK collateral token not configured"))?;
+
+        Ok(Self {
+            contract: chain.parse_address(contract)?,
+            collateral: chain.parse_address(collateral)?,
+            collateral_decimals: config.collateral_decimals,
+            function_signature: config.market_function.clone(),
+            wallet: EvmWallet::new(chain, rpc_url, private_key)?,
+        })
+    }
+
+    pub fn with_wallet(mut self, wallet: EvmWallet) -> Self {
+        self.wallet = wallet;
+        self
+    }
+
+    pub fn wallet(&self) -> &EvmWallet {
+        &self.wallet
+    }
+
+    pub fn buy_calldata(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        amount: Decimal,
+        max_price: Decimal,
+    ) -> Result<Vec<u8>> {
+        Ok(abi::encode_call(
+            &self.function_signature,
+            &[
+                AbiToken::String(market_id.to_string()),
+                AbiToken::String(outcome.to_string()),
+                AbiToken::Uint(evm::to_base_units(amount, self.collateral_decimals)?),
+                AbiToken::Uint(evm::to_base_units(max_price, 18)?),
+            ],
+        ))
+    }
+
+    /// Approve the collateral, then buy the outcome and wait for its receipt
+    pub async fn buy_outcome(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        amount: Decimal,
+        max_price: Decimal,
+    ) -> Result<RskTradeOutcome> {
+        let approve_hash = self
+            .wallet
+            .erc20_approve(&self.collateral, &self.contract, amount, self.collateral_decimals)
+            .await?;
+
+        match self.wallet.wait_for_receipt(&approve_hash).await? {
+            Some(receipt) if receipt.success => {}
+            Some(_) => return Err(anyhow!("RSK collateral approval {} reverted", approve_hash)),
+            None => return Err(anyhow!("RSK collateral approval {} not mined", approve_hash)),
+        }
+
+        let data = self.buy_calldata(market_id, outcome, amount, max_price)?;
+        let tx_hash = self.wallet.send_call(&self.contract, 0, data).await?;
+        let receipt = self.wallet.wait_for_receipt(&tx_hash).await?;
+
+        Ok(RskTradeOutcome { tx_hash, receipt })
+    }
+
+    /// Gas balance in RBTC
+    pub async fn rbtc_balance(&self) -> Result<Decimal> {
+        self.wallet.native_balance().await
+    }
+
+    pub async fn collateral_balance(&self) -> Result<Decimal> {
+        self.wallet
+            .erc20_balance(&self.collateral, self.collateral_decimals)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chains::evm::EvmRpcClient;
+    use std::str::FromStr;
+    use tokio::time::Duration;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_config(rpc_url: &str) -> RskConfig {
+        RskConfig {
+            rpc_url: Some(rpc_url.to_string()),
+            private_key: Some("46".repeat(32)),
+            rsk_network: "testnet".to_string(),
+            market_contract: Some("0x0000000000000000000000000000000000000abc".to_string()),
+            collateral_token: Some("0x0000000000000000000000000000000000000def".to_string()),
+            collateral_decimals: 18,
+            market_function: "buyOutcome(string,string,uint256,uint256)".to_string(),
+        }
+    }
+
+    async fn mock_rpc(server: &MockServer, rpc_method: &str, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": rpc_method })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_buy_calldata() {
+        let market = RskMarket::from_config(&test_config("http://localhost:4444")).unwrap();
+        let data = market
+            .buy_calldata("btc-100k", "Yes", Decimal::from(10), Decimal::from_str("0.5").unwrap())
+            .unwrap();
+
+        assert_eq!(&data[..4], &abi::selector("buyOutcome(string,string,uint256,uint256)"));
+        assert_eq!(abi::decode_uint(&data[4 + 64..4 + 96]), Some(10 * 10u128.pow(18)));
+        assert_eq!(abi::decode_uint(&data[4 + 96..4 + 128]), Some(5 * 10u128.pow(17)));
+    }
+
+    #[test]
+    fn test_missing_contract_is_error() {
+        let mut config = test_config("http://localhost:4444");
+        config.market_contract = None;
+        assert!(RskMarket::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_buy_outcome_against_mock_node() {
+        let server = MockServer::start().await;
+        mock_rpc(&server, "eth_chainId", serde_json::json!("0x1f")).await;
+        mock_rpc(&server, "eth_getTransactionCount", serde_json::json!("0x0")).await;
+        mock_rpc(&server, "eth_gasPrice", serde_json::json!("0x3938700")).await;
+        mock_rpc(&server, "eth_estimateGas", serde_json::json!("0x186a0")).await;
+        mock_rpc(&server, "eth_sendRawTransaction", serde_json::json!("0xaaa")).await;
+        mock_rpc(
+            &server,
+            "eth_getTransactionReceipt",
+            serde_json::json!({
+                "transactionHash": "0xaaa",
+                "blockNumber": "0x64",
+                "gasUsed": "0x186a0",
+                "status": "0x1"
+            }),
+        )
+        .await;
+        mock_rpc(&server, "eth_getBalance", serde_json::json!("0x2386f26fc10000")).await;
+
+        let config = test_config(&server.uri());
+        let market = RskMarket::from_config(&config).unwrap();
+        let wallet = EvmWallet::new(EvmChain::rsk_testnet(), &server.uri(), &"46".repeat(32))
+            .unwrap()
+            .with_client(
+                EvmRpcClient::new(&server.uri())
+                    .with_polling(Duration::from_millis(10), Duration::from_secs(2)),
+            );
+        let market = market.with_wallet(wallet);
+
+        let outcome = market
+            .buy_outcome("btc-100k", "Yes", Decimal::from(5), Decimal::from_str("0.4").unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.tx_hash, "0xaaa");
+        assert!(outcome.receipt.unwrap().success);
+        assert_eq!(market.rbtc_balance().await.unwrap(), Decimal::from_str("0.01").unwrap());
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+
+const C32_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Address version bytes for single-sig (P2PKH) principals
+pub const MAINNET_SINGLESIG: u8 = 22;
+pub const TESTNET_SINGLESIG: u8 = 26;
+
+/// A standard Stacks principal: version byte plus hash160 of the public key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StacksAddress {
+    pub version: u8,
+    pub hash160: [u8; 20],
+}
+
+impl StacksAddress {
+    pub fn new(version: u8, hash160: [u8; 20]) -> Self {
+        Self { version, hash160 }
+    }
+
+    /// Parse a c32check-encoded address such as `SP000000000000000000002Q6VF78`
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim().to_uppercase();
+        let mut chars = s.chars();
+
+        if chars.next() != Some('S') {
+            return Err(anyhow!("Stacks address must start with 'S': {}", s));
+        }
+
+        let version_char = chars
+            .next()
+            .ok_or_else(|| anyhow!("Stacks address too short: {}", s))?;
+        let version = c32_digit(version_char)
+            .ok_or_else(|| anyhow!("Invalid Stacks address version: {}", version_char))?;
+
+        let decoded = c32_decode(chars.as_str())?;
+        if decoded.len() != 24 {
+            return Err(anyhow!("Invalid Stacks address length: {}", s));
+        }
+
+        let (hash, checksum) = decoded.split_at(20);
+        if checksum != c32_checksum(version, hash) {
+            return Err(anyhow!("Invalid Stacks address checksum: {}", s));
+        }
+
+        let mut hash160 = [0u8; 20];
+        hash160.copy_from_slice(hash);
+        Ok(Self { version, hash160 })
+    }
+
+    pub fn is_mainnet(&self) -> bool {
+        self.version == MAINNET_SINGLESIG || self.version == 20
+    }
+}
+
+impl std::fmt::Display for StacksAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut data = self.hash160.to_vec();
+        data.extend_from_slice(&c32_checksum(self.version, &self.hash160));
+        write!(
+            f,
+            "S{}{}",
+            C32_ALPHABET[self.version as usize & 0x1f] as char,
+            c32_encode(&data)
+        )
+    }
+}
+
+/// A fully-qualified contract identifier (`<address>.<contract-name>`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractId {
+    pub address: StacksAddress,
+    pub name: String,
+}
+
+impl ContractId {
+    pub fn parse(s: &str) -> Result<Self> {
+        let (address, name) = s
+            .split_once('.')
+            .ok_or_else(|| anyhow!("Contract id must be <address>.<name>: {}", s))?;
+
+        if name.is_empty() || name.len() > 128 {
+            return Err(anyhow!("Invalid contract name: {}", name));
+        }
+
+        Ok(Self {
+            address: StacksAddress::parse(address)?,
+            name: name.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for ContractId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.address, self.name)
+    }
+}
+
+fn c32_checksum(version: u8, hash: &[u8]) -> [u8; 4] {
+    let mut data = vec![version];
+    data.extend_from_slice(hash);
+    let digest = Sha256::digest(Sha256::digest(&data));
+    let mut checksum = [0u8; 4];
+    checksum.copy_from_slice(&digest[..4]);
+    checksum
+}
+
+fn c32_digit(c: char) -> Option<u8> {
+    // Crockford-style normalization of ambiguous characters
+    let c = match c.to_ascii_uppercase() {
+        'O' => '0',
+        'L' | 'I' => '1',
+        other => other,
+    };
+    C32_ALPHABET.iter().position(|&a| a as char == c).map(|i| i as u8)
+}
+
+/// Encode bytes as c32, keeping one '0' per leading zero byte
+pub fn c32_encode(data: &[u8]) -> String {
+    let mut num = data.to_vec();
+    let mut digits = Vec::new();
+
+    while num.iter().any(|b| *b != 0) {
+        let mut rem = 0u32;
+        for byte in num.iter_mut() {
+            let acc = (rem << 8) | *byte as u32;
+            *byte = (acc / 32) as u8;
+            rem = acc % 32;
+        }
+        digits.push(C32_ALPHABET[rem as usize] as char);
+    }
+
+    let leading_zeros = data.iter().take_while(|b| **b == 0).count();
+    std::iter::repeat_n('0', leading_zeros)
+        .chain(digits.into_iter().rev())
+        .collect()
+}
+
+/// Decode a c32 string, the inverse of [`c32_encode`]
+pub fn c32_decode(s: &str) -> Result<Vec<u8>> {
+    let digits = s
+        .chars()
+        .map(|c| c32_digit(c).ok_or_else(|| anyhow!("Invalid c32 character: {}", c)))
+        .collect::<Result<Vec<u8>>>()?;
+
+    let mut bytes: Vec<u8> = Vec::new();
+    for digit in digits.iter() {
+        let mut carry = *digit as u32;
+        for byte in bytes.iter_mut().rev() {
+            let acc = (*byte as u32) * 32 + carry;
+            *byte = (acc & 0xff) as u8;
+            carry = acc >> 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, (carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let leading_zeros = digits.iter().take_while(|d| **d == 0).count();
+    let mut result = vec![0u8; leading_zeros];
+    result.extend(bytes.into_iter().skip_while(|b| *b == 0));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boot_addresses() {
+        let mainnet = StacksAddress::new(MAINNET_SINGLESIG, [0u8; 20]);
+        assert_eq!(mainnet.to_string(), "SP000000000000000000002Q6VF78");
+
+        let testnet = StacksAddress::new(TESTNET_SINGLESIG, [0u8; 20]);
+        assert_eq!(testnet.to_string(), "ST000000000000000000002AMW42H");
+    }
+
+    #[test]
+    fn test_address_round_trip() {
+        let mut hash = [0u8; 20];
+        for (i, b) in hash.iter_mut().enumerate() {
+            *b = (i * 13 + 7) as u8;
+        }
+        let address = StacksAddress::new(MAINNET_SINGLESIG, hash);
+        let parsed = StacksAddress::parse(&address.to_string()).unwrap();
+        assert_eq!(parsed, address);
+    }
+
+    #[test]
+    fn test_rejects_bad_checksum() {
+        assert!(StacksAddress::parse("SP000000000000000000002Q6VF79").is_err());
+        assert!(StacksAddress::parse("XP000000000000000000002Q6VF78").is_err());
+    }
+
+    #[test]
+    fn test_contract_id() {
+        let id = ContractId::parse("SP000000000000000000002Q6VF78.prediction-market").unwrap();
+        assert_eq!(id.name, "prediction-market");
+        assert!(id.address.is_mainnet());
+        assert_eq!(id.to_string(), "SP000000000000000000002Q6VF78.prediction-market");
+        assert!(ContractId::parse("SP000000000000000000002Q6VF78").is_err());
+    }
+}
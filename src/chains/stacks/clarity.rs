@@ -0,0 +1,145 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+
+use super::address::{ContractId, StacksAddress};
+
+/// A Clarity value as passed to a contract function
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClarityValue {
+    Int(i128),
+    UInt(u128),
+    Buffer(Vec<u8>),
+    Bool(bool),
+    StandardPrincipal(StacksAddress),
+    ContractPrincipal(ContractId),
+    ResponseOk(Box<ClarityValue>),
+    ResponseErr(Box<ClarityValue>),
+    OptionalNone,
+    OptionalSome(Box<ClarityValue>),
+    List(Vec<ClarityValue>),
+    Tuple(BTreeMap<String, ClarityValue>),
+    StringAscii(String),
+    StringUtf8(String),
+}
+
+impl ClarityValue {
+    pub fn string_ascii(s: &str) -> Result<Self> {
+        if !s.is_ascii() {
+            return Err(anyhow!("Clarity string-ascii must be ASCII: {}", s));
+        }
+        Ok(Self::StringAscii(s.to_string()))
+    }
+
+    /// Consensus serialization of the value (SIP-005)
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.serialize_into(&mut out);
+        out
+    }
+
+    fn serialize_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Int(v) => {
+                out.push(0x00);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Self::UInt(v) => {
+                out.push(0x01);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Self::Buffer(bytes) => {
+                out.push(0x02);
+                out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+                out.extend_from_slice(bytes);
+            }
+            Self::Bool(true) => out.push(0x03),
+            Self::Bool(false) => out.push(0x04),
+            Self::StandardPrincipal(address) => {
+                out.push(0x05);
+                out.push(address.version);
+                out.extend_from_slice(&address.hash160);
+            }
+            Self::ContractPrincipal(contract) => {
+                out.push(0x06);
+                out.push(contract.address.version);
+                out.extend_from_slice(&contract.address.hash160);
+                out.push(contract.name.len() as u8);
+                out.extend_from_slice(contract.name.as_bytes());
+            }
+            Self::ResponseOk(inner) => {
+                out.push(0x07);
+                inner.serialize_into(out);
+            }
+            Self::ResponseErr(inner) => {
+                out.push(0x08);
+                inner.serialize_into(out);
+            }
+            Self::OptionalNone => out.push(0x09),
+            Self::OptionalSome(inner) => {
+                out.push(0x0a);
+                inner.serialize_into(out);
+            }
+            Self::List(items) => {
+                out.push(0x0b);
+                out.extend_from_slice(&(items.len() as u32).to_be_bytes());
+                for item in items {
+                    item.serialize_into(out);
+                }
+            }
+            Self::Tuple(fields) => {
+                // BTreeMap keeps keys in the sorted order Clarity requires
+                out.push(0x0c);
+                out.extend_from_slice(&(fields.len() as u32).to_be_bytes());
+                for (name, value) in fields {
+                    out.push(name.len() as u8);
+                    out.extend_from_slice(name.as_bytes());
+                    value.serialize_into(out);
+                }
+            }
+            Self::StringAscii(s) => {
+                out.push(0x0d);
+                out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+                out.extend_from_slice(s.as_bytes());
+            }
+            Self::StringUtf8(s) => {
+                out.push(0x0e);
+                out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+                out.extend_from_slice(s.as_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_integers() {
+        assert_eq!(
+            hex::encode(ClarityValue::UInt(1).serialize()),
+            "0100000000000000000000000000000001"
+        );
+        assert_eq!(
+            hex::encode(ClarityValue::Int(-1).serialize()),
+            "00ffffffffffffffffffffffffffffffff"
+        );
+    }
+
+    #[test]
+    fn test_serialize_strings_and_bools() {
+        let value = ClarityValue::string_ascii("yes").unwrap();
+        assert_eq!(hex::encode(value.serialize()), "0d00000003796573");
+        assert_eq!(ClarityValue::Bool(true).serialize(), vec![0x03]);
+        assert!(ClarityValue::string_ascii("ñ").is_err());
+    }
+
+    #[test]
+    fn test_serialize_tuple_sorted() {
+        let mut fields = BTreeMap::new();
+        fields.insert("b".to_string(), ClarityValue::Bool(false));
+        fields.insert("a".to_string(), ClarityValue::OptionalNone);
+        let bytes = ClarityValue::Tuple(fields).serialize();
+        assert_eq!(hex::encode(bytes), "0c00000002016109016204");
+    }
+}
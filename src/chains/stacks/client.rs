@@ -0,0 +1,401 @@
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info, warn};
+
+use super::address::StacksAddress;
+use super::transaction::{ContractCall, StacksTransaction};
+
+/// Longest a broadcast is polled for before it is reported still pending
+pub const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Final state of a broadcast transaction as reported by the API node
+#[derive(Debug, Clone, PartialEq)]
+pub enum StacksTxStatus {
+    /// Not yet in an anchored block (mempool or microblock only)
+    Pending,
+    /// Included in an anchored block with the given height
+    Anchored { block_height: u64, result: Option<String> },
+    /// Rejected, aborted or dropped by the network
+    Failed(String),
+}
+
+/// Client for a Stacks API node (stacks-node RPC plus the extended API)
+pub struct StacksClient {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+    poll_interval: Duration,
+    confirmation_timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountResponse {
+    balance: String,
+    nonce: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeeEstimateResponse {
+    estimations: Vec<FeeEstimation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeeEstimation {
+    fee: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxResponse {
+    tx_status: String,
+    #[serde(default)]
+    block_height: Option<u64>,
+    #[serde(default)]
+    is_unanchored: bool,
+    #[serde(default)]
+    tx_result: Option<TxResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxResult {
+    repr: String,
+}
+
+impl StacksClient {
+    pub fn new(base_url: &str, api_key: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            poll_interval: Duration::from_secs(10),
+            // Anchored blocks follow Bitcoin blocks, so allow a few of them
+            confirmation_timeout: CONFIRMATION_TIMEOUT,
+        }
+    }
+
+    pub fn with_polling(mut self, poll_interval: Duration, confirmation_timeout: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self.confirmation_timeout = confirmation_timeout;
+        self
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        self.with_key(self.client.get(format!("{}{}", self.base_url, path)))
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        self.with_key(self.client.post(format!("{}{}", self.base_url, path)))
+    }
+
+    fn with_key(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.header("x-api-key", key),
+            None => request,
+        }
+    }
+
+    async fn get_account(&self, address: &StacksAddress) -> Result<AccountResponse> {
+        let response = self
+            .get(&format!("/v2/accounts/{}?proof=0", address))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Next nonce for the account according to the node
+    pub async fn get_nonce(&self, address: &StacksAddress) -> Result<u64> {
+        Ok(self.get_account(address).await?.nonce)
+    }
+
+    /// Spendable balance in micro-STX
+    pub async fn get_balance(&self, address: &StacksAddress) -> Result<u128> {
+        let account = self.get_account(address).await?;
+        u128::from_str_radix(account.balance.trim_start_matches("0x"), 16)
+            .map_err(|e| anyhow!("Invalid balance from Stacks node: {}", e))
+    }
+
+    /// Fee in micro-STX from the node's estimator, using the middle estimate.
+    /// Falls back to the flat per-byte transfer rate if no estimate is available.
+    pub async fn estimate_fee(&self, payload: &ContractCall, estimated_len: usize) -> Result<u64> {
+        let body = serde_json::json!({
+            "transaction_payload": hex::encode(payload.serialize()),
+            "estimated_len": estimated_len,
+        });
+
+        let response = self.post("/v2/fees/transaction").json(&body).send().await?;
+
+        if response.status().is_success() {
+            let estimate: FeeEstimateResponse = response.json().await?;
+            let middle = estimate
+                .estimations
+                .get(1)
+                .or_else(|| estimate.estimations.first())
+                .ok_or_else(|| anyhow!("Stacks fee estimator returned no estimations"))?;
+            return Ok(middle.fee);
+        }
+
+        warn!(
+            "Stacks fee estimator unavailable ({}), falling back to transfer fee rate",
+            response.status()
+        );
+
+        let rate: u64 = self
+            .get("/v2/fees/transfer")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(rate * estimated_len as u64)
+    }
+
+    /// Broadcast a signed transaction, returning the txid accepted by the node
+    pub async fn broadcast(&self, tx: &StacksTransaction) -> Result<String> {
+        let response = self
+            .post("/v2/transactions")
+            .header("Content-Type", "application/octet-stream")
+            .body(tx.serialize())
+            .send()
+            .await?;
+
+        let status = response.status();
+        let text = response.text().await?;
+
+        if !status.is_success() {
+            return Err(anyhow!("Stacks node rejected transaction ({}): {}", status, text));
+        }
+
+        let txid: String = serde_json::from_str(&text).unwrap_or(text);
+        Ok(txid.trim_matches('"').trim_start_matches("0x").to_string())
+    }
+
+    pub async fn get_tx_status(&self, txid: &str) -> Result<StacksTxStatus> {
+        let response = self
+            .get(&format!("/extended/v1/tx/0x{}", txid))
+            .send()
+            .await?;
+
+        // The indexer may not have seen a freshly broadcast transaction yet
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(StacksTxStatus::Pending);
+        }
+
+        let tx: TxResponse = response.error_for_status()?.json().await?;
+        let result = tx.tx_result.map(|r| r.repr);
+
+        Ok(match tx.tx_status.as_str() {
+            "pending" => StacksTxStatus::Pending,
+            "success" => match tx.block_height {
+                Some(block_height) if !tx.is_unanchored => {
+                    StacksTxStatus::Anchored { block_height, result }
+                }
+                _ => StacksTxStatus::Pending,
+            },
+            other => StacksTxStatus::Failed(format!(
+                "{}{}",
+                other,
+                result.map(|r| format!(": {}", r)).unwrap_or_default()
+            )),
+        })
+    }
+
+    /// Poll until the transaction is anchored, fails, or the timeout elapses
+    pub async fn wait_for_anchor(&self, txid: &str) -> Result<StacksTxStatus> {
+        let deadline = Instant::now() + self.confirmation_timeout;
+
+        loop {
+            let status = self.get_tx_status(txid).await?;
+            if status != StacksTxStatus::Pending {
+                return Ok(status);
+            }
+
+            if Instant::now() + self.poll_interval > deadline {
+                return Ok(StacksTxStatus::Pending);
+            }
+
+            debug!("Stacks tx {} still pending", txid);
+            sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Fill nonce and fee from the node, sign and broadcast
+    pub async fn sign_and_broadcast(
+        &self,
+        tx: &mut StacksTransaction,
+        signer: &super::StacksSigner,
+    ) -> Result<String> {
+        let sender = signer.address();
+        let nonce = self.get_nonce(&sender).await?;
+        let fee = self.estimate_fee(&tx.payload, tx.serialize().len()).await?;
+
+        tx.sign(signer, nonce, fee)?;
+
+        let txid = self.broadcast(tx).await?;
+        info!(
+            "Broadcast Stacks tx {} from {} (nonce {}, fee {} uSTX)",
+            txid, sender, nonce, fee
+        );
+        Ok(txid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chains::stacks::{
+        ClarityValue, ContractId, PostConditionMode, StacksNetwork, StacksSigner,
+    };
+    use wiremock::matchers::{method, path, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TEST_KEY: &str = "edf9aee84d9b7abc145504dde6726c64f369d37ee34ded868fabd876c26570bc01";
+
+    fn test_tx(signer: &StacksSigner) -> StacksTransaction {
+        StacksTransaction::new_contract_call(
+            signer,
+            ContractCall {
+                contract: ContractId::parse("ST000000000000000000002AMW42H.prediction-market")
+                    .unwrap(),
+                function_name: "buy-outcome".to_string(),
+                args: vec![ClarityValue::UInt(1)],
+            },
+            PostConditionMode::Deny,
+            vec![],
+        )
+    }
+
+    fn fast_client(server: &MockServer) -> StacksClient {
+        StacksClient::new(&server.uri(), None)
+            .with_polling(Duration::from_millis(10), Duration::from_secs(2))
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_broadcast_uses_node_nonce_and_fee() {
+        let server = MockServer::start().await;
+        let signer = StacksSigner::from_hex(TEST_KEY, StacksNetwork::Testnet).unwrap();
+
+        Mock::given(method("GET"))
+            .and(path(format!("/v2/accounts/{}", signer.address())))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "balance": "0x0000000000000000000000003b9aca00", "nonce": 12 }),
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/fees/transaction"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "estimations": [{ "fee": 300 }, { "fee": 450 }, { "fee": 900 }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/transactions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json("0xabc123"))
+            .mount(&server)
+            .await;
+
+        let client = fast_client(&server);
+        let mut tx = test_tx(&signer);
+        let txid = client.sign_and_broadcast(&mut tx, &signer).await.unwrap();
+
+        assert_eq!(txid, "abc123");
+        assert_eq!(tx.nonce, 12);
+        assert_eq!(tx.fee, 450);
+        assert_eq!(client.get_balance(&signer.address()).await.unwrap(), 1_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_fee_falls_back_to_transfer_rate() {
+        let server = MockServer::start().await;
+        let signer = StacksSigner::from_hex(TEST_KEY, StacksNetwork::Testnet).unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/v2/fees/transaction"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("NoEstimateAvailable"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/fees/transfer"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(2))
+            .mount(&server)
+            .await;
+
+        let tx = test_tx(&signer);
+        let fee = fast_client(&server).estimate_fee(&tx.payload, 200).await.unwrap();
+        assert_eq!(fee, 400);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_rejection_is_error() {
+        let server = MockServer::start().await;
+        let signer = StacksSigner::from_hex(TEST_KEY, StacksNetwork::Testnet).unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/v2/transactions"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "transaction rejected", "reason": "BadNonce"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = fast_client(&server).broadcast(&test_tx(&signer)).await.unwrap_err();
+        assert!(err.to_string().contains("BadNonce"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_anchor_polls_until_anchored() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex("^/extended/v1/tx/0xabc123$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tx_status": "pending"
+            })))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/extended/v1/tx/0xabc123$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tx_status": "success",
+                "block_height": 150000,
+                "is_unanchored": false,
+                "tx_result": { "repr": "(ok true)" }
+            })))
+            .mount(&server)
+            .await;
+
+        let status = fast_client(&server).wait_for_anchor("abc123").await.unwrap();
+        assert_eq!(
+            status,
+            StacksTxStatus::Anchored {
+                block_height: 150000,
+                result: Some("(ok true)".to_string())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aborted_transaction_is_failed() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex("^/extended/v1/tx/0xdead$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tx_status": "abort_by_response",
+                "block_height": 150001,
+                "tx_result": { "repr": "(err u101)" }
+            })))
+            .mount(&server)
+            .await;
+
+        let status = fast_client(&server).wait_for_anchor("dead").await.unwrap();
+        assert_eq!(
+            status,
+            StacksTxStatus::Failed("abort_by_response: (err u101)".to_string())
+        );
+    }
+}
@@ -0,0 +1,234 @@
+pub mod address;
+pub mod clarity;
+pub mod client;
+pub mod transaction;
+
+use anyhow::{anyhow, Result};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+pub use address::{ContractId, StacksAddress};
+pub use clarity::ClarityValue;
+pub use client::{StacksClient, StacksTxStatus};
+pub use transaction::{
+    ContractCall, FungibleConditionCode, PostConditionMode, StacksNetwork, StacksSigner,
+    StacksTransaction, StxPostCondition,
+};
+
+use crate::config::StacksConfig;
+
+/// Prediction-market contract on Stacks, called as
+/// `(<function> (market-id (string-ascii)) (outcome (string-ascii)) (amount uint) (max-price uint))`.
+///
+/// `amount` is in micro-STX and `max-price` is the outcome price scaled by 10^6.
+/// Calls are sent in deny mode with a post-condition capping the STX the
+/// sender can spend at `amount`.
+pub struct StacksMarket {
+    client: StacksClient,
+    signer: StacksSigner,
+    contract: ContractId,
+    function_name: String,
+}
+
+impl StacksMarket {
+    pub fn from_config(config: &StacksConfig) -> Result<Self> {
+        let network = StacksNetwork::from_name(&config.network)?;
+
+        let private_key = config
+            .private_key
+            .as_ref()
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| anyhow!("Stacks private key not configured"))?;
+
+        let contract = config
+            .market_contract
+            .as_ref()
+            .ok_or_else(|| anyhow!("Stacks market contract not configured"))?;
+
+        let base_url = config
+            .stacks_api_url
+            .clone()
+            .unwrap_or_else(|| network.default_api_url().to_string());
+
+        Ok(Self {
+            client: StacksClient::new(&base_url, config.stacks_api_key.clone()),
+            signer: StacksSigner::from_hex(private_key, network)?,
+            contract: ContractId::parse(contract)?,
+            function_name: config.market_function.clone(),
+        })
+    }
+
+    pub fn with_client(mut self, client: StacksClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn sender(&self) -> StacksAddress {
+        self.signer.address()
+    }
+
+    /// Build the (unsigned) contract call for buying an outcome.
+    /// `stx` is the most the buy may spend, in STX (shares × `max_price` at
+    /// the STX/USD rate); it is sent in µSTX and capped by a deny-mode post
+    /// condition. `max_price` is the per-share odds limit, sent in millionths.
+    pub fn build_buy(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        stx: Decimal,
+        max_price: Decimal,
+    ) -> Result<StacksTransaction> {
+        let amount_ustx = to_micro(stx)?;
+        let max_price_micro = to_micro(max_price)?;
+
+        let payload = ContractCall {
+            contract: self.contract.clone(),
+            function_name: self.function_name.clone(),
+            args: vec![
+                ClarityValue::string_ascii(market_id)?,
+                ClarityValue::string_ascii(outcome)?,
+                ClarityValue::UInt(amount_ustx as u128),
+                ClarityValue::UInt(max_price_micro as u128),
+            ],
+        };
+
+        let cap = StxPostCondition {
+            principal: self.signer.address(),
+            code: FungibleConditionCode::SentLe,
+            amount: amount_ustx,
+        };
+
+        Ok(StacksTransaction::new_contract_call(
+            &self.signer,
+            payload,
+            PostConditionMode::Deny,
+            vec![cap],
+        ))
+    }
+
+    /// Sign, broadcast and wait for the buy to be anchored; `stx` and
+    /// `max_price` as in `build_buy`. Returns the txid together with its final status.
+    pub async fn buy_outcome(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        stx: Decimal,
+        max_price: Decimal,
+    ) -> Result<(String, StacksTxStatus)> {
+        let mut tx = self.build_buy(market_id, outcome, stx, max_price)?;
+        let txid = self.client.sign_and_broadcast(&mut tx, &self.signer).await?;
+        let status = self.client.wait_for_anchor(&txid).await?;
+        Ok((txid, status))
+    }
+
+    /// Spendable balance in STX
+    pub async fn get_balance(&self) -> Result<Decimal> {
+        let micro = self.client.get_balance(&self.signer.address()).await?;
+        let micro = i128::try_from(micro).map_err(|_| anyhow!("Stacks balance out of range"))?;
+        Ok(Decimal::from_i128_with_scale(micro, 6))
+    }
+}
+
+fn to_micro(value: Decimal) -> Result<u64> {
+    (value * Decimal::from(1_000_000))
+        .trunc()
+        .to_u64()
+        .ok_or_else(|| anyhow!("Amount out of range for Stacks: {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use tokio::time::Duration;
+    use wiremock::matchers::{method, path, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_config(api_url: Option<String>) -> StacksConfig {
+        StacksConfig {
+            stacks_api_key: None,
+            network: "testnet".to_string(),
+            private_key: Some(
+                "edf9aee84d9b7abc145504dde6726c64f369d37ee34ded868fabd876c26570bc01".to_string(),
+            ),
+            stacks_api_url: api_url,
+            market_contract: Some("ST000000000000000000002AMW42H.prediction-market".to_string()),
+            market_function: "buy-outcome".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_buy_arguments() {
+        let market = StacksMarket::from_config(&test_config(None)).unwrap();
+        let tx = market
+            .build_buy(
+                "btc-100k",
+                "Yes",
+                Decimal::from(25),
+                Decimal::from_str("0.615").unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(tx.payload.function_name, "buy-outcome");
+        assert_eq!(tx.payload.args[2], ClarityValue::UInt(25_000_000));
+        assert_eq!(tx.payload.args[3], ClarityValue::UInt(615_000));
+        assert_eq!(tx.post_condition_mode, PostConditionMode::Deny);
+        assert_eq!(tx.post_conditions[0].amount, 25_000_000);
+    }
+
+    #[test]
+    fn test_missing_key_is_error() {
+        let mut config = test_config(None);
+        config.private_key = None;
+        assert!(StacksMarket::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_buy_outcome_against_mock_node() {
+        let server = MockServer::start().await;
+        let market = StacksMarket::from_config(&test_config(Some(server.uri())))
+            .unwrap()
+            .with_client(
+                StacksClient::new(&server.uri(), None)
+                    .with_polling(Duration::from_millis(10), Duration::from_secs(2)),
+            );
+
+        Mock::given(method("GET"))
+            .and(path(format!("/v2/accounts/{}", market.sender())))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "balance": "0x0", "nonce": 3 }),
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/fees/transaction"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "estimations": [{ "fee": 100 }, { "fee": 200 }, { "fee": 300 }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/transactions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json("beef"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/extended/v1/tx/0xbeef$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tx_status": "success",
+                "block_height": 10,
+                "tx_result": { "repr": "(ok u1)" }
+            })))
+            .mount(&server)
+            .await;
+
+        let (txid, status) = market
+            .buy_outcome("btc-100k", "Yes", Decimal::ONE, Decimal::from_str("0.5").unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(txid, "beef");
+        assert!(matches!(status, StacksTxStatus::Anchored { block_height: 10, .. }));
+    }
+}
@@ -0,0 +1,370 @@
+use anyhow::{anyhow, Result};
+use k256::ecdsa::SigningKey;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256, Sha512_256};
+
+use super::address::{ContractId, StacksAddress, MAINNET_SINGLESIG, TESTNET_SINGLESIG};
+use super::clarity::ClarityValue;
+
+const AUTH_STANDARD: u8 = 0x04;
+const HASH_MODE_P2PKH: u8 = 0x00;
+const KEY_ENCODING_COMPRESSED: u8 = 0x00;
+const ANCHOR_MODE_ANY: u8 = 0x03;
+const PAYLOAD_CONTRACT_CALL: u8 = 0x02;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StacksNetwork {
+    Mainnet,
+    Testnet,
+}
+
+impl StacksNetwork {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "mainnet" => Ok(Self::Mainnet),
+            "testnet" | "devnet" | "mocknet" => Ok(Self::Testnet),
+            other => Err(anyhow!("Unknown Stacks network: {}", other)),
+        }
+    }
+
+    fn version_byte(&self) -> u8 {
+        match self {
+            Self::Mainnet => 0x00,
+            Self::Testnet => 0x80,
+        }
+    }
+
+    fn chain_id(&self) -> u32 {
+        match self {
+            Self::Mainnet => 0x0000_0001,
+            Self::Testnet => 0x8000_0000,
+        }
+    }
+
+    pub fn address_version(&self) -> u8 {
+        match self {
+            Self::Mainnet => MAINNET_SINGLESIG,
+            Self::Testnet => TESTNET_SINGLESIG,
+        }
+    }
+
+    pub fn default_api_url(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "https://api.hiro.so",
+            Self::Testnet => "https://api.testnet.hiro.so",
+        }
+    }
+}
+
+/// secp256k1 key used to sign single-sig Stacks transactions
+pub struct StacksSigner {
+    key: SigningKey,
+    network: StacksNetwork,
+}
+
+impl StacksSigner {
+    /// Accepts a 32-byte hex key, optionally with the trailing `01`
+    /// compressed-pubkey marker used by Stacks wallets
+    pub fn from_hex(private_key: &str, network: StacksNetwork) -> Result<Self> {
+        let bytes = hex::decode(private_key.trim().trim_start_matches("0x"))
+            .map_err(|e| anyhow!("Invalid Stacks private key hex: {}", e))?;
+
+        let secret = match bytes.len() {
+            32 => &bytes[..],
+            33 if bytes[32] == 0x01 => &bytes[..32],
+            n => return Err(anyhow!("Invalid Stacks private key length: {} bytes", n)),
+        };
+
+        let key = SigningKey::from_slice(secret)
+            .map_err(|e| anyhow!("Invalid Stacks private key: {}", e))?;
+
+        Ok(Self { key, network })
+    }
+
+    pub fn public_key(&self) -> Vec<u8> {
+        self.key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+    }
+
+    pub fn address(&self) -> StacksAddress {
+        StacksAddress::new(self.network.address_version(), hash160(&self.public_key()))
+    }
+
+    pub fn network(&self) -> StacksNetwork {
+        self.network
+    }
+
+    /// Sign a 32-byte sighash, returning the VRS-encoded recoverable signature
+    fn sign_hash(&self, hash: &[u8; 32]) -> Result<[u8; 65]> {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(hash)
+            .map_err(|e| anyhow!("Failed to sign Stacks transaction: {}", e))?;
+
+        let mut out = [0u8; 65];
+        out[0] = recovery_id.to_byte();
+        out[1..].copy_from_slice(&signature.to_bytes());
+        Ok(out)
+    }
+}
+
+/// Condition code for fungible post-conditions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FungibleConditionCode {
+    SentEq = 0x01,
+    SentGt = 0x02,
+    SentGe = 0x03,
+    SentLt = 0x04,
+    SentLe = 0x05,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostConditionMode {
+    Allow = 0x01,
+    Deny = 0x02,
+}
+
+/// STX post-condition on the amount a standard principal may send
+#[derive(Debug, Clone, PartialEq)]
+pub struct StxPostCondition {
+    pub principal: StacksAddress,
+    pub code: FungibleConditionCode,
+    pub amount: u64,
+}
+
+impl StxPostCondition {
+    fn serialize_into(&self, out: &mut Vec<u8>) {
+        out.push(0x00); // STX asset
+        out.push(0x02); // standard principal
+        out.push(self.principal.version);
+        out.extend_from_slice(&self.principal.hash160);
+        out.push(self.code as u8);
+        out.extend_from_slice(&self.amount.to_be_bytes());
+    }
+}
+
+/// Contract-call payload
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractCall {
+    pub contract: ContractId,
+    pub function_name: String,
+    pub args: Vec<ClarityValue>,
+}
+
+impl ContractCall {
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = vec![PAYLOAD_CONTRACT_CALL];
+        out.push(self.contract.address.version);
+        out.extend_from_slice(&self.contract.address.hash160);
+        out.push(self.contract.name.len() as u8);
+        out.extend_from_slice(self.contract.name.as_bytes());
+        out.push(self.function_name.len() as u8);
+        out.extend_from_slice(self.function_name.as_bytes());
+        out.extend_from_slice(&(self.args.len() as u32).to_be_bytes());
+        for arg in &self.args {
+            out.extend_from_slice(&arg.serialize());
+        }
+        out
+    }
+}
+
+/// Single-sig, standard-authorization contract-call transaction
+#[derive(Debug, Clone)]
+pub struct StacksTransaction {
+    pub network: StacksNetwork,
+    pub signer: [u8; 20],
+    pub nonce: u64,
+    pub fee: u64,
+    pub signature: [u8; 65],
+    pub post_condition_mode: PostConditionMode,
+    pub post_conditions: Vec<StxPostCondition>,
+    pub payload: ContractCall,
+}
+
+impl StacksTransaction {
+    pub fn new_contract_call(
+        signer: &StacksSigner,
+        payload: ContractCall,
+        post_condition_mode: PostConditionMode,
+        post_conditions: Vec<StxPostCondition>,
+    ) -> Self {
+        Self {
+            network: signer.network(),
+            signer: signer.address().hash160,
+            nonce: 0,
+            fee: 0,
+            signature: [0u8; 65],
+            post_condition_mode,
+            post_conditions,
+            payload,
+        }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = vec![self.network.version_byte()];
+        out.extend_from_slice(&self.network.chain_id().to_be_bytes());
+
+        out.push(AUTH_STANDARD);
+        out.push(HASH_MODE_P2PKH);
+        out.extend_from_slice(&self.signer);
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.fee.to_be_bytes());
+        out.push(KEY_ENCODING_COMPRESSED);
+        out.extend_from_slice(&self.signature);
+
+        out.push(ANCHOR_MODE_ANY);
+        out.push(self.post_condition_mode as u8);
+        out.extend_from_slice(&(self.post_conditions.len() as u32).to_be_bytes());
+        for condition in &self.post_conditions {
+            condition.serialize_into(&mut out);
+        }
+
+        out.extend_from_slice(&self.payload.serialize());
+        out
+    }
+
+    /// Transaction id: SHA-512/256 of the serialized transaction
+    pub fn txid(&self) -> String {
+        hex::encode(sha512_256(&self.serialize()))
+    }
+
+    /// Sign with the sender key, filling in nonce and fee first
+    pub fn sign(&mut self, signer: &StacksSigner, nonce: u64, fee: u64) -> Result<()> {
+        if signer.address().hash160 != self.signer {
+            return Err(anyhow!("Signer does not match transaction origin"));
+        }
+
+        // The initial sighash commits to the transaction with auth cleared
+        let mut cleared = self.clone();
+        cleared.nonce = 0;
+        cleared.fee = 0;
+        cleared.signature = [0u8; 65];
+        let initial_sighash = sha512_256(&cleared.serialize());
+
+        let mut presign = initial_sighash.to_vec();
+        presign.push(AUTH_STANDARD);
+        presign.extend_from_slice(&fee.to_be_bytes());
+        presign.extend_from_slice(&nonce.to_be_bytes());
+        let presign_sighash = sha512_256(&presign);
+
+        self.nonce = nonce;
+        self.fee = fee;
+        self.signature = signer.sign_hash(&presign_sighash)?;
+        Ok(())
+    }
+}
+
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    let digest = Ripemd160::digest(Sha256::digest(data));
+    let mut out = [0u8; 20];
+    out.copy_from_slice(&digest);
+    out
+}
+
+fn sha512_256(data: &[u8]) -> [u8; 32] {
+    let digest = Sha512_256::digest(data);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&digest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    const TEST_KEY: &str = "edf9aee84d9b7abc145504dde6726c64f369d37ee34ded868fabd876c26570bc01";
+
+    fn test_call() -> ContractCall {
+        ContractCall {
+            contract: ContractId::parse("ST000000000000000000002AMW42H.prediction-market").unwrap(),
+            function_name: "buy-outcome".to_string(),
+            args: vec![ClarityValue::UInt(42)],
+        }
+    }
+
+    #[test]
+    fn test_signer_address() {
+        let signer = StacksSigner::from_hex(TEST_KEY, StacksNetwork::Testnet).unwrap();
+        let address = signer.address().to_string();
+        assert!(address.starts_with("ST"));
+        assert_eq!(signer.public_key().len(), 33);
+        assert!(StacksSigner::from_hex("abcd", StacksNetwork::Testnet).is_err());
+    }
+
+    #[test]
+    fn test_serialized_layout() {
+        let signer = StacksSigner::from_hex(TEST_KEY, StacksNetwork::Testnet).unwrap();
+        let tx = StacksTransaction::new_contract_call(
+            &signer,
+            test_call(),
+            PostConditionMode::Deny,
+            vec![],
+        );
+        let bytes = tx.serialize();
+
+        assert_eq!(bytes[0], 0x80);
+        assert_eq!(&bytes[1..5], &[0x80, 0x00, 0x00, 0x00]);
+        assert_eq!(bytes[5], AUTH_STANDARD);
+        // header (5) + auth (1 + 1 + 20 + 8 + 8 + 1 + 65) + anchor + mode + pc count
+        let payload_offset = 5 + 104 + 1 + 1 + 4;
+        assert_eq!(bytes[payload_offset], PAYLOAD_CONTRACT_CALL);
+        assert_eq!(bytes.len(), payload_offset + tx.payload.serialize().len());
+    }
+
+    #[test]
+    fn test_signature_recovers_signer() {
+        let signer = StacksSigner::from_hex(TEST_KEY, StacksNetwork::Testnet).unwrap();
+        let mut tx = StacksTransaction::new_contract_call(
+            &signer,
+            test_call(),
+            PostConditionMode::Deny,
+            vec![StxPostCondition {
+                principal: signer.address(),
+                code: FungibleConditionCode::SentLe,
+                amount: 1_000_000,
+            }],
+        );
+        tx.sign(&signer, 7, 2_000).unwrap();
+        assert_eq!(tx.nonce, 7);
+        assert_eq!(tx.fee, 2_000);
+
+        // Recompute the presign sighash and recover the public key from it
+        let mut cleared = tx.clone();
+        cleared.nonce = 0;
+        cleared.fee = 0;
+        cleared.signature = [0u8; 65];
+        let mut presign = sha512_256(&cleared.serialize()).to_vec();
+        presign.push(AUTH_STANDARD);
+        presign.extend_from_slice(&2_000u64.to_be_bytes());
+        presign.extend_from_slice(&7u64.to_be_bytes());
+        let sighash = sha512_256(&presign);
+
+        let signature = Signature::from_slice(&tx.signature[1..]).unwrap();
+        let recovery_id = RecoveryId::from_byte(tx.signature[0]).unwrap();
+        let recovered =
+            VerifyingKey::recover_from_prehash(&sighash, &signature, recovery_id).unwrap();
+        assert_eq!(
+            recovered.to_encoded_point(true).as_bytes(),
+            signer.public_key().as_slice()
+        );
+    }
+
+    #[test]
+    fn test_txid_changes_with_signature() {
+        let signer = StacksSigner::from_hex(TEST_KEY, StacksNetwork::Testnet).unwrap();
+        let mut tx = StacksTransaction::new_contract_call(
+            &signer,
+            test_call(),
+            PostConditionMode::Allow,
+            vec![],
+        );
+        let unsigned = tx.txid();
+        tx.sign(&signer, 0, 180).unwrap();
+        assert_ne!(unsigned, tx.txid());
+        assert_eq!(tx.txid().len(), 64);
+    }
+}
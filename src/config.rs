@@ -1,22 +1,48 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::NaiveTime;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::fs;
 use std::str::FromStr;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::chains::evm::{EvmAddress, EvmChain, EvmSigner};
+use crate::chains::stacks::{ContractId, StacksNetwork, StacksSigner};
+use crate::ledger::TradingDay;
+use crate::reconcile::ReconcilePolicy;
+use crate::trader::{BtcTradeExecutor, ExecutionStrategy, Leg, TimeInForce};
+use crate::valuation::MarkMethod;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
+    #[serde(default)]
     pub general: GeneralConfig,
+    #[serde(default)]
     pub polymarket: PolymarketConfig,
+    #[serde(default)]
     pub bitcoin: BitcoinConfig,
+    #[serde(default)]
     pub trading: TradingConfig,
+    #[serde(default)]
     pub risk: RiskConfig,
+    #[serde(default)]
+    pub treasury: TreasuryConfig,
+    #[serde(default)]
+    pub rebalance: RebalanceConfig,
+    #[serde(default)]
+    pub resolver: ResolverConfig,
+    #[serde(default)]
+    pub valuation: ValuationConfig,
+    #[serde(default)]
+    pub reconcile: ReconcileConfig,
+    #[serde(default)]
     pub logging: LoggingConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct GeneralConfig {
     #[serde(default = "default_min_profit")]
     pub min_profit_threshold: Decimal,
@@ -27,7 +53,9 @@ pub struct GeneralConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PolymarketConfig {
+    #[serde(default = "default_polygon_rpc_url")]
     pub rpc_url: String,
     #[serde(default)]
     pub private_key: Option<String>,
@@ -36,7 +64,10 @@ pub struct PolymarketConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct BitcoinConfig {
+    /// "lightning", "ordinals", "stacks", "rsk" or "liquid"
+    #[serde(default = "default_protocol")]
     pub protocol: String,
     #[serde(default)]
     pub lightning: Option<LightningConfig>,
@@ -51,7 +82,9 @@ pub struct BitcoinConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LightningConfig {
+    #[serde(default)]
     pub endpoint: Option<String>,
     #[serde(default)]
     pub macaroon_path: Option<String>,
@@ -59,44 +92,200 @@ pub struct LightningConfig {
     pub cert_path: Option<String>,
     #[serde(default)]
     pub predyx_api_key: Option<String>,
+    /// Predyx API, polled for resolutions and asked for payouts
+    #[serde(default = "default_predyx_url")]
+    pub predyx_url: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct OrdinalsConfig {
-    pub ordinals_wallet_address: Option<String>,
+    /// Wallet address holding outcome inscriptions and paying for buys
+    #[serde(default, alias = "ordinals_wallet_address")]
+    pub address: Option<String>,
+    /// `ord server` base URL
+    #[serde(default, alias = "ordinals_api_endpoint")]
+    pub endpoint: Option<String>,
+    /// Parent inscription whose children publish market state
+    #[serde(default)]
+    pub parent_inscription: Option<String>,
+    #[serde(default = "default_esplora_url")]
+    pub esplora_url: String,
+    /// External PSBT signer; keys never touch this process
     #[serde(default)]
-    pub ordinals_api_endpoint: Option<String>,
+    pub signer_url: Option<String>,
+    /// Marketplace endpoint sell offers are posted to
+    #[serde(default)]
+    pub offer_url: Option<String>,
+    #[serde(default = "default_bitcoin_network")]
+    pub network: String,
+    /// Fee rate in sat/vB
+    #[serde(default = "default_fee_rate")]
+    pub fee_rate: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct StacksConfig {
+    #[serde(default)]
     pub stacks_api_key: Option<String>,
-    pub stacks_network: String,
+    #[serde(default = "default_stacks_network", alias = "stacks_network")]
+    pub network: String,
+    #[serde(default, alias = "stacks_private_key")]
+    pub private_key: Option<String>,
+    #[serde(default)]
+    pub stacks_api_url: Option<String>,
+    /// Prediction-market contract as `<address>.<contract-name>`
+    #[serde(default)]
+    pub market_contract: Option<String>,
+    #[serde(default = "default_stacks_market_function")]
+    pub market_function: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RskConfig {
-    pub rsk_rpc_url: Option<String>,
-    pub rsk_private_key: Option<String>,
+    #[serde(default, alias = "rsk_rpc_url")]
+    pub rpc_url: Option<String>,
+    #[serde(default, alias = "rsk_private_key")]
+    pub private_key: Option<String>,
+    #[serde(default = "default_rsk_network")]
+    pub rsk_network: String,
+    #[serde(default)]
+    pub market_contract: Option<String>,
+    /// ERC-20 collateral accepted by the market contract (e.g. DOC)
+    #[serde(default)]
+    pub collateral_token: Option<String>,
+    #[serde(default = "default_collateral_decimals")]
+    pub collateral_decimals: u32,
+    #[serde(default = "default_rsk_market_function")]
+    pub market_function: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LiquidConfig {
-    pub liquid_rpc_url: Option<String>,
-    pub liquid_private_key: Option<String>,
+    #[serde(default, alias = "liquid_rpc_url")]
+    pub rpc_url: Option<String>,
+    #[serde(default, alias = "liquid_private_key")]
+    pub private_key: Option<String>,
+    #[serde(default)]
+    pub liquid_rpc_user: Option<String>,
+    #[serde(default)]
+    pub liquid_rpc_password: Option<String>,
+    /// Elements wallet name, for nodes with several wallets loaded
+    #[serde(default)]
+    pub liquid_wallet: Option<String>,
+    /// Confidential address of the market that issues outcome tokens
+    #[serde(default)]
+    pub settlement_address: Option<String>,
+    /// Asset id paid to the market (e.g. L-BTC or Liquid USDt)
+    #[serde(default)]
+    pub collateral_asset: Option<String>,
+    #[serde(default)]
+    pub outcome_assets: Vec<LiquidOutcomeAsset>,
+    #[serde(default = "default_liquid_confirmations")]
+    pub min_confirmations: u64,
+}
+
+/// Maps a Liquid-issued outcome token to its market and outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LiquidOutcomeAsset {
+    pub asset_id: String,
+    pub market_id: String,
+    pub outcome: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct TradingConfig {
     #[serde(default = "default_auto_execute")]
     pub auto_execute: bool,
+    /// Hold each trade until an operator approves it
     #[serde(default = "default_require_confirmation")]
     pub require_confirmation: bool,
+    /// Unanswered approval requests expire after this long
+    #[serde(default = "default_approval_timeout")]
+    pub approval_timeout_secs: u64,
+    /// Where approvals are taken: "terminal" and/or "http"
+    #[serde(default = "default_approval_channels")]
+    pub approval_channels: Vec<String>,
+    /// Local address of the approval endpoint
+    #[serde(default = "default_approval_listen_addr")]
+    pub approval_listen_addr: String,
+    /// Enables the signed chat-command webhook on the approval endpoint and
+    /// requires the same signature on approve and reject calls
+    #[serde(default)]
+    pub approval_webhook_secret: Option<String>,
+    /// Fee rate on notional assumed when estimating trade costs
+    #[serde(default)]
+    pub estimated_fee_rate: Decimal,
     #[serde(default = "default_max_concurrent")]
     pub max_concurrent_trades: u32,
+    /// Queued trades whose quotes are older than this are rejected
+    #[serde(default = "default_max_queue_age")]
+    pub max_queue_age_secs: u64,
+    /// Per-venue API request and order limits per minute; 0 disables a limit
+    #[serde(default = "default_polymarket_requests_per_min")]
+    pub polymarket_requests_per_min: u32,
+    #[serde(default = "default_polymarket_orders_per_min")]
+    pub polymarket_orders_per_min: u32,
+    #[serde(default = "default_btc_requests_per_min")]
+    pub btc_requests_per_min: u32,
+    #[serde(default = "default_btc_orders_per_min")]
+    pub btc_orders_per_min: u32,
+    /// Leg ordering: "thin_side_first", "concurrent" or "hedge_after_fill"
+    #[serde(default = "default_execution_strategy")]
+    pub execution_strategy: String,
+    /// Less liquid / slower leg, sent first by the sequential strategies: "btc" or "polymarket"
+    #[serde(default = "default_thin_leg")]
+    pub thin_leg: String,
+    #[serde(default = "default_polymarket_leg_timeout")]
+    pub polymarket_leg_timeout_secs: u64,
+    #[serde(default = "default_btc_leg_timeout")]
+    pub btc_leg_timeout_secs: u64,
+    /// Default time in force: "gtc", "ioc" or "fok"
+    #[serde(default = "default_time_in_force")]
+    pub time_in_force: String,
+    /// Log trades without sending orders
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Simulate fills against live order books instead of sending orders
+    #[serde(default)]
+    pub paper_trading: bool,
+    /// Virtual starting balance per venue, in USD
+    #[serde(default = "default_paper_balance")]
+    pub paper_starting_balance: Decimal,
+    /// Fee charged on paper fill notional
+    #[serde(default)]
+    pub paper_fee_rate: Decimal,
+    /// Delay between decision and fill, during which the book can move
+    #[serde(default = "default_paper_latency")]
+    pub paper_latency_ms: u64,
+    /// Detections of the same market pair within one window of this many
+    /// seconds share an idempotency key, so only one of them is traded
+    #[serde(default = "default_idempotency_window")]
+    pub idempotency_window_secs: u64,
+    /// Write-ahead journal of trade intents, replayed on startup
+    #[serde(default = "default_journal_path")]
+    pub journal_path: String,
+    /// SQLite database of positions, loaded on startup
+    #[serde(default = "default_positions_db_path")]
+    pub positions_db_path: String,
+    /// Kill-switch state; a halt recorded here survives restarts
+    #[serde(default = "default_halt_state_path")]
+    pub halt_state_path: String,
+    /// Trading halts while this file exists
+    #[serde(default = "default_kill_file")]
+    pub kill_file: String,
+    /// Local address of the admin endpoint; empty disables it
+    #[serde(default = "default_admin_listen_addr")]
+    pub admin_listen_addr: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RiskConfig {
     #[serde(default = "default_max_daily_loss")]
     pub max_daily_loss: Decimal,
@@ -104,9 +293,168 @@ pub struct RiskConfig {
     pub stop_on_max_loss: bool,
     #[serde(default = "default_max_positions")]
     pub max_open_positions: u32,
+    /// Most notional, in USD, held on any one market
+    #[serde(default = "default_max_market_exposure")]
+    pub max_market_exposure: Decimal,
+    /// Most notional, in USD, held at either venue
+    #[serde(default = "default_max_venue_exposure")]
+    pub max_venue_exposure: Decimal,
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f64,
+    /// Quotes older than this are not traded
+    #[serde(default = "default_max_quote_age")]
+    pub max_quote_age_secs: u64,
+    /// Largest gap allowed between the two markets' resolution times
+    #[serde(default = "default_max_resolution_gap")]
+    pub max_resolution_gap_hours: u64,
+    /// Most an unwind of a one-legged position may lose, in USD
+    #[serde(default = "default_unwind_max_loss")]
+    pub unwind_max_loss: Decimal,
+    /// Price step between hedge retries at successively worse limits
+    #[serde(default = "default_unwind_price_step")]
+    pub unwind_price_step: Decimal,
+    #[serde(default = "default_unwind_max_attempts")]
+    pub unwind_max_attempts: u32,
+    /// Circuit breakers: consecutive failed legs and API errors before halting
+    #[serde(default = "default_breaker_max_failed_legs")]
+    pub breaker_max_failed_legs: u32,
+    #[serde(default = "default_breaker_max_api_errors")]
+    pub breaker_max_api_errors: u32,
+    /// Largest move in a market's odds between two quotes before halting
+    #[serde(default = "default_breaker_max_price_jump")]
+    pub breaker_max_price_jump: Decimal,
+    /// Ledger of realized PnL, fees and unwind costs behind the daily loss limit
+    #[serde(default = "default_pnl_ledger_path")]
+    pub pnl_ledger_path: String,
+    /// Trading days start at this "HH:MM" in `daily_reset_timezone`
+    #[serde(default = "default_daily_reset_time")]
+    pub daily_reset_time: String,
+    /// "UTC" or a fixed offset such as "-05:00"
+    #[serde(default = "default_daily_reset_timezone")]
+    pub daily_reset_timezone: String,
+    /// Webhook that receives alerts needing operator action
+    #[serde(default)]
+    pub alert_webhook_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TreasuryConfig {
+    /// Size trades to the balances both venues can fund
+    #[serde(default = "default_treasury_enabled")]
+    pub enabled: bool,
+    /// Cached balances older than this are re-fetched before trading
+    #[serde(default = "default_max_balance_age")]
+    pub max_balance_age_secs: u64,
+    /// Funds held for an order left working are released after this long
+    /// if its venue cannot say how the order ended
+    #[serde(default = "default_pending_reservation_ttl")]
+    pub pending_reservation_ttl_secs: u64,
+    /// USD prices used to size legs funded in BTC-denominated assets or STX.
+    /// The BTC/USD price is the fallback until `btc_usd_url` answers.
+    #[serde(default = "default_btc_usd_price")]
+    pub btc_usd_price: Decimal,
+    /// Spot BTC/USD endpoint, re-fetched with the balances; empty keeps `btc_usd_price`
+    #[serde(default = "default_btc_usd_url")]
+    pub btc_usd_url: String,
+    #[serde(default = "default_stx_usd_price")]
+    pub stx_usd_price: Decimal,
+    /// USD price of the RSK market's ERC-20 collateral; 1.0 for a dollar stablecoin like DOC
+    #[serde(default = "default_rsk_collateral_usd_price")]
+    pub rsk_collateral_usd_price: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResolverConfig {
+    /// Poll venues for resolved markets and settle open positions
+    #[serde(default = "default_resolver_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_resolver_interval")]
+    pub poll_interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValuationConfig {
+    /// Mark open positions and record portfolio snapshots
+    #[serde(default = "default_valuation_enabled")]
+    pub enabled: bool,
+    /// "bid", "mid" or "liquidation"
+    #[serde(default = "default_mark_method")]
+    pub mark_method: String,
+    #[serde(default = "default_valuation_interval")]
+    pub interval_secs: u64,
+    /// Marks from books older than this are flagged stale
+    #[serde(default = "default_max_mark_age")]
+    pub max_mark_age_secs: u64,
+    #[serde(default = "default_valuation_series_path")]
+    pub series_path: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReconcileConfig {
+    /// Compare tracked positions with the holdings venues report
+    #[serde(default = "default_reconcile_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_reconcile_interval")]
+    pub interval_secs: u64,
+    /// "alert" or "correct"
+    #[serde(default = "default_reconcile_policy")]
+    pub policy: String,
+    /// Quantity differences up to this many shares are ignored
+    #[serde(default = "default_quantity_tolerance")]
+    pub quantity_tolerance: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RebalanceConfig {
+    /// Propose transfers when a venue leaves its band
+    #[serde(default = "default_rebalance_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_rebalance_interval")]
+    pub check_interval_secs: u64,
+    /// Per-venue inventory bands in USD
+    #[serde(default = "default_band_min")]
+    pub polymarket_min_usd: Decimal,
+    #[serde(default = "default_band_target")]
+    pub polymarket_target_usd: Decimal,
+    #[serde(default = "default_band_max")]
+    pub polymarket_max_usd: Decimal,
+    #[serde(default = "default_band_min")]
+    pub btc_min_usd: Decimal,
+    #[serde(default = "default_band_target")]
+    pub btc_target_usd: Decimal,
+    #[serde(default = "default_band_max")]
+    pub btc_max_usd: Decimal,
+    /// Alert when a venue holds less than this
+    #[serde(default = "default_depleted_usd")]
+    pub depleted_usd: Decimal,
+    #[serde(default = "default_min_transfer")]
+    pub min_transfer_usd: Decimal,
+    /// Skip transfers costing more than this fraction of the amount moved
+    #[serde(default = "default_max_cost_rate")]
+    pub max_cost_rate: Decimal,
+    /// Cost model: exchange trading fee, Polygon gas, exchange withdrawal
+    /// fees, on-chain miner fee and submarine swap fee
+    #[serde(default = "default_exchange_fee_rate")]
+    pub exchange_fee_rate: Decimal,
+    #[serde(default = "default_polygon_gas")]
+    pub polygon_gas_usd: Decimal,
+    #[serde(default = "default_usdc_withdraw_fee")]
+    pub usdc_withdraw_fee_usd: Decimal,
+    #[serde(default = "default_btc_withdraw_fee")]
+    pub btc_withdraw_fee_usd: Decimal,
+    #[serde(default = "default_onchain_fee")]
+    pub onchain_fee_usd: Decimal,
+    #[serde(default = "default_swap_fee_rate")]
+    pub swap_fee_rate: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LoggingConfig {
     #[serde(default = "default_log_level")]
     pub level: String,
@@ -114,35 +462,487 @@ pub struct LoggingConfig {
     pub file: String,
 }
 
+/// Prefix of environment variables that override config keys. Nested keys
+/// are joined by `__`: `POLYARB_TRADING__AUTO_EXECUTE=true`.
+pub const ENV_PREFIX: &str = "POLYARB_";
+
 impl Config {
+    /// Defaults, overlaid by the file at `path`, overlaid by `POLYARB_*`
+    /// environment variables
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = fs::read_to_string(path)?;
-        let config: Self = toml::from_str(&content)?;
-        Ok(config)
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        Self::layered(Some(&content), std::env::vars())
+            .with_context(|| format!("Invalid config {}", path.display()))
     }
 
+    /// Like `load`, but only defaults and environment when `path` doesn't exist
     pub fn load_or_default<P: AsRef<Path>>(path: P) -> Result<Self> {
         if path.as_ref().exists() {
             Self::load(path)
         } else {
-            Ok(Self::default())
+            Self::layered(None, std::env::vars())
         }
     }
+
+    /// Build a config from defaults, an optional TOML document and
+    /// environment variables; variables without the `POLYARB_` prefix are ignored
+    pub fn layered(file: Option<&str>, env: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        let mut tree = toml::Value::try_from(Self::default())?;
+        if let Some(content) = file {
+            // Parsed on its own first, so errors point at the file's lines
+            toml::from_str::<Self>(content)?;
+            merge(&mut tree, toml::from_str(content)?);
+        }
+
+        let mut overrides: Vec<String> = Vec::new();
+        for (name, value) in env {
+            if let Some(key) = name.strip_prefix(ENV_PREFIX) {
+                set_env_override(&mut tree, key, &value).with_context(|| format!("Invalid {}", name))?;
+                overrides.push(name);
+            }
+        }
+
+        tree.try_into().map_err(|e: toml::de::Error| {
+            if overrides.is_empty() {
+                e.into()
+            } else {
+                anyhow::anyhow!("{} (environment overrides: {})", e, overrides.join(", "))
+            }
+        })
+    }
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            general: GeneralConfig::default(),
-            polymarket: PolymarketConfig::default(),
-            bitcoin: BitcoinConfig::default(),
-            trading: TradingConfig::default(),
-            risk: RiskConfig::default(),
-            logging: LoggingConfig::default(),
+/// A setting that doesn't make sense, named by its key path
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    pub key: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Collects problems while `Config::validate` walks the sections
+#[derive(Default)]
+struct Problems(Vec<ConfigProblem>);
+
+impl Problems {
+    fn add(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigProblem { key: key.into(), message: message.into() });
+    }
+
+    /// Record `result`'s error, if any, against `key`
+    fn check<T>(&mut self, key: impl Into<String>, result: Result<T>) -> Option<T> {
+        result.map_err(|e| self.add(key, format!("{:#}", e))).ok()
+    }
+
+    fn url(&mut self, key: &str, url: &str) {
+        self.check(key, reqwest::Url::parse(url).with_context(|| format!("Invalid URL {}", url)));
+    }
+
+    fn non_negative(&mut self, key: &str, value: Decimal) {
+        if value.is_sign_negative() && !value.is_zero() {
+            self.add(key, format!("must not be negative, got {}", value));
+        }
+    }
+
+    fn positive(&mut self, key: &str, value: Decimal) {
+        if value <= Decimal::ZERO {
+            self.add(key, format!("must be positive, got {}", value));
+        }
+    }
+
+    /// Rates are fractions: 0.01 is 1%
+    fn rate(&mut self, key: &str, value: Decimal) {
+        if value < Decimal::ZERO || value >= Decimal::ONE {
+            self.add(key, format!("must be a fraction in [0, 1), got {}", value));
+        }
+    }
+
+    fn nonzero(&mut self, key: &str, value: u64) {
+        if value == 0 {
+            self.add(key, "must be greater than 0");
+        }
+    }
+}
+
+/// `[bitcoin]` protocols with a section of their own
+const PROTOCOLS: [&str; 5] = ["lightning", "ordinals", "stacks", "rsk", "liquid"];
+
+impl Config {
+    /// Check that the settings make sense on their own and together. Every
+    /// problem is returned, not just the first; an empty list means the
+    /// config is usable. Only the selected `bitcoin.protocol` section is checked.
+    pub fn validate(&self) -> Vec<ConfigProblem> {
+        let mut problems = Problems::default();
+        self.validate_general(&mut problems);
+        self.validate_polymarket(&mut problems);
+        self.validate_bitcoin(&mut problems);
+        self.validate_trading(&mut problems);
+        self.validate_risk(&mut problems);
+        self.validate_background(&mut problems);
+        problems.0
+    }
+
+    fn validate_general(&self, problems: &mut Problems) {
+        let general = &self.general;
+        problems.non_negative("general.min_profit_threshold", general.min_profit_threshold);
+        problems.positive("general.max_position_size", general.max_position_size);
+        problems.non_negative("general.max_slippage", general.max_slippage);
+        if general.max_slippage > general.min_profit_threshold {
+            problems.add(
+                "general.max_slippage",
+                format!(
+                    "{} exceeds min_profit_threshold {}; slippage alone could turn a trade into a loss",
+                    general.max_slippage, general.min_profit_threshold
+                ),
+            );
+        }
+    }
+
+    fn validate_polymarket(&self, problems: &mut Problems) {
+        let polymarket = &self.polymarket;
+        problems.check("polymarket.network", EvmChain::polygon_from_network(&polymarket.network));
+        problems.url("polymarket.rpc_url", &polymarket.rpc_url);
+        if let Some(key) = polymarket.private_key.as_deref().filter(|k| !k.is_empty()) {
+            problems.check("polymarket.private_key", EvmSigner::from_hex(key));
+        }
+    }
+
+    fn validate_bitcoin(&self, problems: &mut Problems) {
+        let bitcoin = &self.bitcoin;
+        let protocol = bitcoin.protocol.as_str();
+        if !PROTOCOLS.contains(&protocol) {
+            problems.add(
+                "bitcoin.protocol",
+                format!("unknown protocol {:?}; expected one of {}", protocol, PROTOCOLS.join(", ")),
+            );
+            return;
+        }
+
+        let present = match protocol {
+            "lightning" => bitcoin.lightning.as_ref().map(|c| validate_lightning(c, problems)),
+            "ordinals" => bitcoin.ordinals.as_ref().map(|c| validate_ordinals(c, problems)),
+            "stacks" => bitcoin.stacks.as_ref().map(|c| validate_stacks(c, problems)),
+            "rsk" => bitcoin.rsk.as_ref().map(|c| validate_rsk(c, problems)),
+            _ => bitcoin.liquid.as_ref().map(|c| validate_liquid(c, problems)),
+        };
+        if present.is_none() {
+            problems.add(
+                format!("bitcoin.{}", protocol),
+                format!("protocol is {:?} but there is no [bitcoin.{}] section", protocol, protocol),
+            );
+        }
+    }
+
+    fn validate_trading(&self, problems: &mut Problems) {
+        let trading = &self.trading;
+        problems.check("trading.execution_strategy", ExecutionStrategy::from_name(&trading.execution_strategy));
+        problems.check("trading.thin_leg", Leg::from_name(&trading.thin_leg));
+        problems.check("trading.time_in_force", TimeInForce::from_name(&trading.time_in_force));
+        for channel in &trading.approval_channels {
+            if !matches!(channel.as_str(), "terminal" | "http") {
+                problems.add(
+                    "trading.approval_channels",
+                    format!("unknown channel {:?}; expected \"terminal\" or \"http\"", channel),
+                );
+            }
+        }
+        if trading.approval_channels.iter().any(|c| c == "http") {
+            problems.check(
+                "trading.approval_listen_addr",
+                trading.approval_listen_addr.parse::<SocketAddr>().context("Expected host:port"),
+            );
+        }
+        if !trading.admin_listen_addr.is_empty() {
+            problems.check(
+                "trading.admin_listen_addr",
+                trading.admin_listen_addr.parse::<SocketAddr>().context("Expected host:port or empty"),
+            );
+        }
+        problems.rate("trading.estimated_fee_rate", trading.estimated_fee_rate);
+        problems.rate("trading.paper_fee_rate", trading.paper_fee_rate);
+        problems.nonzero("trading.max_concurrent_trades", trading.max_concurrent_trades.into());
+        problems.nonzero("trading.polymarket_leg_timeout_secs", trading.polymarket_leg_timeout_secs);
+        problems.nonzero("trading.btc_leg_timeout_secs", trading.btc_leg_timeout_secs);
+        problems.nonzero("trading.idempotency_window_secs", trading.idempotency_window_secs);
+        if trading.paper_trading {
+            problems.positive("trading.paper_starting_balance", trading.paper_starting_balance);
+            let ordinals = self.bitcoin.ordinals.as_ref().filter(|_| self.bitcoin.protocol == "ordinals");
+            if ordinals.is_none_or(|o| o.endpoint.is_none() || o.parent_inscription.is_none()) {
+                problems.add(
+                    "trading.paper_trading",
+                    "needs a BTC-side order book: use the ordinals protocol with an endpoint and parent inscription",
+                );
+            }
+        }
+
+        if trading.auto_execute && PROTOCOLS.contains(&self.bitcoin.protocol.as_str()) {
+            let wait = BtcTradeExecutor::new(self.bitcoin.clone()).confirmation_wait();
+            if trading.btc_leg_timeout_secs < wait.as_secs() {
+                problems.add(
+                    "trading.btc_leg_timeout_secs",
+                    format!(
+                        "{}s is shorter than the {}s a {} leg may wait for confirmation; a broadcast order would time out without its txid",
+                        trading.btc_leg_timeout_secs,
+                        wait.as_secs(),
+                        self.bitcoin.protocol
+                    ),
+                );
+            }
+        }
+
+        // Dry runs never sign anything
+        if trading.auto_execute && !trading.dry_run {
+            if self.polymarket.private_key.as_deref().is_none_or(str::is_empty) {
+                problems.add("trading.auto_execute", "enabled but polymarket.private_key is not set");
+            }
+            if PROTOCOLS.contains(&self.bitcoin.protocol.as_str())
+                && !BtcTradeExecutor::new(self.bitcoin.clone()).is_configured()
+            {
+                problems.add(
+                    "trading.auto_execute",
+                    format!("enabled but the bitcoin.{} wallet is not fully configured", self.bitcoin.protocol),
+                );
+            }
+        }
+    }
+
+    fn validate_risk(&self, problems: &mut Problems) {
+        let risk = &self.risk;
+        problems.non_negative("risk.max_daily_loss", risk.max_daily_loss);
+        problems.non_negative("risk.max_market_exposure", risk.max_market_exposure);
+        problems.non_negative("risk.max_venue_exposure", risk.max_venue_exposure);
+        problems.non_negative("risk.unwind_max_loss", risk.unwind_max_loss);
+        problems.positive("risk.unwind_price_step", risk.unwind_price_step);
+        if !(0.0..=1.0).contains(&risk.min_confidence) {
+            problems.add("risk.min_confidence", format!("must be between 0 and 1, got {}", risk.min_confidence));
+        }
+        if risk.breaker_max_price_jump <= Decimal::ZERO || risk.breaker_max_price_jump > Decimal::ONE {
+            problems.add(
+                "risk.breaker_max_price_jump",
+                format!("must be in (0, 1], got {}", risk.breaker_max_price_jump),
+            );
+        }
+        if NaiveTime::parse_from_str(&risk.daily_reset_time, "%H:%M").is_err() {
+            problems.add("risk.daily_reset_time", format!("expected \"HH:MM\", got {:?}", risk.daily_reset_time));
+        } else {
+            problems.check("risk.daily_reset_timezone", TradingDay::from_config(risk));
+        }
+        if let Some(url) = &risk.alert_webhook_url {
+            problems.url("risk.alert_webhook_url", url);
+        }
+    }
+
+    /// Treasury, rebalancing and the periodic tasks
+    fn validate_background(&self, problems: &mut Problems) {
+        if self.treasury.enabled {
+            problems.positive("treasury.btc_usd_price", self.treasury.btc_usd_price);
+            if !self.treasury.btc_usd_url.is_empty() {
+                problems.url("treasury.btc_usd_url", &self.treasury.btc_usd_url);
+            }
+            problems.positive("treasury.stx_usd_price", self.treasury.stx_usd_price);
+            problems.positive("treasury.rsk_collateral_usd_price", self.treasury.rsk_collateral_usd_price);
+        }
+
+        let rebalance = &self.rebalance;
+        if rebalance.enabled {
+            problems.nonzero("rebalance.check_interval_secs", rebalance.check_interval_secs);
+            let bands = [
+                ("polymarket", rebalance.polymarket_min_usd, rebalance.polymarket_target_usd, rebalance.polymarket_max_usd),
+                ("btc", rebalance.btc_min_usd, rebalance.btc_target_usd, rebalance.btc_max_usd),
+            ];
+            for (venue, min, target, max) in bands {
+                problems.non_negative(&format!("rebalance.{}_min_usd", venue), min);
+                if !(min <= target && target <= max) {
+                    problems.add(
+                        format!("rebalance.{}_target_usd", venue),
+                        format!("band must satisfy min <= target <= max, got {} / {} / {}", min, target, max),
+                    );
+                }
+            }
+            problems.non_negative("rebalance.min_transfer_usd", rebalance.min_transfer_usd);
+            problems.rate("rebalance.max_cost_rate", rebalance.max_cost_rate);
+            problems.rate("rebalance.exchange_fee_rate", rebalance.exchange_fee_rate);
+            problems.rate("rebalance.swap_fee_rate", rebalance.swap_fee_rate);
+        }
+
+        if self.resolver.enabled {
+            problems.nonzero("resolver.poll_interval_secs", self.resolver.poll_interval_secs);
+        }
+        problems.check("valuation.mark_method", MarkMethod::from_name(&self.valuation.mark_method));
+        if self.valuation.enabled {
+            problems.nonzero("valuation.interval_secs", self.valuation.interval_secs);
+        }
+        problems.check("reconcile.policy", ReconcilePolicy::from_name(&self.reconcile.policy));
+        problems.non_negative("reconcile.quantity_tolerance", self.reconcile.quantity_tolerance);
+        if self.reconcile.enabled {
+            problems.nonzero("reconcile.interval_secs", self.reconcile.interval_secs);
+        }
+        problems.check(
+            "logging.level",
+            tracing::Level::from_str(&self.logging.level).map_err(|e| anyhow::anyhow!("{}", e)),
+        );
+    }
+}
+
+fn validate_lightning(config: &LightningConfig, problems: &mut Problems) {
+    match config.endpoint.as_deref() {
+        None | Some("") => problems.add("bitcoin.lightning.endpoint", "is required"),
+        Some(_) => {}
+    }
+    for (key, path) in [("macaroon_path", &config.macaroon_path), ("cert_path", &config.cert_path)] {
+        if let Some(path) = path {
+            problems.check(
+                format!("bitcoin.lightning.{}", key),
+                fs::File::open(path).with_context(|| format!("Cannot read {}", path)),
+            );
+        }
+    }
+    problems.url("bitcoin.lightning.predyx_url", &config.predyx_url);
+}
+
+fn validate_ordinals(config: &OrdinalsConfig, problems: &mut Problems) {
+    let network = problems.check(
+        "bitcoin.ordinals.network",
+        bitcoin::Network::from_str(&config.network)
+            .with_context(|| format!("Unknown Bitcoin network {}", config.network)),
+    );
+    if let Some(address) = &config.address {
+        let parsed = bitcoin::Address::from_str(address)
+            .with_context(|| format!("Invalid address {}", address))
+            .and_then(|a| match network {
+                Some(network) => Ok(a.require_network(network)?),
+                None => Ok(a.assume_checked()),
+            });
+        problems.check("bitcoin.ordinals.address", parsed);
+    }
+    for (key, url) in [("endpoint", &config.endpoint), ("signer_url", &config.signer_url), ("offer_url", &config.offer_url)] {
+        if let Some(url) = url {
+            problems.url(&format!("bitcoin.ordinals.{}", key), url);
+        }
+    }
+    problems.url("bitcoin.ordinals.esplora_url", &config.esplora_url);
+    problems.nonzero("bitcoin.ordinals.fee_rate", config.fee_rate);
+}
+
+fn validate_stacks(config: &StacksConfig, problems: &mut Problems) {
+    let network = problems.check("bitcoin.stacks.network", StacksNetwork::from_name(&config.network));
+    if let Some(key) = config.private_key.as_deref().filter(|k| !k.is_empty()) {
+        problems.check(
+            "bitcoin.stacks.private_key",
+            StacksSigner::from_hex(key, network.unwrap_or(StacksNetwork::Mainnet)),
+        );
+    }
+    if let Some(contract) = &config.market_contract {
+        problems.check("bitcoin.stacks.market_contract", ContractId::parse(contract));
+    }
+    if let Some(url) = &config.stacks_api_url {
+        problems.url("bitcoin.stacks.stacks_api_url", url);
+    }
+}
+
+fn validate_rsk(config: &RskConfig, problems: &mut Problems) {
+    let chain = match config.rsk_network.as_str() {
+        "mainnet" => Some(EvmChain::rsk_mainnet()),
+        "testnet" => Some(EvmChain::rsk_testnet()),
+        other => {
+            problems.add("bitcoin.rsk.rsk_network", format!("unknown network {:?}; expected \"mainnet\" or \"testnet\"", other));
+            None
+        }
+    };
+    if let Some(url) = &config.rpc_url {
+        problems.url("bitcoin.rsk.rpc_url", url);
+    }
+    if let Some(key) = config.private_key.as_deref().filter(|k| !k.is_empty()) {
+        problems.check("bitcoin.rsk.private_key", EvmSigner::from_hex(key));
+    }
+    let checksum_chain_id = chain.and_then(|c| c.checksum_chain_id());
+    for (key, address) in [("market_contract", &config.market_contract), ("collateral_token", &config.collateral_token)] {
+        if let Some(address) = address {
+            problems.check(format!("bitcoin.rsk.{}", key), EvmAddress::parse(address, checksum_chain_id));
         }
     }
 }
 
+fn validate_liquid(config: &LiquidConfig, problems: &mut Problems) {
+    if let Some(url) = &config.rpc_url {
+        problems.url("bitcoin.liquid.rpc_url", url);
+    }
+    if let Some(asset) = &config.collateral_asset {
+        check_asset_id(problems, "bitcoin.liquid.collateral_asset", asset);
+    }
+    for (i, outcome) in config.outcome_assets.iter().enumerate() {
+        check_asset_id(problems, &format!("bitcoin.liquid.outcome_assets[{}].asset_id", i), &outcome.asset_id);
+    }
+}
+
+/// Liquid asset ids are 32 bytes of hex
+fn check_asset_id(problems: &mut Problems, key: &str, asset: &str) {
+    if asset.len() != 64 || !asset.chars().all(|c| c.is_ascii_hexdigit()) {
+        problems.add(key, format!("expected a 64-character hex asset id, got {:?}", asset));
+    }
+}
+
+/// Overlay `overlay` on `base`, table by table; anything else is replaced
+fn merge(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Set the key named by `path` (`SECTION__KEY`, case-insensitive). Values
+/// replacing a string stay strings. Otherwise booleans, decimal numbers,
+/// arrays and quoted strings are read as TOML and anything else (hex keys,
+/// URLs) as a string; quote a number (`'"123"'`) to force a string.
+fn set_env_override(tree: &mut toml::Value, path: &str, raw: &str) -> Result<()> {
+    let keys: Vec<String> = path.split("__").map(str::to_lowercase).collect();
+    let (last, sections) = keys
+        .split_last()
+        .filter(|(last, _)| !last.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("No config key named"))?;
+
+    let mut node = tree;
+    for section in sections {
+        node = node
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("{} is not a section", section))?
+            .entry(section.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    }
+    let table = node
+        .as_table_mut()
+        .ok_or_else(|| anyhow::anyhow!("{} is not a section", sections.join(".")))?;
+
+    let typed = matches!(raw, "true" | "false")
+        || raw.starts_with(['[', '"', '\''])
+        || (!raw.contains(char::is_alphabetic) && raw.parse::<f64>().is_ok());
+    let value = match table.get(last) {
+        Some(toml::Value::String(_)) => None,
+        _ if typed => toml::from_str::<toml::Table>(&format!("value = {}", raw))
+            .ok()
+            .and_then(|mut parsed| parsed.remove("value")),
+        _ => None,
+    };
+    table.insert(last.clone(), value.unwrap_or_else(|| toml::Value::String(raw.to_string())));
+    Ok(())
+}
+
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
@@ -156,7 +956,7 @@ impl Default for GeneralConfig {
 impl Default for PolymarketConfig {
     fn default() -> Self {
         Self {
-            rpc_url: "https://polygon-rpc.com".to_string(),
+            rpc_url: default_polygon_rpc_url(),
             private_key: None,
             network: default_network(),
         }
@@ -166,7 +966,7 @@ impl Default for PolymarketConfig {
 impl Default for BitcoinConfig {
     fn default() -> Self {
         Self {
-            protocol: "lightning".to_string(),
+            protocol: default_protocol(),
             lightning: None,
             ordinals: None,
             stacks: None,
@@ -181,7 +981,33 @@ impl Default for TradingConfig {
         Self {
             auto_execute: default_auto_execute(),
             require_confirmation: default_require_confirmation(),
+            approval_timeout_secs: default_approval_timeout(),
+            approval_channels: default_approval_channels(),
+            approval_listen_addr: default_approval_listen_addr(),
+            approval_webhook_secret: None,
+            estimated_fee_rate: Decimal::ZERO,
             max_concurrent_trades: default_max_concurrent(),
+            max_queue_age_secs: default_max_queue_age(),
+            polymarket_requests_per_min: default_polymarket_requests_per_min(),
+            polymarket_orders_per_min: default_polymarket_orders_per_min(),
+            btc_requests_per_min: default_btc_requests_per_min(),
+            btc_orders_per_min: default_btc_orders_per_min(),
+            execution_strategy: default_execution_strategy(),
+            thin_leg: default_thin_leg(),
+            polymarket_leg_timeout_secs: default_polymarket_leg_timeout(),
+            btc_leg_timeout_secs: default_btc_leg_timeout(),
+            time_in_force: default_time_in_force(),
+            dry_run: false,
+            paper_trading: false,
+            paper_starting_balance: default_paper_balance(),
+            paper_fee_rate: Decimal::ZERO,
+            paper_latency_ms: default_paper_latency(),
+            idempotency_window_secs: default_idempotency_window(),
+            journal_path: default_journal_path(),
+            positions_db_path: default_positions_db_path(),
+            halt_state_path: default_halt_state_path(),
+            kill_file: default_kill_file(),
+            admin_listen_addr: default_admin_listen_addr(),
         }
     }
 }
@@ -192,6 +1018,91 @@ impl Default for RiskConfig {
             max_daily_loss: default_max_daily_loss(),
             stop_on_max_loss: default_stop_on_loss(),
             max_open_positions: default_max_positions(),
+            max_market_exposure: default_max_market_exposure(),
+            max_venue_exposure: default_max_venue_exposure(),
+            min_confidence: default_min_confidence(),
+            max_quote_age_secs: default_max_quote_age(),
+            max_resolution_gap_hours: default_max_resolution_gap(),
+            unwind_max_loss: default_unwind_max_loss(),
+            unwind_price_step: default_unwind_price_step(),
+            unwind_max_attempts: default_unwind_max_attempts(),
+            breaker_max_failed_legs: default_breaker_max_failed_legs(),
+            breaker_max_api_errors: default_breaker_max_api_errors(),
+            breaker_max_price_jump: default_breaker_max_price_jump(),
+            pnl_ledger_path: default_pnl_ledger_path(),
+            daily_reset_time: default_daily_reset_time(),
+            daily_reset_timezone: default_daily_reset_timezone(),
+            alert_webhook_url: None,
+        }
+    }
+}
+
+impl Default for TreasuryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_treasury_enabled(),
+            max_balance_age_secs: default_max_balance_age(),
+            pending_reservation_ttl_secs: default_pending_reservation_ttl(),
+            btc_usd_price: default_btc_usd_price(),
+            btc_usd_url: default_btc_usd_url(),
+            stx_usd_price: default_stx_usd_price(),
+            rsk_collateral_usd_price: default_rsk_collateral_usd_price(),
+        }
+    }
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_rebalance_enabled(),
+            check_interval_secs: default_rebalance_interval(),
+            polymarket_min_usd: default_band_min(),
+            polymarket_target_usd: default_band_target(),
+            polymarket_max_usd: default_band_max(),
+            btc_min_usd: default_band_min(),
+            btc_target_usd: default_band_target(),
+            btc_max_usd: default_band_max(),
+            depleted_usd: default_depleted_usd(),
+            min_transfer_usd: default_min_transfer(),
+            max_cost_rate: default_max_cost_rate(),
+            exchange_fee_rate: default_exchange_fee_rate(),
+            polygon_gas_usd: default_polygon_gas(),
+            usdc_withdraw_fee_usd: default_usdc_withdraw_fee(),
+            btc_withdraw_fee_usd: default_btc_withdraw_fee(),
+            onchain_fee_usd: default_onchain_fee(),
+            swap_fee_rate: default_swap_fee_rate(),
+        }
+    }
+}
+
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_resolver_enabled(),
+            poll_interval_secs: default_resolver_interval(),
+        }
+    }
+}
+
+impl Default for ValuationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_valuation_enabled(),
+            mark_method: default_mark_method(),
+            interval_secs: default_valuation_interval(),
+            max_mark_age_secs: default_max_mark_age(),
+            series_path: default_valuation_series_path(),
+        }
+    }
+}
+
+impl Default for ReconcileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_reconcile_enabled(),
+            interval_secs: default_reconcile_interval(),
+            policy: default_reconcile_policy(),
+            quantity_tolerance: default_quantity_tolerance(),
         }
     }
 }
@@ -222,6 +1133,54 @@ fn default_network() -> String {
     "polygon".to_string()
 }
 
+fn default_polygon_rpc_url() -> String {
+    "https://polygon-rpc.com".to_string()
+}
+
+fn default_protocol() -> String {
+    "lightning".to_string()
+}
+
+fn default_stacks_network() -> String {
+    "mainnet".to_string()
+}
+
+fn default_stacks_market_function() -> String {
+    "buy-outcome".to_string()
+}
+
+fn default_rsk_network() -> String {
+    "mainnet".to_string()
+}
+
+fn default_collateral_decimals() -> u32 {
+    18
+}
+
+fn default_rsk_market_function() -> String {
+    "buyOutcome(string,string,uint256,uint256)".to_string()
+}
+
+fn default_liquid_confirmations() -> u64 {
+    2
+}
+
+fn default_predyx_url() -> String {
+    "https://beta.predyx.com/api/v1".to_string()
+}
+
+fn default_esplora_url() -> String {
+    "https://mempool.space/api".to_string()
+}
+
+fn default_bitcoin_network() -> String {
+    "bitcoin".to_string()
+}
+
+fn default_fee_rate() -> u64 {
+    10
+}
+
 fn default_auto_execute() -> bool {
     false
 }
@@ -230,10 +1189,94 @@ fn default_require_confirmation() -> bool {
     true
 }
 
+fn default_approval_timeout() -> u64 {
+    300
+}
+
+fn default_approval_channels() -> Vec<String> {
+    vec!["terminal".to_string()]
+}
+
+fn default_approval_listen_addr() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
 fn default_max_concurrent() -> u32 {
     3
 }
 
+fn default_max_queue_age() -> u64 {
+    30
+}
+
+fn default_polymarket_requests_per_min() -> u32 {
+    300
+}
+
+fn default_polymarket_orders_per_min() -> u32 {
+    60
+}
+
+fn default_btc_requests_per_min() -> u32 {
+    60
+}
+
+fn default_btc_orders_per_min() -> u32 {
+    6
+}
+
+fn default_execution_strategy() -> String {
+    "thin_side_first".to_string()
+}
+
+fn default_thin_leg() -> String {
+    "btc".to_string()
+}
+
+fn default_polymarket_leg_timeout() -> u64 {
+    30
+}
+
+fn default_btc_leg_timeout() -> u64 {
+    600
+}
+
+fn default_time_in_force() -> String {
+    "ioc".to_string()
+}
+
+fn default_paper_balance() -> Decimal {
+    Decimal::from(10000)
+}
+
+fn default_paper_latency() -> u64 {
+    500
+}
+
+fn default_idempotency_window() -> u64 {
+    300
+}
+
+fn default_journal_path() -> String {
+    "data/trade_journal.jsonl".to_string()
+}
+
+fn default_positions_db_path() -> String {
+    "data/positions.db".to_string()
+}
+
+fn default_halt_state_path() -> String {
+    "data/halt_state.json".to_string()
+}
+
+fn default_kill_file() -> String {
+    "data/KILL".to_string()
+}
+
+fn default_admin_listen_addr() -> String {
+    "127.0.0.1:8788".to_string()
+}
+
 fn default_max_daily_loss() -> Decimal {
     Decimal::from_str("500.0").unwrap_or(Decimal::from(500))
 }
@@ -246,6 +1289,190 @@ fn default_max_positions() -> u32 {
     5
 }
 
+fn default_max_market_exposure() -> Decimal {
+    Decimal::from(1000)
+}
+
+fn default_max_venue_exposure() -> Decimal {
+    Decimal::from(5000)
+}
+
+fn default_min_confidence() -> f64 {
+    0.8
+}
+
+fn default_max_quote_age() -> u64 {
+    30
+}
+
+fn default_max_resolution_gap() -> u64 {
+    24
+}
+
+fn default_unwind_max_loss() -> Decimal {
+    Decimal::from(25)
+}
+
+fn default_unwind_price_step() -> Decimal {
+    Decimal::from_str("0.01").unwrap_or(Decimal::ZERO)
+}
+
+fn default_unwind_max_attempts() -> u32 {
+    3
+}
+
+fn default_breaker_max_failed_legs() -> u32 {
+    3
+}
+
+fn default_breaker_max_api_errors() -> u32 {
+    5
+}
+
+fn default_breaker_max_price_jump() -> Decimal {
+    Decimal::new(25, 2)
+}
+
+fn default_pnl_ledger_path() -> String {
+    "data/pnl_ledger.jsonl".to_string()
+}
+
+fn default_daily_reset_time() -> String {
+    "00:00".to_string()
+}
+
+fn default_daily_reset_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_treasury_enabled() -> bool {
+    true
+}
+
+fn default_max_balance_age() -> u64 {
+    60
+}
+
+fn default_pending_reservation_ttl() -> u64 {
+    3600
+}
+
+fn default_btc_usd_price() -> Decimal {
+    Decimal::from(60_000)
+}
+
+fn default_btc_usd_url() -> String {
+    "https://api.coinbase.com/v2/prices/BTC-USD/spot".to_string()
+}
+
+fn default_stx_usd_price() -> Decimal {
+    Decimal::new(150, 2)
+}
+
+fn default_rsk_collateral_usd_price() -> Decimal {
+    Decimal::ONE
+}
+
+fn default_resolver_enabled() -> bool {
+    true
+}
+
+fn default_resolver_interval() -> u64 {
+    300
+}
+
+fn default_valuation_enabled() -> bool {
+    true
+}
+
+fn default_mark_method() -> String {
+    "mid".to_string()
+}
+
+fn default_valuation_interval() -> u64 {
+    30
+}
+
+fn default_max_mark_age() -> u64 {
+    120
+}
+
+fn default_valuation_series_path() -> String {
+    "data/valuations.jsonl".to_string()
+}
+
+fn default_reconcile_enabled() -> bool {
+    true
+}
+
+fn default_reconcile_interval() -> u64 {
+    600
+}
+
+fn default_reconcile_policy() -> String {
+    "alert".to_string()
+}
+
+fn default_quantity_tolerance() -> Decimal {
+    Decimal::new(1, 4)
+}
+
+fn default_rebalance_enabled() -> bool {
+    true
+}
+
+fn default_rebalance_interval() -> u64 {
+    300
+}
+
+fn default_band_min() -> Decimal {
+    Decimal::from(500)
+}
+
+fn default_band_target() -> Decimal {
+    Decimal::from(2500)
+}
+
+fn default_band_max() -> Decimal {
+    Decimal::from(5000)
+}
+
+fn default_depleted_usd() -> Decimal {
+    Decimal::from(100)
+}
+
+fn default_min_transfer() -> Decimal {
+    Decimal::from(100)
+}
+
+fn default_max_cost_rate() -> Decimal {
+    Decimal::new(2, 2)
+}
+
+fn default_exchange_fee_rate() -> Decimal {
+    Decimal::new(1, 3)
+}
+
+fn default_polygon_gas() -> Decimal {
+    Decimal::new(5, 2)
+}
+
+fn default_usdc_withdraw_fee() -> Decimal {
+    Decimal::ONE
+}
+
+fn default_btc_withdraw_fee() -> Decimal {
+    Decimal::from(5)
+}
+
+fn default_onchain_fee() -> Decimal {
+    Decimal::from(3)
+}
+
+fn default_swap_fee_rate() -> Decimal {
+    Decimal::new(5, 3)
+}
+
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -253,3 +1480,129 @@ fn default_log_level() -> String {
 fn default_log_file() -> String {
     "/var/log/polymarket-btc-arb.log".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_example_config_round_trips() {
+        let config = Config::layered(Some(include_str!("../config.example.toml")), vec![]).unwrap();
+        let rsk = config.bitcoin.rsk.as_ref().unwrap();
+        assert_eq!(rsk.rpc_url.as_deref(), Some("https://public-node.rsk.co"));
+        assert_eq!(config.bitcoin.stacks.as_ref().unwrap().network, "mainnet");
+        assert_eq!(config.bitcoin.ordinals.as_ref().unwrap().address.as_deref(), Some("bc1q..."));
+        assert_eq!(config.reconcile.policy, "alert");
+
+        let written = toml::to_string(&config).unwrap();
+        let reread = Config::layered(Some(&written), vec![]).unwrap();
+        assert_eq!(toml::to_string(&reread).unwrap(), written);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let err = Config::layered(Some("[trading]\nauto_exec = true\n"), vec![]).unwrap_err();
+        assert!(err.to_string().contains("auto_exec"), "{}", err);
+        let err = Config::layered(None, env(&[("POLYARB_RISK__MAX_LOSS", "10")])).unwrap_err();
+        assert!(format!("{:#}", err).contains("POLYARB_RISK__MAX_LOSS"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_validate_accepts_complete_config() {
+        let file = "[bitcoin.lightning]\nendpoint = \"localhost:10009\"\n";
+        let config = Config::layered(Some(file), vec![]).unwrap();
+        assert_eq!(config.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_validate_collects_every_problem() {
+        let file = r#"
+[general]
+min_profit_threshold = 0.02
+max_slippage = 0.05
+
+[polymarket]
+private_key = "0xnothex"
+
+[bitcoin]
+protocol = "rsk"
+
+[bitcoin.lightning]
+macaroon_path = "/nonexistent/admin.macaroon"
+
+[trading]
+auto_execute = true
+
+[risk]
+min_confidence = 1.5
+"#;
+        let config = Config::layered(Some(file), vec![]).unwrap();
+        let keys: Vec<String> = config.validate().into_iter().map(|p| p.key).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "general.max_slippage",
+                "polymarket.private_key",
+                "bitcoin.rsk",
+                "trading.auto_execute",
+                "risk.min_confidence",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_btc_leg_timeout_must_outlast_confirmation() {
+        let file = "[bitcoin]\nprotocol = \"stacks\"\n\n[bitcoin.stacks]\n\n[trading]\nauto_execute = true\ndry_run = true\n";
+        let config = Config::layered(Some(file), vec![]).unwrap();
+        let keys: Vec<String> = config.validate().into_iter().map(|p| p.key).collect();
+        assert_eq!(keys, vec!["trading.btc_leg_timeout_secs"]);
+
+        let config = Config::layered(Some(file), env(&[("POLYARB_TRADING__BTC_LEG_TIMEOUT_SECS", "3600")])).unwrap();
+        assert_eq!(config.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_paper_trading_still_needs_wallets() {
+        let file = r#"
+[bitcoin]
+protocol = "ordinals"
+
+[bitcoin.ordinals]
+endpoint = "http://localhost:80"
+parent_inscription = "abc"
+
+[trading]
+auto_execute = true
+paper_trading = true
+"#;
+        let config = Config::layered(Some(file), vec![]).unwrap();
+        let keys: Vec<String> = config.validate().into_iter().map(|p| p.key).collect();
+        assert_eq!(keys, vec!["trading.auto_execute", "trading.auto_execute"]);
+    }
+
+    #[test]
+    fn test_environment_overrides_file() {
+        let file = "[trading]\nauto_execute = false\n\n[bitcoin.rsk]\nrsk_rpc_url = \"http://localhost:4444\"\n";
+        let config = Config::layered(
+            Some(file),
+            env(&[
+                ("POLYARB_TRADING__AUTO_EXECUTE", "true"),
+                ("POLYARB_GENERAL__MIN_PROFIT_THRESHOLD", "0.1"),
+                ("POLYARB_BITCOIN__RSK__PRIVATE_KEY", "0xabc"),
+                ("HOME", "/root"),
+            ]),
+        )
+        .unwrap();
+
+        assert!(config.trading.auto_execute);
+        assert_eq!(config.general.min_profit_threshold, Decimal::new(1, 1));
+        let rsk = config.bitcoin.rsk.unwrap();
+        assert_eq!(rsk.rpc_url.as_deref(), Some("http://localhost:4444"));
+        assert_eq!(rsk.private_key.as_deref(), Some("0xabc"));
+        assert_eq!(config.risk.max_open_positions, default_max_positions());
+    }
+}
@@ -0,0 +1,580 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::ledger::{LedgerKind, PnlLedger};
+use crate::pnl::PnlCalculator;
+use crate::position_store::{PositionStore, SqlitePositionStore};
+use crate::positions::{Position, PositionLeg, PositionStatus, TradeSide};
+use crate::trader::Leg;
+use crate::treasury::{Asset, Treasury};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportKind {
+    Fills,
+    Positions,
+    Settlements,
+    DailyPnl,
+}
+
+impl ExportKind {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "fills" => Ok(Self::Fills),
+            "positions" => Ok(Self::Positions),
+            "settlements" => Ok(Self::Settlements),
+            "daily-pnl" => Ok(Self::DailyPnl),
+            other => Err(anyhow::anyhow!("Unknown export: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::Jsonl),
+            other => Err(anyhow::anyhow!("Unknown export format: {}", other)),
+        }
+    }
+}
+
+/// Which records to export. Dates are inclusive UTC days; daily PnL uses
+/// trading days and ignores venue and status, which the ledger doesn't record.
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub venue: Option<Leg>,
+    pub status: Option<PositionStatus>,
+}
+
+impl ExportFilter {
+    fn in_range(&self, day: NaiveDate) -> bool {
+        self.from.is_none_or(|from| day >= from) && self.to.is_none_or(|to| day <= to)
+    }
+
+    /// Whether the position was open at any time in the range
+    fn overlaps(&self, position: &Position) -> bool {
+        self.to.is_none_or(|to| position.opened_at.date_naive() <= to)
+            && self.from.is_none_or(|from| position.closed_at.is_none_or(|closed| closed.date_naive() >= from))
+    }
+
+    fn status_matches(&self, position: &Position) -> bool {
+        self.status.as_ref().is_none_or(|status| *status == position.status)
+    }
+
+    fn venue_matches(&self, venue: Leg) -> bool {
+        self.venue.is_none_or(|v| v == venue)
+    }
+}
+
+/// A record with a fixed column order. JSON Lines output uses the same
+/// names, so both formats share one schema.
+pub trait ExportRow: Serialize {
+    const COLUMNS: &'static [&'static str];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FillRow {
+    pub filled_at: DateTime<Utc>,
+    pub position_id: String,
+    pub venue: String,
+    pub market_id: String,
+    pub outcome: String,
+    pub side: String,
+    pub tx_id: Option<String>,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub cost_usd: Decimal,
+    pub cost_sats: Decimal,
+}
+
+impl ExportRow for FillRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "filled_at", "position_id", "venue", "market_id", "outcome", "side", "tx_id", "price", "size", "cost_usd",
+        "cost_sats",
+    ];
+}
+
+/// One leg of a position as it stands
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionRow {
+    pub position_id: String,
+    pub opened_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub status: String,
+    pub position_type: String,
+    pub venue: String,
+    pub market_id: String,
+    pub outcome: String,
+    pub token_id: Option<String>,
+    pub side: String,
+    pub quantity: Decimal,
+    pub avg_cost: Decimal,
+    pub fees_usd: Decimal,
+    pub cost_basis_usd: Decimal,
+    pub cost_basis_sats: Decimal,
+}
+
+impl ExportRow for PositionRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "position_id", "opened_at", "closed_at", "status", "position_type", "venue", "market_id", "outcome",
+        "token_id", "side", "quantity", "avg_cost", "fees_usd", "cost_basis_usd", "cost_basis_sats",
+    ];
+}
+
+/// One leg of a closed position, at the price it was sold or settled at
+#[derive(Debug, Clone, Serialize)]
+pub struct SettlementRow {
+    pub closed_at: DateTime<Utc>,
+    pub position_id: String,
+    pub venue: String,
+    pub market_id: String,
+    pub outcome: String,
+    pub side: String,
+    pub quantity: Decimal,
+    pub exit_price: Decimal,
+    pub cost_basis_usd: Decimal,
+    pub cost_basis_sats: Decimal,
+    pub proceeds_usd: Decimal,
+    pub proceeds_sats: Decimal,
+    pub pnl_usd: Decimal,
+    pub pnl_sats: Decimal,
+    pub tx_id: Option<String>,
+}
+
+impl ExportRow for SettlementRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "closed_at", "position_id", "venue", "market_id", "outcome", "side", "quantity", "exit_price",
+        "cost_basis_usd", "cost_basis_sats", "proceeds_usd", "proceeds_sats", "pnl_usd", "pnl_sats", "tx_id",
+    ];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyPnlRow {
+    pub day: NaiveDate,
+    pub realized_usd: Decimal,
+    pub fees_usd: Decimal,
+    pub unwind_cost_usd: Decimal,
+    pub net_usd: Decimal,
+    pub net_sats: Decimal,
+}
+
+impl ExportRow for DailyPnlRow {
+    const COLUMNS: &'static [&'static str] =
+        &["day", "realized_usd", "fees_usd", "unwind_cost_usd", "net_usd", "net_sats"];
+}
+
+/// Builds export rows. Sats use a leg's own entry and exit BTC/USD rates
+/// where it has them, and `btc_usd` otherwise.
+pub struct Exporter {
+    btc_usd: Decimal,
+}
+
+impl Exporter {
+    pub fn new(btc_usd: Decimal) -> Self {
+        Self { btc_usd }
+    }
+
+    fn sats(&self, usd: Decimal, rate: Option<Decimal>) -> Decimal {
+        PnlCalculator::new(rate.unwrap_or(self.btc_usd)).to_sats(usd)
+    }
+
+    pub fn fills(&self, store: &dyn PositionStore, filter: &ExportFilter) -> Result<Vec<FillRow>> {
+        let mut rows = Vec::new();
+        for position in store.all_positions()?.iter().filter(|p| filter.status_matches(p)) {
+            for fill in store.fills(&position.id)? {
+                if !filter.venue_matches(fill.leg) || !filter.in_range(fill.filled_at.date_naive()) {
+                    continue;
+                }
+                let leg = position.leg(fill.leg);
+                let cost = fill.price * fill.size;
+                rows.push(FillRow {
+                    filled_at: fill.filled_at,
+                    position_id: fill.position_id.clone(),
+                    venue: fill.leg.to_string(),
+                    market_id: leg.map(|l| l.market_id.clone()).unwrap_or_else(|| position.market_id.clone()),
+                    outcome: leg.map(|l| l.outcome.clone()).unwrap_or_default(),
+                    side: leg.map(|l| side_name(&l.side)).unwrap_or_default(),
+                    tx_id: fill.tx_id,
+                    price: fill.price,
+                    size: fill.size,
+                    cost_usd: cost,
+                    cost_sats: self.sats(cost, leg.and_then(|l| l.entry_btc_usd)),
+                });
+            }
+        }
+        rows.sort_by_key(|row| row.filled_at);
+        Ok(rows)
+    }
+
+    pub fn positions(&self, positions: &[Position], filter: &ExportFilter) -> Vec<PositionRow> {
+        self.legs(positions, filter, |p| filter.overlaps(p))
+            .map(|(position, leg)| PositionRow {
+                position_id: position.id.clone(),
+                opened_at: position.opened_at,
+                closed_at: position.closed_at,
+                status: format!("{:?}", position.status).to_lowercase(),
+                position_type: format!("{:?}", position.position_type).to_lowercase(),
+                venue: leg.venue.to_string(),
+                market_id: leg.market_id.clone(),
+                outcome: leg.outcome.clone(),
+                token_id: leg.token_id.clone(),
+                side: side_name(&leg.side),
+                quantity: leg.quantity,
+                avg_cost: leg.avg_cost,
+                fees_usd: leg.fees,
+                cost_basis_usd: leg.cost_basis(),
+                cost_basis_sats: self.sats(leg.cost_basis(), leg.entry_btc_usd),
+            })
+            .collect()
+    }
+
+    pub fn settlements(&self, positions: &[Position], filter: &ExportFilter) -> Vec<SettlementRow> {
+        let closed_in_range = |p: &Position| p.closed_at.is_some_and(|closed| filter.in_range(closed.date_naive()));
+        self.legs(positions, filter, closed_in_range)
+            .filter_map(|(position, leg)| {
+                let exit = leg.exit_price?;
+                let closed_at = position.closed_at?;
+                let sign = match leg.side {
+                    TradeSide::Long => Decimal::ONE,
+                    TradeSide::Short => -Decimal::ONE,
+                };
+                let fx = leg.exit_btc_usd.map(|rate| leg.fx_pnl(exit, rate)).unwrap_or_default();
+                let proceeds = sign * leg.quantity * exit + fx;
+                let cost_basis = leg.cost_basis();
+                let cost_basis_sats = self.sats(cost_basis, leg.entry_btc_usd);
+                let proceeds_sats = self.sats(proceeds, leg.exit_btc_usd);
+                Some(SettlementRow {
+                    closed_at,
+                    position_id: position.id.clone(),
+                    venue: leg.venue.to_string(),
+                    market_id: leg.market_id.clone(),
+                    outcome: leg.outcome.clone(),
+                    side: side_name(&leg.side),
+                    quantity: leg.quantity,
+                    exit_price: exit,
+                    cost_basis_usd: cost_basis,
+                    cost_basis_sats,
+                    proceeds_usd: proceeds,
+                    proceeds_sats,
+                    pnl_usd: proceeds - cost_basis,
+                    pnl_sats: proceeds_sats - cost_basis_sats,
+                    tx_id: leg.tx_ids.last().cloned(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn daily_pnl(&self, ledger: &PnlLedger, filter: &ExportFilter) -> Vec<DailyPnlRow> {
+        let day = ledger.trading_day();
+        let mut totals: BTreeMap<NaiveDate, [Decimal; 3]> = BTreeMap::new();
+        for entry in ledger.entries() {
+            let slot = match entry.kind {
+                LedgerKind::Realized => 0,
+                LedgerKind::Fee => 1,
+                LedgerKind::UnwindCost => 2,
+            };
+            totals.entry(day.day_of(entry.at)).or_default()[slot] += entry.amount;
+        }
+
+        totals
+            .into_iter()
+            .filter(|(day, _)| filter.in_range(*day))
+            .map(|(day, [realized, fees, unwind_cost])| {
+                let net = realized + fees + unwind_cost;
+                DailyPnlRow {
+                    day,
+                    realized_usd: realized,
+                    fees_usd: fees,
+                    unwind_cost_usd: unwind_cost,
+                    net_usd: net,
+                    net_sats: self.sats(net, None),
+                }
+            })
+            .collect()
+    }
+
+    fn legs<'a>(
+        &self,
+        positions: &'a [Position],
+        filter: &'a ExportFilter,
+        include: impl Fn(&Position) -> bool + 'a,
+    ) -> impl Iterator<Item = (&'a Position, &'a PositionLeg)> + 'a {
+        positions
+            .iter()
+            .filter(move |p| filter.status_matches(p) && include(p))
+            .flat_map(|p| p.legs.iter().map(move |leg| (p, leg)))
+            .filter(move |(_, leg)| filter.venue_matches(leg.venue))
+    }
+}
+
+fn side_name(side: &TradeSide) -> String {
+    format!("{:?}", side).to_lowercase()
+}
+
+/// Write rows in `format`; CSV starts with a header row
+pub fn write_rows<T: ExportRow>(rows: &[T], format: ExportFormat, out: &mut dyn Write) -> Result<()> {
+    match format {
+        ExportFormat::Csv => {
+            writeln!(out, "{}", T::COLUMNS.join(","))?;
+            for row in rows {
+                let value = serde_json::to_value(row)?;
+                let fields: Vec<String> = T::COLUMNS
+                    .iter()
+                    .map(|column| match &value[*column] {
+                        serde_json::Value::Null => String::new(),
+                        serde_json::Value::String(s) => csv_field(s),
+                        other => csv_field(&other.to_string()),
+                    })
+                    .collect();
+                writeln!(out, "{}", fields.join(","))?;
+            }
+        }
+        ExportFormat::Jsonl => {
+            for row in rows {
+                writeln!(out, "{}", serde_json::to_string(row)?)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `export` command line: `<kind> [--format csv|jsonl] [--from YYYY-MM-DD]
+/// [--to YYYY-MM-DD] [--venue polymarket|btc] [--status open|closed|failed|partial] [--out PATH]`
+#[derive(Debug, Clone)]
+pub struct ExportArgs {
+    pub kind: ExportKind,
+    pub format: ExportFormat,
+    pub filter: ExportFilter,
+    /// Standard output when unset
+    pub out: Option<PathBuf>,
+}
+
+impl ExportArgs {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let (kind, flags) = args
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("Export kind required: fills, positions, settlements or daily-pnl"))?;
+        let mut parsed = Self {
+            kind: ExportKind::from_name(kind)?,
+            format: ExportFormat::Csv,
+            filter: ExportFilter::default(),
+            out: None,
+        };
+
+        let mut flags = flags.iter();
+        while let Some(flag) = flags.next() {
+            let value = flags
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Missing value for {}", flag))?;
+            let date = || {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d").with_context(|| format!("Invalid date for {}: {}", flag, value))
+            };
+            match flag.as_str() {
+                "--format" => parsed.format = ExportFormat::from_name(value)?,
+                "--from" => parsed.filter.from = Some(date()?),
+                "--to" => parsed.filter.to = Some(date()?),
+                "--venue" => parsed.filter.venue = Some(Leg::from_name(value)?),
+                "--status" => parsed.filter.status = Some(PositionStatus::from_name(value)?),
+                "--out" => parsed.out = Some(PathBuf::from(value)),
+                other => return Err(anyhow::anyhow!("Unknown export option: {}", other)),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Export from the position store and PnL ledger the config points at.
+/// Legs without recorded rates are converted at the current BTC/USD rate.
+pub async fn run(config: &Config, args: &ExportArgs) -> Result<()> {
+    let treasury = Treasury::from_config(config);
+    if let Err(e) = treasury.refresh_btc_usd().await {
+        tracing::warn!("Failed to fetch BTC/USD rate, using the configured price: {}", e);
+    }
+    let btc_usd = treasury.usd_price(Asset::Btc).unwrap_or(config.treasury.btc_usd_price);
+    let exporter = Exporter::new(btc_usd);
+    let mut out: Box<dyn Write> = match &args.out {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?,
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let store = || SqlitePositionStore::open(&config.trading.positions_db_path);
+    match args.kind {
+        ExportKind::Fills => write_rows(&exporter.fills(&store()?, &args.filter)?, args.format, &mut out)?,
+        ExportKind::Positions => {
+            write_rows(&exporter.positions(&store()?.all_positions()?, &args.filter), args.format, &mut out)?
+        }
+        ExportKind::Settlements => {
+            write_rows(&exporter.settlements(&store()?.all_positions()?, &args.filter), args.format, &mut out)?
+        }
+        ExportKind::DailyPnl => {
+            let ledger = PnlLedger::from_config(&config.risk)?;
+            write_rows(&exporter.daily_pnl(&ledger, &args.filter), args.format, &mut out)?
+        }
+    }
+    Ok(out.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::{LedgerEntry, TradingDay};
+    use crate::position_store::{Fill, InMemoryPositionStore, PositionUpdate};
+    use crate::positions::PositionType;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    /// Polymarket Yes at 0.45 hedged by No on the BTC venue at 0.50 (BTC at
+    /// 50k), settled two days later with Yes winning and BTC at 60k
+    fn settled() -> Position {
+        let leg = |venue, outcome: &str, avg_cost, exit, rate: Option<(i64, i64)>| PositionLeg {
+            venue,
+            market_id: "btc-100k".to_string(),
+            outcome: outcome.to_string(),
+            token_id: None,
+            side: TradeSide::Long,
+            quantity: Decimal::from(100),
+            avg_cost,
+            fees: Decimal::ONE,
+            tx_ids: vec![format!("{}-tx", venue)],
+            exit_price: Some(exit),
+            entry_btc_usd: rate.map(|(entry, _)| Decimal::from(entry)),
+            exit_btc_usd: rate.map(|(_, exit)| Decimal::from(exit)),
+        };
+        Position {
+            id: "p1".to_string(),
+            market_id: "btc-100k".to_string(),
+            market_question: "Yes".to_string(),
+            position_type: PositionType::Arbitrage,
+            legs: vec![
+                leg(Leg::Polymarket, "Yes", Decimal::new(45, 2), Decimal::ONE, None),
+                leg(Leg::Btc, "No", Decimal::new(50, 2), Decimal::ZERO, Some((50_000, 60_000))),
+            ],
+            status: PositionStatus::Closed,
+            opened_at: at(2, 12),
+            closed_at: Some(at(4, 9)),
+            pnl: Some(Decimal::from(3)),
+            unwind_cost: None,
+        }
+    }
+
+    fn export<T: ExportRow>(rows: &[T], format: ExportFormat) -> String {
+        let mut out = Vec::new();
+        write_rows(rows, format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_csv_snapshots() {
+        let exporter = Exporter::new(Decimal::from(60_000));
+        let positions = vec![settled()];
+        let mut store = InMemoryPositionStore::new();
+        store
+            .write(&PositionUpdate {
+                position: settled(),
+                transition: None,
+                fills: vec![Fill {
+                    position_id: "p1".to_string(),
+                    leg: Leg::Btc,
+                    tx_id: Some("btc-tx".to_string()),
+                    price: Decimal::new(50, 2),
+                    size: Decimal::from(100),
+                    filled_at: at(2, 12),
+                }],
+            })
+            .unwrap();
+        let filter = ExportFilter::default();
+
+        assert_eq!(
+            export(&exporter.fills(&store, &filter).unwrap(), ExportFormat::Csv),
+            "filled_at,position_id,venue,market_id,outcome,side,tx_id,price,size,cost_usd,cost_sats\n\
+             2026-03-02T12:00:00Z,p1,btc,btc-100k,No,long,btc-tx,0.50,100,50.00,100000\n"
+        );
+        assert_eq!(
+            export(&exporter.positions(&positions, &filter), ExportFormat::Csv),
+            "position_id,opened_at,closed_at,status,position_type,venue,market_id,outcome,token_id,side,quantity,\
+             avg_cost,fees_usd,cost_basis_usd,cost_basis_sats\n\
+             p1,2026-03-02T12:00:00Z,2026-03-04T09:00:00Z,closed,arbitrage,polymarket,btc-100k,Yes,,long,100,0.45,1,46.00,76667\n\
+             p1,2026-03-02T12:00:00Z,2026-03-04T09:00:00Z,closed,arbitrage,btc,btc-100k,No,,long,100,0.50,1,51.00,102000\n"
+        );
+        assert_eq!(
+            export(&exporter.settlements(&positions, &filter), ExportFormat::Csv),
+            "closed_at,position_id,venue,market_id,outcome,side,quantity,exit_price,cost_basis_usd,cost_basis_sats,\
+             proceeds_usd,proceeds_sats,pnl_usd,pnl_sats,tx_id\n\
+             2026-03-04T09:00:00Z,p1,polymarket,btc-100k,Yes,long,100,1,46.00,76667,100,166667,54.00,90000,polymarket-tx\n\
+             2026-03-04T09:00:00Z,p1,btc,btc-100k,No,long,100,0,51.00,102000,0,0,-51.00,-102000,btc-tx\n"
+        );
+    }
+
+    #[test]
+    fn test_filters_and_jsonl_daily_pnl() {
+        let exporter = Exporter::new(Decimal::from(50_000));
+        let positions = vec![settled()];
+        let btc_closed_on_the_4th = ExportFilter {
+            from: NaiveDate::from_ymd_opt(2026, 3, 4),
+            venue: Some(Leg::Btc),
+            ..Default::default()
+        };
+        assert_eq!(exporter.settlements(&positions, &btc_closed_on_the_4th).len(), 1);
+        let open_only = ExportFilter {
+            status: Some(PositionStatus::Open),
+            ..Default::default()
+        };
+        assert!(exporter.positions(&positions, &open_only).is_empty());
+        let before_open = ExportFilter {
+            to: NaiveDate::from_ymd_opt(2026, 3, 1),
+            ..Default::default()
+        };
+        assert!(exporter.positions(&positions, &before_open).is_empty());
+
+        let path = std::env::temp_dir().join(format!("export-ledger-{}.jsonl", uuid::Uuid::new_v4()));
+        let ledger = PnlLedger::open(&path, TradingDay::default()).unwrap();
+        for (kind, amount) in [(LedgerKind::Fee, Decimal::new(-2, 0)), (LedgerKind::Realized, Decimal::from(5))] {
+            ledger
+                .append(LedgerEntry { at: at(4, 9), kind, amount, position_id: Some("p1".to_string()) })
+                .unwrap();
+        }
+        assert_eq!(
+            export(&exporter.daily_pnl(&ledger, &ExportFilter::default()), ExportFormat::Jsonl),
+            "{\"day\":\"2026-03-04\",\"realized_usd\":\"5\",\"fees_usd\":\"-2\",\"unwind_cost_usd\":\"0\",\
+             \"net_usd\":\"3\",\"net_sats\":\"6000\"}\n"
+        );
+        std::fs::remove_file(&path).ok();
+
+        let args: Vec<String> = ["settlements", "--format", "jsonl", "--venue", "btc", "--status", "closed"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let parsed = ExportArgs::parse(&args).unwrap();
+        assert_eq!((parsed.kind, parsed.format), (ExportKind::Settlements, ExportFormat::Jsonl));
+        assert_eq!(parsed.filter.status, Some(PositionStatus::Closed));
+        assert!(ExportArgs::parse(&args[..2]).is_err());
+    }
+}
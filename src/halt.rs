@@ -0,0 +1,421 @@
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::time::Duration;
+
+use crate::config::{RiskConfig, TradingConfig};
+use crate::models::MarketOdds;
+use crate::trader::{Leg, LegOutcome};
+
+/// What stopped trading
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HaltSource {
+    KillFile,
+    Signal,
+    Admin,
+    /// A circuit breaker, by name
+    Breaker(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HaltEvent {
+    pub source: HaltSource,
+    pub reason: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Persisted kill-switch state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HaltState {
+    /// The halt in force, if trading is stopped
+    pub active: Option<HaltEvent>,
+    /// Every halt so far, oldest first
+    #[serde(default)]
+    pub history: Vec<HaltEvent>,
+}
+
+/// Global stop for all trading. The state is written to disk on every change,
+/// so a restart stays halted until someone resumes explicitly.
+pub struct KillSwitch {
+    path: PathBuf,
+    state: Mutex<HaltState>,
+    halted: watch::Sender<bool>,
+}
+
+impl KillSwitch {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let state: HaltState = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read halt state {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse halt state {}", path.display()))?
+        } else {
+            HaltState::default()
+        };
+
+        if let Some(active) = &state.active {
+            tracing::error!(
+                "Trading is halted since {} ({:?}): {}. Resume explicitly to trade again.",
+                active.at,
+                active.source,
+                active.reason
+            );
+        }
+        let (halted, _) = watch::channel(state.active.is_some());
+
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+            halted,
+        })
+    }
+
+    pub fn is_halted(&self) -> bool {
+        *self.halted.borrow()
+    }
+
+    pub fn state(&self) -> HaltState {
+        self.state.lock().expect("halt state lock poisoned").clone()
+    }
+
+    /// Fires on every halt or resume
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.halted.subscribe()
+    }
+
+    /// Stop trading. A second halt while halted is recorded but keeps the first reason active.
+    pub fn halt(&self, source: HaltSource, reason: &str) -> Result<()> {
+        let event = HaltEvent {
+            source,
+            reason: reason.to_string(),
+            at: Utc::now(),
+        };
+        tracing::error!("TRADING HALTED ({:?}): {}", event.source, event.reason);
+
+        let mut state = self.state.lock().expect("halt state lock poisoned");
+        state.history.push(event.clone());
+        if state.active.is_none() {
+            state.active = Some(event);
+        }
+        self.persist(&state)?;
+        self.halted.send_replace(true);
+        Ok(())
+    }
+
+    pub fn resume(&self, source: HaltSource) -> Result<()> {
+        let mut state = self.state.lock().expect("halt state lock poisoned");
+        if let Some(active) = state.active.take() {
+            tracing::warn!("Trading resumed ({:?}) after halt: {}", source, active.reason);
+        }
+        self.persist(&state)?;
+        self.halted.send_replace(false);
+        Ok(())
+    }
+
+    fn persist(&self, state: &HaltState) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so a crash never leaves a half-written state
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to persist halt state {}", self.path.display()))
+    }
+}
+
+/// Halt while `kill_file` exists; resume when it is removed, unless
+/// something else halted trading in the meantime
+pub fn spawn_kill_file_watch(switch: Arc<KillSwitch>, kill_file: PathBuf) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            let active = switch.state().active.map(|a| a.source);
+            let result = match (kill_file.exists(), active) {
+                (true, None) => switch.halt(HaltSource::KillFile, &format!("{} exists", kill_file.display())),
+                (false, Some(HaltSource::KillFile)) => switch.resume(HaltSource::KillFile),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                tracing::error!("Kill file watch: {}", e);
+            }
+        }
+    });
+}
+
+/// SIGUSR1 halts trading, SIGUSR2 resumes it
+#[cfg(unix)]
+pub fn spawn_signal_handlers(switch: Arc<KillSwitch>) -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut halt = signal(SignalKind::user_defined1())?;
+    let mut resume = signal(SignalKind::user_defined2())?;
+    tokio::spawn(async move {
+        loop {
+            let result = tokio::select! {
+                _ = halt.recv() => switch.halt(HaltSource::Signal, "SIGUSR1"),
+                _ = resume.recv() => switch.resume(HaltSource::Signal),
+            };
+            if let Err(e) = result {
+                tracing::error!("Kill switch signal: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn spawn_signal_handlers(_switch: Arc<KillSwitch>) -> Result<()> {
+    Ok(())
+}
+
+/// Start the kill file watch, signal handlers and admin endpoint from config
+pub fn spawn_controls(switch: Arc<KillSwitch>, config: &TradingConfig) {
+    if !config.kill_file.is_empty() {
+        spawn_kill_file_watch(switch.clone(), PathBuf::from(&config.kill_file));
+    }
+    if let Err(e) = spawn_signal_handlers(switch.clone()) {
+        tracing::error!("Failed to install kill switch signal handlers: {}", e);
+    }
+    if !config.admin_listen_addr.is_empty() {
+        let addr = config.admin_listen_addr.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_admin(switch, &addr).await {
+                tracing::error!("Admin endpoint on {} stopped: {}", addr, e);
+            }
+        });
+    }
+}
+
+/// `GET /admin/halt` for the state, `POST /admin/halt` (body: reason) and `POST /admin/resume`
+pub fn admin_router(switch: Arc<KillSwitch>) -> Router {
+    Router::new()
+        .route("/admin/halt", get(halt_status).post(halt_trading))
+        .route("/admin/resume", post(resume_trading))
+        .with_state(switch)
+}
+
+pub async fn serve_admin(switch: Arc<KillSwitch>, addr: &str) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Admin endpoint listening on {}", listener.local_addr()?);
+    axum::serve(listener, admin_router(switch)).await?;
+    Ok(())
+}
+
+async fn halt_status(State(switch): State<Arc<KillSwitch>>) -> Json<HaltState> {
+    Json(switch.state())
+}
+
+async fn halt_trading(State(switch): State<Arc<KillSwitch>>, reason: String) -> Result<Json<HaltState>, String> {
+    let reason = if reason.trim().is_empty() { "halted by operator".to_string() } else { reason };
+    switch.halt(HaltSource::Admin, &reason).map_err(|e| e.to_string())?;
+    Ok(Json(switch.state()))
+}
+
+async fn resume_trading(State(switch): State<Arc<KillSwitch>>) -> Result<Json<HaltState>, String> {
+    switch.resume(HaltSource::Admin).map_err(|e| e.to_string())?;
+    Ok(Json(switch.state()))
+}
+
+#[derive(Default)]
+struct BreakerCounters {
+    failed_legs: u32,
+    api_errors: u32,
+    last_odds: HashMap<(String, String), Decimal>,
+}
+
+/// Trips the kill switch on consecutive failed legs, abnormal price jumps,
+/// repeated API errors or the daily loss limit
+pub struct CircuitBreakers {
+    switch: Arc<KillSwitch>,
+    config: RiskConfig,
+    counters: Mutex<BreakerCounters>,
+}
+
+impl CircuitBreakers {
+    pub fn new(switch: Arc<KillSwitch>, config: RiskConfig) -> Self {
+        Self {
+            switch,
+            config,
+            counters: Mutex::new(BreakerCounters::default()),
+        }
+    }
+
+    pub fn kill_switch(&self) -> &Arc<KillSwitch> {
+        &self.switch
+    }
+
+    pub fn record_leg(&self, leg: Leg, outcome: &LegOutcome) {
+        let mut counters = self.counters.lock().expect("breaker lock poisoned");
+        if outcome.is_accepted() {
+            counters.failed_legs = 0;
+        } else {
+            counters.failed_legs += 1;
+        }
+        match outcome {
+            LegOutcome::Errored(_) => counters.api_errors += 1,
+            LegOutcome::Completed(_) => counters.api_errors = 0,
+            _ => {}
+        }
+
+        let (failed_legs, api_errors) = (counters.failed_legs, counters.api_errors);
+        drop(counters);
+        if failed_legs >= self.config.breaker_max_failed_legs {
+            self.trip("failed_legs", format!("{} consecutive failed legs, last on {}", failed_legs, leg));
+        }
+        self.check_api_errors(api_errors);
+    }
+
+    /// A venue request outside of order placement succeeded or failed
+    pub fn record_api(&self, ok: bool) {
+        let mut counters = self.counters.lock().expect("breaker lock poisoned");
+        counters.api_errors = if ok { 0 } else { counters.api_errors + 1 };
+        let api_errors = counters.api_errors;
+        drop(counters);
+        self.check_api_errors(api_errors);
+    }
+
+    /// Trip if a market's odds moved more than `breaker_max_price_jump` since its last quote
+    pub fn record_quote(&self, odds: &MarketOdds) {
+        let key = (odds.market_id.clone(), odds.outcome.clone());
+        let previous = self
+            .counters
+            .lock()
+            .expect("breaker lock poisoned")
+            .last_odds
+            .insert(key, odds.odds);
+
+        if let Some(previous) = previous {
+            let jump = (odds.odds - previous).abs();
+            if jump > self.config.breaker_max_price_jump {
+                self.trip(
+                    "price_jump",
+                    format!("{} {} jumped from {} to {}", odds.market_id, odds.outcome, previous, odds.odds),
+                );
+            }
+        }
+    }
+
+    pub fn record_daily_pnl(&self, pnl: Decimal) {
+        if self.config.stop_on_max_loss && -pnl >= self.config.max_daily_loss {
+            self.trip("daily_loss", format!("daily loss {} reached limit {}", -pnl, self.config.max_daily_loss));
+        }
+    }
+
+    fn check_api_errors(&self, api_errors: u32) {
+        if api_errors >= self.config.breaker_max_api_errors {
+            self.trip("api_errors", format!("{} consecutive API errors", api_errors));
+        }
+    }
+
+    fn trip(&self, breaker: &str, reason: String) {
+        if let Err(e) = self.switch.halt(HaltSource::Breaker(breaker.to_string()), &reason) {
+            tracing::error!("Failed to persist {} breaker trip: {}", breaker, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MarketSource;
+
+    fn state_path() -> PathBuf {
+        std::env::temp_dir().join(format!("halt-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    fn breakers(path: &Path) -> CircuitBreakers {
+        let config = RiskConfig {
+            breaker_max_failed_legs: 2,
+            breaker_max_api_errors: 3,
+            breaker_max_price_jump: Decimal::new(20, 2),
+            ..RiskConfig::default()
+        };
+        CircuitBreakers::new(Arc::new(KillSwitch::open(path).unwrap()), config)
+    }
+
+    #[test]
+    fn test_halt_survives_restart_until_resumed() {
+        let path = state_path();
+        KillSwitch::open(&path).unwrap().halt(HaltSource::Admin, "venue returning garbage").unwrap();
+
+        let reopened = KillSwitch::open(&path).unwrap();
+        assert!(reopened.is_halted());
+        assert_eq!(reopened.state().active.unwrap().reason, "venue returning garbage");
+
+        reopened.resume(HaltSource::Admin).unwrap();
+        let reopened = KillSwitch::open(&path).unwrap();
+        assert!(!reopened.is_halted());
+        assert_eq!(reopened.state().history.len(), 1);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_consecutive_failed_legs_trip() {
+        let path = state_path();
+        let breakers = breakers(&path);
+
+        breakers.record_leg(Leg::Btc, &LegOutcome::TimedOut);
+        breakers.record_leg(Leg::Btc, &LegOutcome::Skipped);
+        assert!(!breakers.kill_switch().is_halted());
+        breakers.record_leg(Leg::Polymarket, &LegOutcome::Skipped);
+        assert!(breakers.kill_switch().is_halted());
+        assert_eq!(
+            breakers.kill_switch().state().active.unwrap().source,
+            HaltSource::Breaker("failed_legs".to_string())
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_api_errors_and_price_jumps_trip() {
+        let path = state_path();
+        let breakers = breakers(&path);
+        for _ in 0..2 {
+            breakers.record_api(false);
+        }
+        breakers.record_api(true);
+        breakers.record_api(false);
+        assert!(!breakers.kill_switch().is_halted());
+
+        let mut odds = MarketOdds {
+            market_id: "m1".to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(40, 2),
+            source: MarketSource::Polymarket,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        breakers.record_quote(&odds);
+        odds.odds = Decimal::new(55, 2);
+        breakers.record_quote(&odds);
+        assert!(!breakers.kill_switch().is_halted());
+        odds.odds = Decimal::new(90, 2);
+        breakers.record_quote(&odds);
+        assert!(breakers.kill_switch().is_halted());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_daily_loss_trips() {
+        let path = state_path();
+        let breakers = breakers(&path);
+        breakers.record_daily_pnl(Decimal::from(-499));
+        assert!(!breakers.kill_switch().is_halted());
+        breakers.record_daily_pnl(Decimal::from(-500));
+        assert!(breakers.kill_switch().is_halted());
+        fs::remove_file(path).unwrap();
+    }
+}
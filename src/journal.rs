@@ -0,0 +1,344 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::models::ArbitrageOpportunity;
+use crate::trader::{Leg, LegOutcome, TradeConfig};
+
+/// One step in the life of an execution, keyed by its idempotency key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// Written before a leg is submitted
+    Intent {
+        leg: Leg,
+        client_order_id: String,
+        opportunity: Box<ArbitrageOpportunity>,
+        config: TradeConfig,
+    },
+    /// What the venue returned for a leg
+    LegResult { leg: Leg, outcome: LegOutcome },
+    /// Positions recorded; nothing left to recover
+    Settled { position_id: Option<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub key: String,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Journaled state of one leg of an unsettled execution
+#[derive(Debug, Clone)]
+pub struct JournaledLeg {
+    pub client_order_id: String,
+    /// `None` if the process stopped before the venue answered
+    pub outcome: Option<LegOutcome>,
+}
+
+/// An execution with intents but no `Settled` entry
+#[derive(Debug, Clone)]
+pub struct UnsettledExecution {
+    pub key: String,
+    pub opportunity: ArbitrageOpportunity,
+    pub config: TradeConfig,
+    pub legs: HashMap<Leg, JournaledLeg>,
+}
+
+/// Settlements between rewrites of the journal file
+const COMPACT_EVERY: usize = 100;
+
+/// Append-only JSON-lines write-ahead journal of trade intents.
+/// Every append is flushed to disk before it returns. Lookups are served from
+/// memory, and the file is rewritten without settled executions on open and
+/// every `COMPACT_EVERY` settlements.
+pub struct TradeJournal {
+    path: PathBuf,
+    file: File,
+    /// Every key journaled since open, settled or not
+    keys: HashSet<String>,
+    /// Entries of executions not yet settled, in write order
+    pending: BTreeMap<String, Vec<JournalEntry>>,
+    settled_since_compaction: usize,
+}
+
+impl TradeJournal {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create journal directory {}", parent.display()))?;
+        }
+
+        let entries = if path.exists() { read_entries(&path)? } else { Vec::new() };
+        let mut journal = Self {
+            file: open_append(&path)?,
+            path,
+            keys: HashSet::new(),
+            pending: BTreeMap::new(),
+            settled_since_compaction: 0,
+        };
+        for entry in entries {
+            journal.index(entry);
+        }
+        // Settled keys are about to leave the file; forget them now so a
+        // reopen answers the same before and after compaction
+        journal.keys = journal.pending.keys().cloned().collect();
+        journal.compact()?;
+        Ok(journal)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&mut self, key: &str, event: JournalEvent) -> Result<()> {
+        let entry = JournalEntry {
+            key: key.to_string(),
+            at: Utc::now(),
+            event,
+        };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()?;
+        self.index(entry);
+
+        if self.settled_since_compaction >= COMPACT_EVERY {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    fn index(&mut self, entry: JournalEntry) {
+        self.keys.insert(entry.key.clone());
+        if let JournalEvent::Settled { .. } = entry.event {
+            self.pending.remove(&entry.key);
+            self.settled_since_compaction += 1;
+        } else {
+            self.pending.entry(entry.key.clone()).or_default().push(entry);
+        }
+    }
+
+    /// Rewrite the file with only unsettled executions. The new file replaces
+    /// the old one by rename, so a crash leaves one or the other intact.
+    pub fn compact(&mut self) -> Result<()> {
+        let tmp = self.path.with_extension("compacting");
+        let mut file = File::create(&tmp)
+            .with_context(|| format!("Failed to create compacted journal {}", tmp.display()))?;
+        for entry in self.pending.values().flatten() {
+            let mut line = serde_json::to_string(entry)?;
+            line.push('\n');
+            file.write_all(line.as_bytes())?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace trade journal {}", self.path.display()))?;
+
+        self.file = open_append(&self.path)?;
+        self.settled_since_compaction = 0;
+        Ok(())
+    }
+
+    /// All entries in the file, in write order
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        read_entries(&self.path)
+    }
+
+    /// Whether any leg of this execution was journaled since open, or is
+    /// still unsettled from an earlier run
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// Executions that were started but never settled
+    pub fn unsettled(&self) -> Vec<UnsettledExecution> {
+        self.pending
+            .iter()
+            .filter_map(|(key, entries)| {
+                let mut execution: Option<UnsettledExecution> = None;
+                for entry in entries {
+                    match &entry.event {
+                        JournalEvent::Intent {
+                            leg,
+                            client_order_id,
+                            opportunity,
+                            config,
+                        } => {
+                            execution
+                                .get_or_insert_with(|| UnsettledExecution {
+                                    key: key.clone(),
+                                    opportunity: (**opportunity).clone(),
+                                    config: config.clone(),
+                                    legs: HashMap::new(),
+                                })
+                                .legs
+                                .insert(*leg, JournaledLeg { client_order_id: client_order_id.clone(), outcome: None });
+                        }
+                        JournalEvent::LegResult { leg, outcome } => {
+                            if let Some(journaled) = execution.as_mut().and_then(|e| e.legs.get_mut(leg)) {
+                                journaled.outcome = Some(outcome.clone());
+                            }
+                        }
+                        JournalEvent::Settled { .. } => {}
+                    }
+                }
+                execution
+            })
+            .collect()
+    }
+}
+
+fn open_append(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open trade journal {}", path.display()))
+}
+
+/// Entries of a journal file. A torn final line from a crash mid-write is skipped.
+fn read_entries(path: &Path) -> Result<Vec<JournalEntry>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => tracing::warn!("Skipping unreadable journal line {}: {}", n + 1, e),
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MarketOdds, MarketSource};
+    use crate::trader::{TimeInForce, TradeResult, TradeStatus};
+    use rust_decimal::Decimal;
+    use tokio::time::Duration;
+
+    fn opportunity() -> ArbitrageOpportunity {
+        let odds = |source| MarketOdds {
+            market_id: "m1".to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(45, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket),
+            odds(MarketSource::BitcoinPredictionMarket),
+            Decimal::new(5, 2),
+            0.9,
+        )
+    }
+
+    fn config(key: &str) -> TradeConfig {
+        TradeConfig {
+            size: Decimal::from(10),
+            max_size: Decimal::from(100),
+            limit_tolerance: Decimal::ZERO,
+            time_in_force: TimeInForce::ImmediateOrCancel,
+            polymarket_timeout: Duration::from_secs(1),
+            btc_timeout: Duration::from_secs(1),
+            dry_run: false,
+            idempotency_key: key.to_string(),
+        }
+    }
+
+    fn intent(key: &str, leg: Leg) -> JournalEvent {
+        JournalEvent::Intent {
+            leg,
+            client_order_id: config(key).client_order_id(leg),
+            opportunity: Box::new(opportunity()),
+            config: config(key),
+        }
+    }
+
+    #[test]
+    fn test_unsettled_survives_reopen() {
+        let dir = std::env::temp_dir().join(format!("journal-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("trades.jsonl");
+
+        {
+            let mut journal = TradeJournal::open(&path).unwrap();
+            journal.append("a", intent("a", Leg::Btc)).unwrap();
+            journal
+                .append(
+                    "a",
+                    JournalEvent::LegResult {
+                        leg: Leg::Btc,
+                        outcome: LegOutcome::Completed(TradeResult {
+                            polymarket_tx: None,
+                            btc_tx: Some("tx1".to_string()),
+                            status: TradeStatus::Success,
+                            executed_at: Utc::now(),
+                            error: None,
+                            position_id: None,
+                            token_id: None,
+                            fill_price: None,
+                            filled_size: None,
+                            fees: None,
+                        }),
+                    },
+                )
+                .unwrap();
+            journal.append("a", intent("a", Leg::Polymarket)).unwrap();
+            journal.append("b", intent("b", Leg::Btc)).unwrap();
+            journal.append("b", JournalEvent::Settled { position_id: None }).unwrap();
+        }
+
+        // Simulate a crash mid-append
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"key\":\"c\",\"at\":").unwrap();
+
+        let journal = TradeJournal::open(&path).unwrap();
+        let unsettled = journal.unsettled();
+        assert_eq!(unsettled.len(), 1);
+        assert_eq!(unsettled[0].key, "a");
+        assert!(unsettled[0].legs[&Leg::Btc].outcome.as_ref().unwrap().is_filled());
+        assert!(unsettled[0].legs[&Leg::Polymarket].outcome.is_none());
+        assert_eq!(unsettled[0].legs[&Leg::Polymarket].client_order_id, "a-polymarket");
+        assert!(journal.contains("a"));
+
+        // Reopening compacted away the settled execution and the torn line
+        assert!(!journal.contains("b"));
+        assert_eq!(journal.entries().unwrap().len(), 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_settled_executions_are_compacted_while_running() {
+        let path = std::env::temp_dir().join(format!("journal-{}.jsonl", uuid::Uuid::new_v4()));
+        let mut journal = TradeJournal::open(&path).unwrap();
+        journal.append("open", intent("open", Leg::Btc)).unwrap();
+
+        for n in 0..COMPACT_EVERY {
+            let key = format!("k{}", n);
+            journal.append(&key, intent(&key, Leg::Btc)).unwrap();
+            journal.append(&key, JournalEvent::Settled { position_id: None }).unwrap();
+        }
+
+        // Still known to this run, so never traded twice, but gone from disk
+        assert!(journal.contains("k0"));
+        assert_eq!(journal.entries().unwrap().len(), 1);
+        assert_eq!(journal.unsettled()[0].key, "open");
+
+        fs::remove_file(path).unwrap();
+    }
+}
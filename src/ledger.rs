@@ -0,0 +1,228 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::config::RiskConfig;
+
+/// What a ledger entry books
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerKind {
+    /// PnL of a closed position before fees and unwind costs, which are
+    /// booked when they are paid
+    Realized,
+    Fee,
+    UnwindCost,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub at: DateTime<Utc>,
+    pub kind: LedgerKind,
+    /// Signed USD amount: gains positive, fees and costs negative
+    pub amount: Decimal,
+    pub position_id: Option<String>,
+}
+
+/// Trading-day boundary: days start at `reset` in a fixed UTC offset.
+/// Offsets don't follow daylight saving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradingDay {
+    reset: NaiveTime,
+    offset: FixedOffset,
+}
+
+impl TradingDay {
+    pub fn new(reset: NaiveTime, offset: FixedOffset) -> Self {
+        Self { reset, offset }
+    }
+
+    /// `daily_reset_time` as "HH:MM" and `daily_reset_timezone` as "UTC" or "+HH:MM"
+    pub fn from_config(config: &RiskConfig) -> Result<Self> {
+        let reset = NaiveTime::parse_from_str(&config.daily_reset_time, "%H:%M")
+            .with_context(|| format!("Invalid daily reset time: {}", config.daily_reset_time))?;
+        let offset = match config.daily_reset_timezone.as_str() {
+            "UTC" | "utc" | "Z" => FixedOffset::east_opt(0).expect("zero offset is valid"),
+            other => other
+                .parse::<FixedOffset>()
+                .map_err(|e| anyhow::anyhow!("Invalid daily reset timezone {}: {}", other, e))?,
+        };
+        Ok(Self::new(reset, offset))
+    }
+
+    /// Trading day `at` falls in, named by the local date it starts on
+    pub fn day_of(&self, at: DateTime<Utc>) -> NaiveDate {
+        let local = at.with_timezone(&self.offset).naive_local();
+        (local - self.reset.signed_duration_since(NaiveTime::MIN)).date()
+    }
+}
+
+impl Default for TradingDay {
+    fn default() -> Self {
+        Self::new(NaiveTime::MIN, FixedOffset::east_opt(0).expect("zero offset is valid"))
+    }
+}
+
+/// Append-only JSON-lines ledger of realized PnL, fees and unwind costs.
+/// Entries are reloaded on open, so daily totals survive restarts.
+pub struct PnlLedger {
+    day: TradingDay,
+    file: Mutex<File>,
+    entries: Mutex<Vec<LedgerEntry>>,
+}
+
+impl PnlLedger {
+    pub fn open(path: impl AsRef<Path>, day: TradingDay) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create ledger directory {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open PnL ledger {}", path.display()))?;
+
+        let mut entries = Vec::new();
+        for (n, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => tracing::warn!("Skipping unreadable ledger line {}: {}", n + 1, e),
+            }
+        }
+
+        Ok(Self {
+            day,
+            file: Mutex::new(file),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    pub fn from_config(config: &RiskConfig) -> Result<Self> {
+        Self::open(&config.pnl_ledger_path, TradingDay::from_config(config)?)
+    }
+
+    pub fn trading_day(&self) -> TradingDay {
+        self.day
+    }
+
+    /// Book an amount now. Zero amounts are skipped.
+    pub fn record(&self, kind: LedgerKind, amount: Decimal, position_id: Option<&str>) -> Result<()> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        self.append(LedgerEntry {
+            at: Utc::now(),
+            kind,
+            amount,
+            position_id: position_id.map(str::to_string),
+        })
+    }
+
+    pub fn append(&self, entry: LedgerEntry) -> Result<()> {
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        {
+            let mut file = self.file.lock().expect("ledger lock poisoned");
+            file.write_all(line.as_bytes())?;
+            file.sync_data()?;
+        }
+        self.entries.lock().expect("ledger lock poisoned").push(entry);
+        Ok(())
+    }
+
+    /// Every entry booked, oldest first
+    pub fn entries(&self) -> Vec<LedgerEntry> {
+        self.entries.lock().expect("ledger lock poisoned").clone()
+    }
+
+    /// Sum of entries in the trading day containing `now`
+    pub fn day_total(&self, now: DateTime<Utc>) -> Decimal {
+        let today = self.day.day_of(now);
+        self.entries
+            .lock()
+            .expect("ledger lock poisoned")
+            .iter()
+            .filter(|e| self.day.day_of(e.at) == today)
+            .map(|e| e.amount)
+            .sum()
+    }
+
+    /// Net total of every trading day with entries
+    pub fn daily_totals(&self) -> BTreeMap<NaiveDate, Decimal> {
+        let mut totals: BTreeMap<NaiveDate, Decimal> = BTreeMap::new();
+        for entry in self.entries.lock().expect("ledger lock poisoned").iter() {
+            *totals.entry(self.day.day_of(entry.at)).or_default() += entry.amount;
+        }
+        totals
+    }
+
+    /// What can still be lost today before `max_daily_loss` is reached,
+    /// given `unrealized` PnL on open positions
+    pub fn remaining_loss_budget(&self, max_daily_loss: Decimal, unrealized: Decimal, now: DateTime<Utc>) -> Decimal {
+        (max_daily_loss + self.day_total(now) + unrealized).max(Decimal::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn ledger_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("pnl-ledger-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_trading_day_rolls_at_local_reset() {
+        // Days start at 17:00 New York standard time (22:00 UTC)
+        let day = TradingDay::new(NaiveTime::from_hms_opt(17, 0, 0).unwrap(), "-05:00".parse().unwrap());
+        let before = Utc.with_ymd_and_hms(2026, 3, 2, 21, 59, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 3, 2, 22, 0, 0).unwrap();
+        let past_midnight = Utc.with_ymd_and_hms(2026, 3, 3, 5, 30, 0).unwrap();
+
+        assert_eq!(day.day_of(before), NaiveDate::from_ymd_opt(2026, 3, 1).unwrap());
+        assert_eq!(day.day_of(after), NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+        assert_eq!(day.day_of(past_midnight), day.day_of(after));
+    }
+
+    #[test]
+    fn test_totals_survive_restart() {
+        let path = ledger_path();
+        let day = TradingDay::default();
+        let now = Utc::now();
+        {
+            let ledger = PnlLedger::open(&path, day).unwrap();
+            ledger.record(LedgerKind::Fee, Decimal::new(-150, 2), Some("p1")).unwrap();
+            ledger.record(LedgerKind::UnwindCost, Decimal::from(-20), Some("p1")).unwrap();
+            ledger
+                .append(LedgerEntry {
+                    at: now - chrono::Duration::days(1),
+                    kind: LedgerKind::Realized,
+                    amount: Decimal::from(-500),
+                    position_id: None,
+                })
+                .unwrap();
+        }
+
+        let ledger = PnlLedger::open(&path, day).unwrap();
+        assert_eq!(ledger.day_total(now), Decimal::new(-2150, 2));
+        assert_eq!(ledger.daily_totals().len(), 2);
+        assert_eq!(
+            ledger.remaining_loss_budget(Decimal::from(100), Decimal::from(-30), now),
+            Decimal::new(4850, 2)
+        );
+        std::fs::remove_file(&path).ok();
+    }
+}
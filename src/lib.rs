@@ -0,0 +1,26 @@
+pub mod alerts;
+pub mod api;
+pub mod approval;
+pub mod chains;
+pub mod config;
+pub mod export;
+pub mod halt;
+pub mod journal;
+pub mod ledger;
+pub mod matcher;
+pub mod models;
+pub mod monitor;
+pub mod paper;
+pub mod pnl;
+pub mod position_store;
+pub mod positions;
+pub mod ratelimit;
+pub mod reconcile;
+pub mod rebalance;
+pub mod resolver;
+pub mod risk;
+pub mod scheduler;
+pub mod trader;
+pub mod treasury;
+pub mod unwind;
+pub mod valuation;
@@ -1,14 +1,76 @@
-mod api;
-mod models;
-mod config;
-mod matcher;
-mod monitor;
+use polymarket_btc_arb::config::Config;
+use polymarket_btc_arb::export::{self, ExportArgs};
+use polymarket_btc_arb::monitor;
+
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+/// `--config PATH` must exist; without it, config.toml is used if present
+fn load_config(args: &mut Vec<String>) -> anyhow::Result<Config> {
+    match args.iter().position(|arg| arg == "--config") {
+        Some(i) => {
+            let path = args
+                .get(i + 1)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("--config needs a path"))?;
+            args.drain(i..=i + 1);
+            Config::load(path)
+        }
+        None => Config::load_or_default(DEFAULT_CONFIG_PATH),
+    }
+}
 
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt().init();
 
-    let monitor = match monitor::Monitor::new().await {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let config = match load_config(&mut args) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to load config: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if args.first().map(String::as_str) == Some("config") {
+        if args.get(1).map(String::as_str) != Some("check") {
+            eprintln!("Usage: polymarket_btc_arb [--config PATH] config check");
+            std::process::exit(2);
+        }
+        let problems = config.validate();
+        if problems.is_empty() {
+            println!("Config OK");
+            return;
+        }
+        for problem in &problems {
+            println!("{}", problem);
+        }
+        eprintln!("{} problem(s) found", problems.len());
+        std::process::exit(1);
+    }
+
+    if args.first().map(String::as_str) == Some("export") {
+        let exported = match ExportArgs::parse(&args[1..]) {
+            Ok(export_args) => export::run(&config, &export_args).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = exported {
+            eprintln!("Export failed: {:#}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let problems = config.validate();
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("{}", problem);
+        }
+        eprintln!("Refusing to start: {} config problem(s) found", problems.len());
+        std::process::exit(1);
+    }
+
+    let monitor = match monitor::Monitor::new(config).await {
         Ok(m) => m,
         Err(e) => {
             tracing::error!("Failed to initialize monitor: {}", e);
@@ -1,6 +1,5 @@
 use std::collections::HashSet;
 use crate::models::MarketOdds;
-use chrono::Utc;
 
 pub struct OutcomeMatcher {
     similarity_threshold: f64,
@@ -85,6 +84,7 @@ impl Default for OutcomeMatcher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
     use rust_decimal::Decimal;
     use std::str::FromStr;
 
@@ -95,6 +95,7 @@ mod tests {
             odds,
             source: crate::models::MarketSource::Polymarket,
             timestamp: Utc::now(),
+            resolves_at: None,
         }
     }
 
@@ -22,6 +22,9 @@ pub struct MarketOdds {
     pub odds: Decimal,
     pub source: MarketSource,
     pub timestamp: DateTime<Utc>,
+    /// When the market resolves, if the venue publishes it
+    #[serde(default)]
+    pub resolves_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
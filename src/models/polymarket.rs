@@ -1,7 +1,6 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize, Deserializer};
-use std::fmt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolymarketMarket {
@@ -5,26 +5,81 @@ use tokio::time::{sleep, Duration};
 use crate::api::{PolymarketClient, MarketClient};
 use crate::models::Market;
 use crate::config::Config;
+use crate::alerts::sink_from_config;
+use crate::halt::KillSwitch;
+use crate::rebalance::{ManualTransfers, RebalancePlanner, TransferExecutor};
+use crate::reconcile::Reconciler;
+use crate::resolver::Resolver;
+use crate::valuation::{ValuationSeries, Valuer};
+use crate::scheduler::TradeScheduler;
+use crate::trader::ArbitrageExecutor;
+use crate::treasury::Asset;
+use std::sync::Arc;
 
 pub struct Monitor {
     client: PolymarketClient,
     config: Config,
+    scheduler: Option<Arc<TradeScheduler>>,
 }
 
 impl Monitor {
-    pub async fn new() -> Result<Self> {
-        let config = Config::default();
-        
+    pub async fn new(config: Config) -> Result<Self> {
         info!("Starting Polymarket-BTC Arbitrage Monitor v0.10.0");
         info!("Configuration loaded");
         info!("Auto-execute: {}", config.trading.auto_execute);
 
+        // Finish or clean up whatever the last run left in flight before trading again
+        let scheduler = if config.trading.auto_execute {
+            let executor = ArbitrageExecutor::from_config(&config)?;
+            for result in executor.recover().await? {
+                info!(
+                    "Recovered execution: {:?}, position {:?}",
+                    result.status, result.position_id
+                );
+            }
+            if let Some(approvals) = executor.approvals() {
+                crate::approval::spawn_channels(approvals.clone(), &config.trading);
+            }
+            let kill_switch = executor.kill_switch().cloned();
+            let scheduler = TradeScheduler::from_config(executor, &config.trading);
+            scheduler.start();
+            if let Some(kill_switch) = kill_switch {
+                crate::halt::spawn_controls(kill_switch.clone(), &config.trading);
+                spawn_halt_cancels(scheduler.clone(), kill_switch);
+            }
+            if config.rebalance.enabled {
+                spawn_rebalance_checks(scheduler.clone(), &config);
+            }
+            if config.resolver.enabled {
+                spawn_resolution_checks(scheduler.clone(), &config);
+            }
+            if config.valuation.enabled {
+                spawn_valuations(scheduler.clone(), &config)?;
+            }
+            if config.reconcile.enabled {
+                spawn_reconciliation(scheduler.clone(), &config)?;
+            }
+            Some(scheduler)
+        } else {
+            None
+        };
+
         Ok(Self {
             client: PolymarketClient::new(),
             config,
+            scheduler,
         })
     }
 
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The trade scheduler, when auto-execution is enabled
+    pub fn scheduler(&self) -> Option<&Arc<TradeScheduler>> {
+        self.scheduler.as_ref()
+    }
+
     pub async fn run(&self) -> Result<()> {
         info!("Starting market monitor loop...");
 
@@ -39,7 +94,11 @@ impl Monitor {
 
     async fn tick(&self) -> Result<()> {
         info!("Fetching Polymarket markets...");
-        let markets: Vec<Market> = self.client.fetch_markets().await?;
+        let fetched = self.client.fetch_markets().await;
+        if let Some(breakers) = self.scheduler.as_ref().and_then(|s| s.executor().breakers()) {
+            breakers.record_api(fetched.is_ok());
+        }
+        let markets: Vec<Market> = fetched?;
         
         info!("Fetched {} markets", markets.len());
         
@@ -57,3 +116,119 @@ impl Monitor {
         Ok(())
     }
 }
+
+/// Ask the venues to cancel resting orders whenever trading is halted
+fn spawn_halt_cancels(scheduler: Arc<TradeScheduler>, kill_switch: Arc<KillSwitch>) {
+    let mut halted = kill_switch.subscribe();
+    tokio::spawn(async move {
+        loop {
+            if *halted.borrow_and_update() {
+                scheduler.executor().cancel_all_orders().await;
+            }
+            if halted.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Periodically plan inventory transfers from the treasury's balances and
+/// hand them to an operator
+fn spawn_rebalance_checks(scheduler: Arc<TradeScheduler>, config: &Config) {
+    let planner = RebalancePlanner::new(&config.rebalance, Asset::for_protocol(&config.bitcoin.protocol));
+    let alerts = sink_from_config(&config.risk);
+    let transfers = ManualTransfers::new(sink_from_config(&config.risk));
+    let interval = Duration::from_secs(config.rebalance.check_interval_secs.max(1));
+
+    tokio::spawn(async move {
+        loop {
+            sleep(interval).await;
+            let executor = scheduler.executor();
+            let Some(treasury) = executor.treasury() else {
+                break;
+            };
+            executor.refresh_treasury().await;
+
+            match planner.check(treasury, alerts.as_ref()).await {
+                Ok(report) => {
+                    for plan in &report.plans {
+                        if let Err(e) = transfers.execute(plan).await {
+                            info!("Failed to hand off rebalance plan: {}", e);
+                        }
+                    }
+                }
+                Err(e) => info!("Rebalance check error: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically settle open positions whose markets have resolved
+fn spawn_resolution_checks(scheduler: Arc<TradeScheduler>, config: &Config) {
+    let resolver = Resolver::new();
+    let alerts = sink_from_config(&config.risk);
+    let interval = Duration::from_secs(config.resolver.poll_interval_secs.max(1));
+
+    tokio::spawn(async move {
+        loop {
+            sleep(interval).await;
+            let report = resolver.check(scheduler.executor(), alerts.as_ref()).await;
+            for settled in &report.settled {
+                info!("Settled resolved position {}: PnL {:?}", settled.position_id, settled.pnl);
+            }
+        }
+    });
+}
+
+/// Mark open positions every interval, feed the marks to the risk engine and
+/// append the snapshot to the valuation series
+fn spawn_valuations(scheduler: Arc<TradeScheduler>, config: &Config) -> Result<()> {
+    let valuer = Valuer::from_config(config)?;
+    let mut series = ValuationSeries::open(&config.valuation.series_path)?;
+    let interval = Duration::from_secs(config.valuation.interval_secs.max(1));
+
+    tokio::spawn(async move {
+        loop {
+            let executor = scheduler.executor();
+            let open = executor.position_manager().get_open_positions();
+            valuer.refresh(&open).await;
+
+            let snapshot = valuer.snapshot(&executor.position_manager().get_all_positions(), chrono::Utc::now());
+            for warning in &snapshot.warnings {
+                tracing::warn!("Stale mark: {}", warning);
+            }
+            if let Some(risk) = executor.risk() {
+                for ((market_id, outcome), price) in snapshot.marks() {
+                    risk.update_mark(&market_id, &outcome, price);
+                }
+            }
+            if let Err(e) = series.append(&snapshot) {
+                info!("Failed to record valuation snapshot: {}", e);
+            }
+            sleep(interval).await;
+        }
+    });
+    Ok(())
+}
+
+/// Compare positions with venue holdings every interval, starting at launch
+/// so drift from a crash is caught before it trades on
+fn spawn_reconciliation(scheduler: Arc<TradeScheduler>, config: &Config) -> Result<()> {
+    let reconciler = Reconciler::from_config(&config.reconcile)?;
+    let alerts = sink_from_config(&config.risk);
+    let interval = Duration::from_secs(config.reconcile.interval_secs.max(1));
+
+    tokio::spawn(async move {
+        loop {
+            let report = reconciler.check(scheduler.executor(), alerts.as_ref()).await;
+            if !report.unchecked.is_empty() {
+                tracing::debug!("Holdings not reconciled for {:?}", report.unchecked);
+            }
+            for id in &report.corrected {
+                info!("Reconciled position {} with venue holdings", id);
+            }
+            sleep(interval).await;
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,478 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::time::Duration;
+
+use crate::api::ordinals::OrdinalsMarketplace;
+use crate::api::polymarket::PolymarketBookSource;
+use crate::api::OrderBookSource;
+use crate::chains::liquid::OutcomeTokenHolding;
+use crate::config::{Config, TradingConfig};
+use crate::models::polymarket::PriceLevel;
+use crate::models::{ArbitrageOpportunity, MarketOdds};
+use crate::positions::PositionLeg;
+use crate::trader::{
+    ArbitrageExecutor, ExecutionPlan, Leg, OrderLookup, TimeInForce, TradeConfig, TradeExecutor, TradeResult, TradeStatus,
+};
+
+/// Quantity and cost of a simulated fill
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperFill {
+    pub quantity: Decimal,
+    pub notional: Decimal,
+    pub fees: Decimal,
+}
+
+impl PaperFill {
+    pub fn average_price(&self) -> Option<Decimal> {
+        if self.quantity.is_zero() {
+            None
+        } else {
+            Some(self.notional / self.quantity)
+        }
+    }
+}
+
+/// Walk the asks up to `limit`, buying at most `size` and spending at most
+/// `budget` including fees
+pub fn fill_buy(asks: &[PriceLevel], limit: Decimal, size: Decimal, budget: Decimal, fee_rate: Decimal) -> PaperFill {
+    let mut levels: Vec<&PriceLevel> = asks.iter().filter(|l| l.price <= limit).collect();
+    levels.sort_by_key(|level| level.price);
+
+    let mut fill = PaperFill { quantity: Decimal::ZERO, notional: Decimal::ZERO, fees: Decimal::ZERO };
+    for level in levels {
+        let remaining = size - fill.quantity;
+        let unit_cost = level.price * (Decimal::ONE + fee_rate);
+        let affordable = if unit_cost.is_zero() {
+            remaining
+        } else {
+            (budget - fill.notional - fill.fees) / unit_cost
+        };
+        let quantity = remaining.min(level.size).min(affordable);
+        if quantity <= Decimal::ZERO {
+            break;
+        }
+
+        fill.quantity += quantity;
+        fill.notional += quantity * level.price;
+        fill.fees += quantity * level.price * fee_rate;
+    }
+    fill
+}
+
+/// Walk the bids down to `limit`, selling at most `size`
+pub fn fill_sell(bids: &[PriceLevel], limit: Decimal, size: Decimal, fee_rate: Decimal) -> PaperFill {
+    let mut levels: Vec<&PriceLevel> = bids.iter().filter(|l| l.price >= limit).collect();
+    levels.sort_by_key(|level| std::cmp::Reverse(level.price));
+
+    let mut fill = PaperFill { quantity: Decimal::ZERO, notional: Decimal::ZERO, fees: Decimal::ZERO };
+    for level in levels {
+        let quantity = (size - fill.quantity).min(level.size);
+        if quantity <= Decimal::ZERO {
+            break;
+        }
+
+        fill.quantity += quantity;
+        fill.notional += quantity * level.price;
+        fill.fees += quantity * level.price * fee_rate;
+    }
+    fill
+}
+
+/// Virtual cash and outcome holdings at one venue
+#[derive(Debug, Clone, Default)]
+pub struct PaperAccount {
+    pub cash: Decimal,
+    /// Shares held, keyed by `(market_id, outcome)`
+    pub holdings: HashMap<(String, String), Decimal>,
+    pub fees_paid: Decimal,
+    /// Buy orders by client order id
+    pub orders: HashMap<String, TradeResult>,
+}
+
+/// `TradeExecutor` that fills against live order books with virtual balances
+/// instead of sending orders
+pub struct PaperTradeExecutor {
+    leg: Leg,
+    books: Box<dyn OrderBookSource>,
+    latency: Duration,
+    fee_rate: Decimal,
+    account: Mutex<PaperAccount>,
+}
+
+impl PaperTradeExecutor {
+    pub fn new(leg: Leg, books: Box<dyn OrderBookSource>, starting_balance: Decimal) -> Self {
+        Self {
+            leg,
+            books,
+            latency: Duration::ZERO,
+            fee_rate: Decimal::ZERO,
+            account: Mutex::new(PaperAccount {
+                cash: starting_balance,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn from_config(leg: Leg, books: Box<dyn OrderBookSource>, config: &TradingConfig) -> Self {
+        Self::new(leg, books, config.paper_starting_balance)
+            .with_latency(Duration::from_millis(config.paper_latency_ms))
+            .with_fee_rate(config.paper_fee_rate)
+    }
+
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_fee_rate(mut self, fee_rate: Decimal) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    pub fn account(&self) -> PaperAccount {
+        self.account.lock().expect("paper account lock poisoned").clone()
+    }
+
+    fn result(&self, status: TradeStatus, fill: &PaperFill, error: Option<String>) -> TradeResult {
+        let tx = (!fill.quantity.is_zero()).then(|| format!("paper-{}", uuid::Uuid::new_v4()));
+        TradeResult {
+            polymarket_tx: if self.leg == Leg::Polymarket { tx.clone() } else { None },
+            btc_tx: if self.leg == Leg::Btc { tx } else { None },
+            status,
+            executed_at: Utc::now(),
+            error,
+            position_id: None,
+            token_id: None,
+            fill_price: fill.average_price(),
+            filled_size: Some(fill.quantity),
+            fees: Some(fill.fees),
+        }
+    }
+}
+
+#[async_trait]
+impl TradeExecutor for PaperTradeExecutor {
+    async fn execute_arbitrage(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> Result<TradeResult> {
+        let odds = match self.leg {
+            Leg::Polymarket => &opportunity.polymarket_odds,
+            Leg::Btc => &opportunity.btc_market_odds,
+        };
+        let size = config.size;
+        let limit = config.limit_price(odds.odds);
+        let client_order_id = config.client_order_id(self.leg);
+
+        // The book is read after the simulated latency, so it may have moved
+        tokio::time::sleep(self.latency).await;
+        let book = self.books.order_book(&odds.market_id, &odds.outcome).await?;
+
+        let mut account = self.account.lock().expect("paper account lock poisoned");
+        // Like a venue, never accept the same client order id twice
+        if let Some(existing) = account.orders.get(&client_order_id) {
+            tracing::warn!("Paper {} order {} already exists", self.leg, client_order_id);
+            return Ok(existing.clone());
+        }
+
+        let mut fill = fill_buy(&book.asks, limit, size, account.cash, self.fee_rate);
+        if config.time_in_force == TimeInForce::FillOrKill && fill.quantity < size {
+            fill = PaperFill { quantity: Decimal::ZERO, notional: Decimal::ZERO, fees: Decimal::ZERO };
+        }
+
+        account.cash -= fill.notional + fill.fees;
+        account.fees_paid += fill.fees;
+        *account
+            .holdings
+            .entry((odds.market_id.clone(), odds.outcome.clone()))
+            .or_default() += fill.quantity;
+
+        tracing::info!(
+            "Paper {} buy {} {}: {}/{} at {:?}",
+            self.leg,
+            odds.market_id,
+            odds.outcome,
+            fill.quantity,
+            size,
+            fill.average_price()
+        );
+
+        let result = if fill.quantity.is_zero() {
+            self.result(TradeStatus::Failed, &fill, Some(format!("No liquidity at or below {}", limit)))
+        } else if fill.quantity < size {
+            self.result(TradeStatus::Partial, &fill, Some(format!("Partially filled {}/{}", fill.quantity, size)))
+        } else {
+            self.result(TradeStatus::Success, &fill, None)
+        };
+        account.orders.insert(client_order_id, result.clone());
+        Ok(result)
+    }
+
+    async fn approve_token(&self, _token: &str, _amount: Decimal) -> Result<String> {
+        Ok("paper".to_string())
+    }
+
+    async fn get_balance(&self) -> Result<Decimal> {
+        Ok(self.account().cash)
+    }
+
+    async fn sell_back(&self, odds: &MarketOdds, size: Decimal, min_price: Decimal) -> Result<TradeResult> {
+        tokio::time::sleep(self.latency).await;
+        let book = self.books.order_book(&odds.market_id, &odds.outcome).await?;
+
+        let key = (odds.market_id.clone(), odds.outcome.clone());
+        let mut account = self.account.lock().expect("paper account lock poisoned");
+        let held = account.holdings.get(&key).copied().unwrap_or_default();
+        let fill = fill_sell(&book.bids, min_price, size.min(held), self.fee_rate);
+
+        account.cash += fill.notional - fill.fees;
+        account.fees_paid += fill.fees;
+        if let Some(quantity) = account.holdings.get_mut(&key) {
+            *quantity -= fill.quantity;
+        }
+        drop(account);
+
+        Ok(if fill.quantity.is_zero() {
+            self.result(TradeStatus::Failed, &fill, Some(format!("No bids at or above {}", min_price)))
+        } else if fill.quantity < size {
+            self.result(TradeStatus::Partial, &fill, Some(format!("Partially sold {}/{}", fill.quantity, size)))
+        } else {
+            self.result(TradeStatus::Success, &fill, None)
+        })
+    }
+
+    async fn outcome_holdings(&self) -> Result<Vec<OutcomeTokenHolding>> {
+        Ok(self
+            .account()
+            .holdings
+            .into_iter()
+            .filter(|(_, quantity)| !quantity.is_zero())
+            .map(|((market_id, outcome), quantity)| OutcomeTokenHolding {
+                asset_id: format!("paper:{}:{}", market_id, outcome),
+                market_id,
+                outcome,
+                quantity,
+            })
+            .collect())
+    }
+
+    async fn reported_holdings(&self, _tracked: &[PositionLeg]) -> Result<Option<Vec<OutcomeTokenHolding>>> {
+        self.outcome_holdings().await.map(Some)
+    }
+
+    /// Paper orders fill or expire at once; none are left resting
+    async fn cancel_all_orders(&self) -> Result<usize> {
+        Ok(0)
+    }
+
+    async fn find_order(&self, client_order_id: &str) -> Result<OrderLookup> {
+        Ok(match self.account().orders.get(client_order_id) {
+            Some(result) => OrderLookup::Found(result.clone()),
+            None => OrderLookup::NotFound,
+        })
+    }
+}
+
+/// Paper executors for both legs, filling against the live Polymarket book and
+/// the Ordinals marketplace. No other Bitcoin-side venue publishes a book.
+pub fn paper_executors(config: &Config) -> Result<(Box<dyn TradeExecutor>, Box<dyn TradeExecutor>)> {
+    let ordinals = config.bitcoin.ordinals.as_ref().filter(|_| config.bitcoin.protocol == "ordinals");
+    let Some((endpoint, parent)) = ordinals.and_then(|o| o.endpoint.clone().zip(o.parent_inscription.clone())) else {
+        return Err(anyhow::anyhow!(
+            "Paper trading needs an order book for the BTC leg: use the ordinals protocol with an endpoint and parent inscription"
+        ));
+    };
+
+    Ok((
+        Box::new(PaperTradeExecutor::from_config(
+            Leg::Polymarket,
+            Box::new(PolymarketBookSource::new()),
+            &config.trading,
+        )),
+        Box::new(PaperTradeExecutor::from_config(
+            Leg::Btc,
+            Box::new(OrdinalsMarketplace::new(endpoint, parent)),
+            &config.trading,
+        )),
+    ))
+}
+
+/// An `ArbitrageExecutor` whose legs are both paper executors
+pub fn paper_arbitrage_executor(
+    config: &TradingConfig,
+    polymarket_books: Box<dyn OrderBookSource>,
+    btc_books: Box<dyn OrderBookSource>,
+) -> Result<ArbitrageExecutor> {
+    Ok(ArbitrageExecutor::with_executors(
+        Box::new(PaperTradeExecutor::from_config(Leg::Polymarket, polymarket_books, config)),
+        Box::new(PaperTradeExecutor::from_config(Leg::Btc, btc_books, config)),
+    )
+    .with_plan(ExecutionPlan::from_config(config)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::polymarket::OrderBook;
+    use crate::models::MarketSource;
+    use crate::positions::PositionStatus;
+
+    fn level(price: i64, size: i64) -> PriceLevel {
+        PriceLevel {
+            price: Decimal::new(price, 2),
+            size: Decimal::from(size),
+        }
+    }
+
+    struct StaticBooks(OrderBook);
+
+    #[async_trait]
+    impl OrderBookSource for StaticBooks {
+        async fn order_book(&self, _market_id: &str, _outcome: &str) -> Result<OrderBook> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn books(asks: Vec<PriceLevel>, bids: Vec<PriceLevel>) -> Box<dyn OrderBookSource> {
+        Box::new(StaticBooks(OrderBook {
+            outcome: "Yes".to_string(),
+            bids,
+            asks,
+        }))
+    }
+
+    fn opportunity(poly_odds: i64, btc_odds: i64) -> ArbitrageOpportunity {
+        let odds = |source, price| MarketOdds {
+            market_id: "m1".to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(price, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket, poly_odds),
+            odds(MarketSource::BitcoinPredictionMarket, btc_odds),
+            Decimal::new(5, 2),
+            0.9,
+        )
+    }
+
+    fn trade_config(size: i64) -> TradeConfig {
+        TradeConfig {
+            size: Decimal::from(size),
+            max_size: Decimal::from(1000),
+            limit_tolerance: Decimal::ZERO,
+            time_in_force: TimeInForce::ImmediateOrCancel,
+            polymarket_timeout: Duration::from_secs(1),
+            btc_timeout: Duration::from_secs(1),
+            dry_run: false,
+            idempotency_key: "paper-test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fill_buy_walks_depth_to_limit() {
+        let asks = vec![level(45, 50), level(43, 30), level(47, 100)];
+        let fill = fill_buy(&asks, Decimal::new(45, 2), Decimal::from(100), Decimal::from(1000), Decimal::ZERO);
+
+        // 30 @ 0.43 + 50 @ 0.45; the 0.47 level is past the limit
+        assert_eq!(fill.quantity, Decimal::from(80));
+        assert_eq!(fill.notional, Decimal::new(3540, 2));
+    }
+
+    #[test]
+    fn test_fill_buy_respects_budget_and_fees() {
+        let asks = vec![level(50, 100)];
+        let fill = fill_buy(&asks, Decimal::ONE, Decimal::from(100), Decimal::from(11), Decimal::new(10, 2));
+
+        // Each share costs 0.55 with fees, so $11 buys 20
+        assert_eq!(fill.quantity, Decimal::from(20));
+        assert_eq!(fill.notional + fill.fees, Decimal::from(11));
+    }
+
+    #[test]
+    fn test_fill_sell_walks_bids_down_to_limit() {
+        let bids = vec![level(38, 10), level(41, 20), level(35, 100)];
+        let fill = fill_sell(&bids, Decimal::new(38, 2), Decimal::from(50), Decimal::ZERO);
+        assert_eq!(fill.quantity, Decimal::from(30));
+        assert_eq!(fill.average_price().unwrap().round_dp(4), Decimal::new(4000, 4));
+    }
+
+    #[tokio::test]
+    async fn test_paper_fills_flow_into_positions() {
+        let config = TradingConfig::default();
+        let executor = paper_arbitrage_executor(
+            &TradingConfig { paper_latency_ms: 0, ..config },
+            books(vec![level(44, 500)], vec![]),
+            books(vec![level(50, 60)], vec![level(49, 500)]),
+        )
+        .unwrap();
+
+        // BTC book only has 60 of the 100 shares: the thin leg partially fills
+        let result = executor.execute(&opportunity(45, 50), &trade_config(100)).await.unwrap();
+        assert!(result.btc_tx.as_deref().unwrap().starts_with("paper-"));
+        assert!(result.position_id.is_some());
+
+        // 40 Polymarket shares are unhedged
+        let opened = executor.position_manager().get_open_positions();
+        assert_eq!(opened.len(), 1);
+        assert_eq!(opened[0].status, PositionStatus::Partial);
+    }
+
+    #[tokio::test]
+    async fn test_fill_or_kill_rejects_partial_fill() {
+        let executor = PaperTradeExecutor::new(Leg::Btc, books(vec![level(50, 60)], vec![]), Decimal::from(1000));
+        let mut config = trade_config(100);
+        config.time_in_force = TimeInForce::FillOrKill;
+
+        let result = executor.execute_arbitrage(&opportunity(45, 50), &config).await.unwrap();
+        assert_eq!(result.status, TradeStatus::Failed);
+        assert_eq!(executor.get_balance().await.unwrap(), Decimal::from(1000));
+    }
+
+    #[tokio::test]
+    async fn test_paper_balances_and_sell_back() {
+        let executor = PaperTradeExecutor::new(
+            Leg::Btc,
+            books(vec![level(50, 100)], vec![level(48, 100)]),
+            Decimal::from(100),
+        )
+        .with_fee_rate(Decimal::new(1, 2));
+
+        let bought = executor.execute_arbitrage(&opportunity(45, 50), &trade_config(100)).await.unwrap();
+        assert_eq!(bought.status, TradeStatus::Success);
+        assert_eq!(executor.get_balance().await.unwrap(), Decimal::new(4950, 2));
+
+        let odds = opportunity(45, 50).btc_market_odds;
+        let sold = executor.sell_back(&odds, Decimal::from(100), Decimal::new(45, 2)).await.unwrap();
+        assert_eq!(sold.fill_price, Some(Decimal::new(48, 2)));
+        assert!(executor.outcome_holdings().await.unwrap().is_empty());
+        assert_eq!(executor.account().fees_paid, Decimal::new(98, 2));
+    }
+
+    #[tokio::test]
+    async fn test_orders_are_found_by_client_order_id() {
+        let executor = PaperTradeExecutor::new(Leg::Btc, books(vec![level(50, 200)], vec![]), Decimal::from(1000));
+        let config = trade_config(100);
+
+        let first = executor.execute_arbitrage(&opportunity(45, 50), &config).await.unwrap();
+        let resent = executor.execute_arbitrage(&opportunity(45, 50), &config).await.unwrap();
+        assert_eq!(resent.btc_tx, first.btc_tx);
+        assert_eq!(executor.get_balance().await.unwrap(), Decimal::from(950));
+
+        match executor.find_order(&config.client_order_id(Leg::Btc)).await.unwrap() {
+            OrderLookup::Found(result) => assert_eq!(result.btc_tx, first.btc_tx),
+            other => panic!("expected the order, got {:?}", other),
+        }
+        assert!(matches!(
+            executor.find_order("unknown-btc").await.unwrap(),
+            OrderLookup::NotFound
+        ));
+    }
+}
@@ -0,0 +1,244 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::AddAssign;
+
+use crate::positions::{Position, PositionLeg, PositionType};
+use crate::trader::Leg;
+
+/// Latest prices by (market id, outcome)
+pub type Marks = HashMap<(String, String), Decimal>;
+
+pub fn mark_key(market_id: &str, outcome: &str) -> (String, String) {
+    (market_id.to_string(), outcome.to_string())
+}
+
+/// PnL split by where it came from. `realized` and `unrealized` are net of
+/// fees at the entry exchange rate; `fx` is what BTC/USD moves added on top.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PnlBreakdown {
+    pub realized: Decimal,
+    pub unrealized: Decimal,
+    pub fx: Decimal,
+    /// Fees paid, already deducted from realized and unrealized
+    pub fees: Decimal,
+}
+
+impl PnlBreakdown {
+    pub fn total(&self) -> Decimal {
+        self.realized + self.unrealized + self.fx
+    }
+
+    fn map(self, f: impl Fn(Decimal) -> Decimal) -> Self {
+        Self {
+            realized: f(self.realized),
+            unrealized: f(self.unrealized),
+            fx: f(self.fx),
+            fees: f(self.fees),
+        }
+    }
+}
+
+impl AddAssign for PnlBreakdown {
+    fn add_assign(&mut self, other: Self) {
+        self.realized += other.realized;
+        self.unrealized += other.unrealized;
+        self.fx += other.fx;
+        self.fees += other.fees;
+    }
+}
+
+/// The same PnL in USD and in sats at the oracle rate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PnlReport {
+    pub usd: PnlBreakdown,
+    pub sats: PnlBreakdown,
+}
+
+/// PnL of one leg, tagged with what it aggregates under
+#[derive(Debug, Clone, Serialize)]
+pub struct LegPnl {
+    pub position_id: String,
+    pub venue: Leg,
+    pub strategy: PositionType,
+    /// Day the PnL belongs to: close day when realized, valuation day otherwise
+    pub day: NaiveDate,
+    pub usd: PnlBreakdown,
+}
+
+/// Values legs at marks and converts to sats at the oracle BTC/USD rate
+pub struct PnlCalculator {
+    btc_usd: Decimal,
+}
+
+impl PnlCalculator {
+    pub fn new(btc_usd: Decimal) -> Self {
+        Self { btc_usd }
+    }
+
+    /// PnL of every leg of `positions`. Closed legs are realized at their
+    /// exit; open legs are marked, or count only their fees when unmarked.
+    pub fn legs(&self, positions: &[Position], marks: &Marks, now: DateTime<Utc>) -> Vec<LegPnl> {
+        positions
+            .iter()
+            .flat_map(|position| position.legs.iter().map(move |leg| self.leg(position, leg, marks, now)))
+            .collect()
+    }
+
+    fn leg(&self, position: &Position, leg: &PositionLeg, marks: &Marks, now: DateTime<Utc>) -> LegPnl {
+        let mut usd = PnlBreakdown {
+            fees: leg.fees,
+            ..Default::default()
+        };
+        let day = match leg.exit_price {
+            Some(exit) => {
+                usd.realized = leg.trading_pnl(exit);
+                usd.fx = leg.fx_pnl(exit, leg.exit_btc_usd.unwrap_or(self.btc_usd));
+                position.closed_at.unwrap_or(now).date_naive()
+            }
+            None => {
+                match marks.get(&mark_key(&leg.market_id, &leg.outcome)) {
+                    Some(mark) => {
+                        usd.unrealized = leg.trading_pnl(*mark);
+                        usd.fx = leg.fx_pnl(*mark, self.btc_usd);
+                    }
+                    None => usd.unrealized = -leg.fees,
+                }
+                now.date_naive()
+            }
+        };
+
+        LegPnl {
+            position_id: position.id.clone(),
+            venue: leg.venue,
+            strategy: position.position_type.clone(),
+            day,
+            usd,
+        }
+    }
+
+    pub fn to_sats(&self, usd: Decimal) -> Decimal {
+        if self.btc_usd.is_zero() {
+            return Decimal::ZERO;
+        }
+        (usd * Decimal::from(100_000_000) / self.btc_usd).round_dp(0)
+    }
+
+    pub fn report(&self, usd: PnlBreakdown) -> PnlReport {
+        PnlReport {
+            usd,
+            sats: usd.map(|value| self.to_sats(value)),
+        }
+    }
+
+    pub fn total(&self, legs: &[LegPnl]) -> PnlReport {
+        let mut usd = PnlBreakdown::default();
+        for leg in legs {
+            usd += leg.usd;
+        }
+        self.report(usd)
+    }
+
+    /// Sum legs by `key`
+    pub fn aggregate<K: Ord>(&self, legs: &[LegPnl], key: impl Fn(&LegPnl) -> K) -> BTreeMap<K, PnlReport> {
+        let mut sums: BTreeMap<K, PnlBreakdown> = BTreeMap::new();
+        for leg in legs {
+            *sums.entry(key(leg)).or_default() += leg.usd;
+        }
+        sums.into_iter().map(|(k, usd)| (k, self.report(usd))).collect()
+    }
+
+    pub fn by_position(&self, legs: &[LegPnl]) -> BTreeMap<String, PnlReport> {
+        self.aggregate(legs, |leg| leg.position_id.clone())
+    }
+
+    pub fn by_venue(&self, legs: &[LegPnl]) -> BTreeMap<String, PnlReport> {
+        self.aggregate(legs, |leg| leg.venue.to_string())
+    }
+
+    pub fn by_day(&self, legs: &[LegPnl]) -> BTreeMap<NaiveDate, PnlReport> {
+        self.aggregate(legs, |leg| leg.day)
+    }
+
+    pub fn by_strategy(&self, legs: &[LegPnl]) -> BTreeMap<String, PnlReport> {
+        self.aggregate(legs, |leg| format!("{:?}", leg.strategy).to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ArbitrageOpportunity, MarketOdds, MarketSource};
+    use crate::positions::PositionManager;
+
+    fn odds(source: MarketSource, market_id: &str, outcome: &str) -> MarketOdds {
+        MarketOdds {
+            market_id: market_id.to_string(),
+            outcome: outcome.to_string(),
+            odds: Decimal::new(50, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        }
+    }
+
+    /// 100 shares at 0.40 on Polymarket and 0.50 on the BTC venue, bought with
+    /// sats at $50,000
+    fn open(positions: &mut PositionManager) -> String {
+        let opportunity = ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket, "poly", "Yes"),
+            odds(MarketSource::BitcoinPredictionMarket, "btc", "No"),
+            Decimal::new(10, 2),
+            0.9,
+        );
+        let legs = vec![
+            PositionLeg::long(Leg::Polymarket, &opportunity.polymarket_odds, Decimal::from(100), Decimal::new(40, 2))
+                .with_fees(Decimal::ONE),
+            PositionLeg::long(Leg::Btc, &opportunity.btc_market_odds, Decimal::from(100), Decimal::new(50, 2))
+                .with_entry_btc_usd(Some(Decimal::from(50_000))),
+        ];
+        positions.open_position(&opportunity, legs).unwrap()
+    }
+
+    #[test]
+    fn test_unrealized_and_fx_split() {
+        let mut positions = PositionManager::new();
+        open(&mut positions);
+        let marks = Marks::from([
+            (mark_key("poly", "Yes"), Decimal::new(45, 2)),
+            (mark_key("btc", "No"), Decimal::new(60, 2)),
+        ]);
+
+        // BTC up 10%: the $60 BTC leg gains $6 in FX on top of its $10
+        let calculator = PnlCalculator::new(Decimal::from(55_000));
+        let legs = calculator.legs(&positions.get_open_positions(), &marks, Utc::now());
+        let by_venue = calculator.by_venue(&legs);
+        assert_eq!(by_venue["polymarket"].usd.unrealized, Decimal::from(4));
+        assert_eq!(by_venue["btc"].usd.unrealized, Decimal::from(10));
+        assert_eq!(by_venue["btc"].usd.fx, Decimal::from(6));
+
+        let total = calculator.total(&legs);
+        assert_eq!(total.usd.total(), Decimal::from(20));
+        assert_eq!(total.sats.unrealized, Decimal::from(25_455));
+    }
+
+    #[test]
+    fn test_close_realizes_per_leg() {
+        let mut positions = PositionManager::new();
+        let id = open(&mut positions);
+        let closed = positions
+            .close_position(&id, &[(Leg::Polymarket, Decimal::ZERO), (Leg::Btc, Decimal::ONE)], Some(Decimal::from(40_000)))
+            .unwrap();
+
+        // Trading: -40 - 1 + 50; FX: 100 * (40k / 50k - 1) = -20
+        assert_eq!(closed.pnl, Some(Decimal::from(-11)));
+
+        let calculator = PnlCalculator::new(Decimal::from(60_000));
+        let legs = calculator.legs(&positions.get_all_positions(), &Marks::new(), Utc::now());
+        let total = calculator.by_strategy(&legs)["arbitrage"].usd;
+        assert_eq!((total.realized, total.fx, total.unrealized), (Decimal::from(9), Decimal::from(-20), Decimal::ZERO));
+        assert_eq!(calculator.by_day(&legs).len(), 1);
+        assert!(positions.close_position(&id, &[], None).is_err());
+    }
+}
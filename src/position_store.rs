@@ -0,0 +1,435 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::positions::{Position, PositionStatus};
+use crate::trader::Leg;
+
+/// An execution against one leg of a position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub position_id: String,
+    pub leg: Leg,
+    pub tx_id: Option<String>,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub filled_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub position_id: String,
+    /// `None` when the position was opened
+    pub from: Option<PositionStatus>,
+    pub to: PositionStatus,
+    pub at: DateTime<Utc>,
+}
+
+/// Everything one position mutation changes, written atomically
+#[derive(Debug, Clone)]
+pub struct PositionUpdate {
+    pub position: Position,
+    pub transition: Option<StatusTransition>,
+    pub fills: Vec<Fill>,
+}
+
+/// Durable record of positions, their legs, fills and status history
+pub trait PositionStore: Send {
+    fn write(&mut self, update: &PositionUpdate) -> Result<()>;
+
+    /// Positions that have not been closed
+    fn open_positions(&self) -> Result<Vec<Position>>;
+
+    /// Every position ever written, oldest first
+    fn all_positions(&self) -> Result<Vec<Position>>;
+
+    fn position(&self, position_id: &str) -> Result<Option<Position>>;
+
+    fn fills(&self, position_id: &str) -> Result<Vec<Fill>>;
+
+    fn transitions(&self, position_id: &str) -> Result<Vec<StatusTransition>>;
+}
+
+/// Keeps everything in memory; for tests and runs that need no persistence
+#[derive(Default)]
+pub struct InMemoryPositionStore {
+    positions: HashMap<String, Position>,
+    fills: Vec<Fill>,
+    transitions: Vec<StatusTransition>,
+}
+
+impl InMemoryPositionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PositionStore for InMemoryPositionStore {
+    fn write(&mut self, update: &PositionUpdate) -> Result<()> {
+        self.positions.insert(update.position.id.clone(), update.position.clone());
+        self.fills.extend(update.fills.iter().cloned());
+        self.transitions.extend(update.transition.iter().cloned());
+        Ok(())
+    }
+
+    fn open_positions(&self) -> Result<Vec<Position>> {
+        let mut open: Vec<Position> = self.positions.values().filter(|p| p.closed_at.is_none()).cloned().collect();
+        open.sort_by_key(|p| p.opened_at);
+        Ok(open)
+    }
+
+    fn all_positions(&self) -> Result<Vec<Position>> {
+        let mut all: Vec<Position> = self.positions.values().cloned().collect();
+        all.sort_by_key(|p| p.opened_at);
+        Ok(all)
+    }
+
+    fn position(&self, position_id: &str) -> Result<Option<Position>> {
+        Ok(self.positions.get(position_id).cloned())
+    }
+
+    fn fills(&self, position_id: &str) -> Result<Vec<Fill>> {
+        Ok(self.fills.iter().filter(|f| f.position_id == position_id).cloned().collect())
+    }
+
+    fn transitions(&self, position_id: &str) -> Result<Vec<StatusTransition>> {
+        Ok(self.transitions.iter().filter(|t| t.position_id == position_id).cloned().collect())
+    }
+}
+
+/// Schema changes, applied in order. `PRAGMA user_version` records how many ran.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE positions (
+        id TEXT PRIMARY KEY,
+        market_id TEXT NOT NULL,
+        status TEXT NOT NULL,
+        opened_at TEXT NOT NULL,
+        closed_at TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX positions_open ON positions (closed_at);
+    CREATE TABLE legs (
+        position_id TEXT NOT NULL REFERENCES positions (id),
+        leg TEXT NOT NULL,
+        tx_id TEXT,
+        PRIMARY KEY (position_id, leg)
+    );
+    CREATE TABLE fills (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        position_id TEXT NOT NULL REFERENCES positions (id),
+        leg TEXT NOT NULL,
+        tx_id TEXT,
+        price TEXT NOT NULL,
+        size TEXT NOT NULL,
+        filled_at TEXT NOT NULL
+    );
+    CREATE INDEX fills_position ON fills (position_id);
+    CREATE TABLE status_transitions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        position_id TEXT NOT NULL REFERENCES positions (id),
+        from_status TEXT,
+        to_status TEXT NOT NULL,
+        at TEXT NOT NULL
+    );
+    CREATE INDEX status_transitions_position ON status_transitions (position_id);",
+    // Legs carry their own market, side, quantity, cost and fees
+    "ALTER TABLE legs ADD COLUMN market_id TEXT;
+    ALTER TABLE legs ADD COLUMN outcome TEXT;
+    ALTER TABLE legs ADD COLUMN token_id TEXT;
+    ALTER TABLE legs ADD COLUMN side TEXT;
+    ALTER TABLE legs ADD COLUMN quantity TEXT;
+    ALTER TABLE legs ADD COLUMN avg_cost TEXT;
+    ALTER TABLE legs ADD COLUMN fees TEXT;",
+];
+
+/// SQLite-backed position store. The full position is kept as JSON next to
+/// the columns queries need, so model changes don't require a migration.
+pub struct SqlitePositionStore {
+    conn: Connection,
+}
+
+impl SqlitePositionStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open position store {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    pub fn in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    pub fn schema_version(&self) -> Result<usize> {
+        let version: i64 = self.conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        Ok(version as usize)
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let current: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)
+            .with_context(|| format!("Position store migration {} failed", version + 1))?;
+        tx.pragma_update(None, "user_version", (version + 1) as i64)?;
+        tx.commit()?;
+        tracing::info!("Applied position store migration {}", version + 1);
+    }
+    Ok(())
+}
+
+fn status_name(status: &PositionStatus) -> String {
+    format!("{:?}", status)
+}
+
+fn parse_status(name: &str) -> Result<PositionStatus> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .with_context(|| format!("Unknown position status {}", name))
+}
+
+fn parse_decimal(value: String) -> rusqlite::Result<Decimal> {
+    Decimal::from_str(&value)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+impl PositionStore for SqlitePositionStore {
+    fn write(&mut self, update: &PositionUpdate) -> Result<()> {
+        let position = &update.position;
+        let tx = self.conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO positions (id, market_id, status, opened_at, closed_at, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (id) DO UPDATE SET
+                status = excluded.status, closed_at = excluded.closed_at, data = excluded.data",
+            params![
+                position.id,
+                position.market_id,
+                status_name(&position.status),
+                position.opened_at,
+                position.closed_at,
+                serde_json::to_string(position)?,
+            ],
+        )?;
+
+        for leg in &position.legs {
+            tx.execute(
+                "INSERT INTO legs (position_id, leg, tx_id, market_id, outcome, token_id, side, quantity, avg_cost, fees)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT (position_id, leg) DO UPDATE SET
+                    tx_id = excluded.tx_id, token_id = excluded.token_id, quantity = excluded.quantity,
+                    avg_cost = excluded.avg_cost, fees = excluded.fees",
+                params![
+                    position.id,
+                    leg.venue.to_string(),
+                    leg.tx_ids.last(),
+                    leg.market_id,
+                    leg.outcome,
+                    leg.token_id,
+                    format!("{:?}", leg.side),
+                    leg.quantity.to_string(),
+                    leg.avg_cost.to_string(),
+                    leg.fees.to_string(),
+                ],
+            )?;
+        }
+
+        for fill in &update.fills {
+            tx.execute(
+                "INSERT INTO fills (position_id, leg, tx_id, price, size, filled_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    fill.position_id,
+                    fill.leg.to_string(),
+                    fill.tx_id,
+                    fill.price.to_string(),
+                    fill.size.to_string(),
+                    fill.filled_at,
+                ],
+            )?;
+        }
+
+        if let Some(transition) = &update.transition {
+            tx.execute(
+                "INSERT INTO status_transitions (position_id, from_status, to_status, at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    transition.position_id,
+                    transition.from.as_ref().map(status_name),
+                    status_name(&transition.to),
+                    transition.at,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn open_positions(&self) -> Result<Vec<Position>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM positions WHERE closed_at IS NULL ORDER BY opened_at")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?)).collect()
+    }
+
+    fn all_positions(&self) -> Result<Vec<Position>> {
+        let mut stmt = self.conn.prepare("SELECT data FROM positions ORDER BY opened_at")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?)).collect()
+    }
+
+    fn position(&self, position_id: &str) -> Result<Option<Position>> {
+        let data: Option<String> = self
+            .conn
+            .query_row("SELECT data FROM positions WHERE id = ?1", [position_id], |row| row.get(0))
+            .optional()?;
+        data.map(|data| Ok(serde_json::from_str(&data)?)).transpose()
+    }
+
+    fn fills(&self, position_id: &str) -> Result<Vec<Fill>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT leg, tx_id, price, size, filled_at FROM fills WHERE position_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map([position_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                parse_decimal(row.get(2)?)?,
+                parse_decimal(row.get(3)?)?,
+                row.get::<_, DateTime<Utc>>(4)?,
+            ))
+        })?;
+        rows.map(|row| {
+            let (leg, tx_id, price, size, filled_at) = row?;
+            Ok(Fill {
+                position_id: position_id.to_string(),
+                leg: Leg::from_name(&leg)?,
+                tx_id,
+                price,
+                size,
+                filled_at,
+            })
+        })
+        .collect()
+    }
+
+    fn transitions(&self, position_id: &str) -> Result<Vec<StatusTransition>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT from_status, to_status, at FROM status_transitions WHERE position_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map([position_id], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, DateTime<Utc>>(2)?,
+            ))
+        })?;
+        rows.map(|row| {
+            let (from, to, at) = row?;
+            Ok(StatusTransition {
+                position_id: position_id.to_string(),
+                from: from.as_deref().map(parse_status).transpose()?,
+                to: parse_status(&to)?,
+                at,
+            })
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ArbitrageOpportunity, MarketOdds, MarketSource};
+    use crate::positions::{PositionLeg, PositionManager};
+
+    fn opportunity() -> ArbitrageOpportunity {
+        let odds = |source| MarketOdds {
+            market_id: "m1".to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(45, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket),
+            odds(MarketSource::BitcoinPredictionMarket),
+            Decimal::new(5, 2),
+            0.9,
+        )
+    }
+
+    fn db_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("positions-{}.db", uuid::Uuid::new_v4()))
+    }
+
+    fn remove_db(path: &Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn test_migrations_are_applied_once() {
+        let path = db_path();
+        assert_eq!(SqlitePositionStore::open(&path).unwrap().schema_version().unwrap(), MIGRATIONS.len());
+        assert_eq!(SqlitePositionStore::open(&path).unwrap().schema_version().unwrap(), MIGRATIONS.len());
+        remove_db(&path);
+    }
+
+    #[test]
+    fn test_open_positions_survive_restart() {
+        let path = db_path();
+        let (open_id, closed_id) = {
+            let store = SqlitePositionStore::open(&path).unwrap();
+            let mut manager = PositionManager::with_store(Box::new(store)).unwrap();
+            let opportunity = opportunity();
+            let leg = PositionLeg::long(Leg::Polymarket, &opportunity.polymarket_odds, Decimal::from(10), Decimal::new(45, 2))
+                .with_tx(Some("0xabc".to_string()));
+            let open_id = manager.open_position(&opportunity, vec![leg]).unwrap();
+            manager.set_status(&open_id, PositionStatus::Partial).unwrap();
+            let closed_id = manager.open_position(&opportunity, vec![]).unwrap();
+            manager.close_position(&closed_id, &[], None).unwrap();
+            (open_id, closed_id)
+        };
+
+        let store = SqlitePositionStore::open(&path).unwrap();
+        let transitions = store.transitions(&open_id).unwrap();
+        assert_eq!(
+            transitions.iter().map(|t| (t.from.clone(), t.to.clone())).collect::<Vec<_>>(),
+            vec![(None, PositionStatus::Open), (Some(PositionStatus::Open), PositionStatus::Partial)]
+        );
+        let fills = store.fills(&open_id).unwrap();
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].leg, fills[0].tx_id.as_deref()), (Leg::Polymarket, Some("0xabc")));
+        assert!(store.position(&closed_id).unwrap().unwrap().closed_at.is_some());
+        assert_eq!(store.all_positions().unwrap().len(), 2);
+
+        let manager = PositionManager::with_store(Box::new(store)).unwrap();
+        let open = manager.get_open_positions();
+        assert_eq!(open.len(), 1);
+        assert_eq!((open[0].id.as_str(), &open[0].status), (open_id.as_str(), &PositionStatus::Partial));
+        assert_eq!(open[0].leg(Leg::Polymarket).unwrap().quantity, Decimal::from(10));
+        drop(manager);
+        remove_db(&path);
+    }
+}
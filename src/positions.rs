@@ -1,10 +1,124 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::models::{Market, ArbitrageOpportunity};
+use crate::models::{ArbitrageOpportunity, MarketOdds};
+use crate::pnl::{mark_key, Marks};
+use crate::position_store::{Fill, InMemoryPositionStore, PositionStore, PositionUpdate, StatusTransition};
+use crate::trader::Leg;
+use crate::unwind::{UnwindAction, UnwindReport};
+
+/// Holding at one venue: an outcome token bought or sold there
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionLeg {
+    pub venue: Leg,
+    pub market_id: String,
+    pub outcome: String,
+    /// Outcome token (CTF token id, Liquid asset id, inscription id)
+    #[serde(default)]
+    pub token_id: Option<String>,
+    pub side: TradeSide,
+    pub quantity: Decimal,
+    /// Average price paid (or received, when short) per share, in USD
+    pub avg_cost: Decimal,
+    /// Fees paid in USD
+    pub fees: Decimal,
+    #[serde(default)]
+    pub tx_ids: Vec<String>,
+    /// Price the leg was sold or settled at, once closed
+    #[serde(default)]
+    pub exit_price: Option<Decimal>,
+    /// BTC/USD at entry and exit, for legs whose collateral is BTC-denominated
+    #[serde(default)]
+    pub entry_btc_usd: Option<Decimal>,
+    #[serde(default)]
+    pub exit_btc_usd: Option<Decimal>,
+}
+
+impl PositionLeg {
+    /// A long leg bought at `avg_cost` on the market quoted by `odds`
+    pub fn long(venue: Leg, odds: &MarketOdds, quantity: Decimal, avg_cost: Decimal) -> Self {
+        Self {
+            venue,
+            market_id: odds.market_id.clone(),
+            outcome: odds.outcome.clone(),
+            token_id: None,
+            side: TradeSide::Long,
+            quantity,
+            avg_cost,
+            fees: Decimal::ZERO,
+            tx_ids: vec![],
+            exit_price: None,
+            entry_btc_usd: None,
+            exit_btc_usd: None,
+        }
+    }
+
+    pub fn with_fees(mut self, fees: Decimal) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    pub fn with_tx(mut self, tx_id: Option<String>) -> Self {
+        self.tx_ids.extend(tx_id);
+        self
+    }
+
+    pub fn with_token_id(mut self, token_id: Option<String>) -> Self {
+        self.token_id = token_id;
+        self
+    }
+
+    /// Mark the leg as held in BTC-denominated collateral bought at `btc_usd`
+    pub fn with_entry_btc_usd(mut self, btc_usd: Option<Decimal>) -> Self {
+        self.entry_btc_usd = btc_usd;
+        self
+    }
+
+    fn sign(&self) -> Decimal {
+        match self.side {
+            TradeSide::Long => Decimal::ONE,
+            TradeSide::Short => -Decimal::ONE,
+        }
+    }
+
+    /// USD PnL at `price` at the entry exchange rate: quantity times the
+    /// price change, signed by side, less fees
+    pub fn trading_pnl(&self, price: Decimal) -> Decimal {
+        self.sign() * self.quantity * (price - self.avg_cost) - self.fees
+    }
+
+    /// USD PnL from BTC/USD moving since entry, on a leg worth `price` per
+    /// share. Zero for dollar-denominated legs.
+    pub fn fx_pnl(&self, price: Decimal, btc_usd: Decimal) -> Decimal {
+        match self.entry_btc_usd {
+            Some(entry) if !entry.is_zero() => self.sign() * self.quantity * price * (btc_usd / entry - Decimal::ONE),
+            _ => Decimal::ZERO,
+        }
+    }
+
+    /// Cash paid for the leg including fees; negative for proceeds of a short
+    pub fn cost_basis(&self) -> Decimal {
+        let notional = self.quantity * self.avg_cost;
+        match self.side {
+            TradeSide::Long => notional + self.fees,
+            TradeSide::Short => -notional + self.fees,
+        }
+    }
+
+    /// Add shares to the leg, averaging their price into its cost
+    pub fn add_fill(&mut self, quantity: Decimal, price: Decimal, fees: Decimal, tx_id: Option<String>) {
+        let total = self.quantity + quantity;
+        if !total.is_zero() {
+            self.avg_cost = (self.quantity * self.avg_cost + quantity * price) / total;
+        }
+        self.quantity = total;
+        self.fees += fees;
+        self.tx_ids.extend(tx_id);
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
@@ -12,26 +126,86 @@ pub struct Position {
     pub market_id: String,
     pub market_question: String,
     pub position_type: PositionType,
-    pub entry_price: Decimal,
-    pub size: Decimal,
-    pub side: TradeSide,
+    /// At most one leg per venue
+    pub legs: Vec<PositionLeg>,
     pub status: PositionStatus,
     pub opened_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
-    pub exit_price: Option<Decimal>,
+    /// Realized PnL in USD, once closed
     pub pnl: Option<Decimal>,
-    pub tx_ids: PositionTxIds,
+    /// Realized cost of unwinding a one-legged execution, in USD
+    #[serde(default)]
+    pub unwind_cost: Option<Decimal>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PositionTxIds {
-    pub polymarket_tx: Option<String>,
-    pub btc_tx: Option<String>,
+impl Position {
+    pub fn leg(&self, venue: Leg) -> Option<&PositionLeg> {
+        self.legs.iter().find(|leg| leg.venue == venue)
+    }
+
+    fn leg_mut(&mut self, venue: Leg) -> Option<&mut PositionLeg> {
+        self.legs.iter_mut().find(|leg| leg.venue == venue)
+    }
+
+    /// Largest leg quantity: the shares exposed if nothing were hedged
+    pub fn quantity(&self) -> Decimal {
+        self.legs.iter().map(|leg| leg.quantity).max().unwrap_or_default()
+    }
+
+    /// Cash paid for all legs, fees included
+    pub fn total_cost(&self) -> Decimal {
+        self.legs.iter().map(|leg| leg.cost_basis()).sum()
+    }
+
+    /// Shares covered on both venues. The legs of an arbitrage hold
+    /// complementary outcomes, so each hedged pair pays out $1 whichever wins.
+    pub fn hedged_quantity(&self) -> Decimal {
+        match (self.position_type.clone(), self.leg(Leg::Polymarket), self.leg(Leg::Btc)) {
+            (PositionType::Arbitrage, Some(poly), Some(btc)) => poly.quantity.min(btc.quantity),
+            _ => Decimal::ZERO,
+        }
+    }
+
+    /// Payout in USD at resolution regardless of the outcome
+    pub fn guaranteed_payout(&self) -> Decimal {
+        self.hedged_quantity()
+    }
+
+    /// Guaranteed payout less what the hedged shares cost
+    pub fn locked_edge(&self) -> Decimal {
+        let hedged = self.hedged_quantity();
+        if hedged.is_zero() {
+            return Decimal::ZERO;
+        }
+        let hedged_cost: Decimal = self
+            .legs
+            .iter()
+            .filter(|leg| !leg.quantity.is_zero())
+            .map(|leg| leg.cost_basis() * hedged / leg.quantity)
+            .sum();
+        hedged - hedged_cost
+    }
+
+    /// Hedged share of the position: 1 when both legs match, 0 when one-legged
+    pub fn hedge_ratio(&self) -> Decimal {
+        let quantity = self.quantity();
+        if quantity.is_zero() {
+            return Decimal::ZERO;
+        }
+        self.hedged_quantity() / quantity
+    }
+
+    /// Transaction ids of every leg
+    pub fn tx_ids(&self) -> impl Iterator<Item = (Leg, &str)> {
+        self.legs.iter().flat_map(|leg| leg.tx_ids.iter().map(move |tx| (leg.venue, tx.as_str())))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum PositionType {
+    /// Offsetting legs on both venues
     Arbitrage,
+    /// A single unhedged leg, e.g. a token found in the wallet
     Speculative,
 }
 
@@ -49,13 +223,27 @@ pub enum PositionState {
     Partial,
 }
 
+impl PositionState {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "open" => Ok(Self::Open),
+            "closed" => Ok(Self::Closed),
+            "failed" => Ok(Self::Failed),
+            "partial" => Ok(Self::Partial),
+            other => Err(anyhow::anyhow!("Unknown position status: {}", other)),
+        }
+    }
+}
+
 // Type alias for backwards compatibility
 pub use PositionState as PositionStatus;
 
-/// Position manager tracks all open and closed positions
+/// Position manager tracks all open and closed positions, writing every
+/// change through to its store
 pub struct PositionManager {
     positions: HashMap<String, Position>,
     open_positions: Vec<String>,
+    store: Box<dyn PositionStore>,
 }
 
 impl PositionManager {
@@ -63,80 +251,325 @@ impl PositionManager {
         Self {
             positions: HashMap::new(),
             open_positions: Vec::new(),
+            store: Box::new(InMemoryPositionStore::new()),
         }
     }
 
-    /// Open a new position
-    pub fn open_position(
-        &mut self,
-        opportunity: &ArbitrageOpportunity,
-        entry_price: Decimal,
-        size: Decimal,
-        poly_tx: Option<String>,
-        btc_tx: Option<String>,
-    ) -> Result<String> {
+    /// Load the store's open positions and persist to it from now on
+    pub fn with_store(store: Box<dyn PositionStore>) -> Result<Self> {
+        let open = store.open_positions()?;
+        if !open.is_empty() {
+            tracing::info!("Loaded {} open positions", open.len());
+        }
+        Ok(Self {
+            open_positions: open.iter().map(|p| p.id.clone()).collect(),
+            positions: open.into_iter().map(|p| (p.id.clone(), p)).collect(),
+            store,
+        })
+    }
+
+    pub fn store(&self) -> &dyn PositionStore {
+        self.store.as_ref()
+    }
+
+    /// Write a position to the store, with a status transition if its status
+    /// moved away from `previous`
+    fn persist(&mut self, position_id: &str, previous: Option<PositionStatus>, fills: Vec<Fill>) -> Result<()> {
+        let position = self
+            .positions
+            .get(position_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Position not found: {}", position_id))?;
+        let transition = (previous.as_ref() != Some(&position.status)).then(|| StatusTransition {
+            position_id: position_id.to_string(),
+            from: previous,
+            to: position.status.clone(),
+            at: Utc::now(),
+        });
+
+        self.store
+            .write(&PositionUpdate {
+                position,
+                transition,
+                fills,
+            })
+            .with_context(|| format!("Failed to persist position {}", position_id))
+    }
+
+    fn fill(position_id: &str, leg: &PositionLeg, quantity: Decimal, price: Decimal, tx_id: Option<String>) -> Fill {
+        Fill {
+            position_id: position_id.to_string(),
+            leg: leg.venue,
+            tx_id,
+            price,
+            size: quantity,
+            filled_at: Utc::now(),
+        }
+    }
+
+    /// Open an arbitrage position from the legs that reached a venue
+    pub fn open_position(&mut self, opportunity: &ArbitrageOpportunity, legs: Vec<PositionLeg>) -> Result<String> {
         let id = uuid::Uuid::new_v4().to_string();
+        let fills = legs
+            .iter()
+            .map(|leg| Self::fill(&id, leg, leg.quantity, leg.avg_cost, leg.tx_ids.last().cloned()))
+            .collect();
         let position = Position {
             id: id.clone(),
             market_id: opportunity.polymarket_odds.market_id.clone(),
             market_question: opportunity.polymarket_odds.outcome.clone(),
             position_type: PositionType::Arbitrage,
-            entry_price,
-            size,
-            side: TradeSide::Long,
+            legs,
             status: PositionStatus::Open,
             opened_at: Utc::now(),
             closed_at: None,
-            exit_price: None,
             pnl: None,
-            tx_ids: PositionTxIds {
-                polymarket_tx: poly_tx,
-                btc_tx: btc_tx,
-            },
+            unwind_cost: None,
         };
 
-        self.positions.insert(id.clone(), position);
-        self.open_positions.push(id.clone());
-
         tracing::info!(
-            "Opened position {}: {} at {}",
+            "Opened position {}: {} legs, cost {}, locked edge {}",
             id,
-            opportunity.polymarket_odds.outcome,
-            entry_price
+            position.legs.len(),
+            position.total_cost(),
+            position.locked_edge()
         );
 
+        self.positions.insert(id.clone(), position);
+        self.open_positions.push(id.clone());
+        self.persist(&id, None, fills)?;
+
         Ok(id)
     }
 
-    /// Close a position
+    /// Close a position, selling or settling each leg at its exit price.
+    /// `btc_usd` is the rate at exit, for legs held in BTC-denominated collateral.
     pub fn close_position(
         &mut self,
         position_id: &str,
-        exit_price: Decimal,
+        exits: &[(Leg, Decimal)],
+        btc_usd: Option<Decimal>,
     ) -> Result<Position> {
         let position = self
             .positions
             .get_mut(position_id)
             .ok_or_else(|| anyhow::anyhow!("Position not found: {}", position_id))?;
 
-        if position.status != PositionStatus::Open {
+        if !matches!(position.status, PositionStatus::Open | PositionStatus::Partial) {
             return Err(anyhow::anyhow!("Position is not open: {}", position_id));
         }
+        if let Some(leg) = position.legs.iter().find(|leg| !exits.iter().any(|(venue, _)| *venue == leg.venue)) {
+            return Err(anyhow::anyhow!("No exit price for the {} leg of {}", leg.venue, position_id));
+        }
 
-        position.status = PositionStatus::Closed;
+        let mut pnl = Decimal::ZERO;
+        for leg in position.legs.iter_mut() {
+            let price = exits.iter().find(|(venue, _)| *venue == leg.venue).map(|(_, p)| *p).unwrap_or_default();
+            leg.exit_price = Some(price);
+            leg.exit_btc_usd = leg.entry_btc_usd.and(btc_usd);
+            pnl += leg.trading_pnl(price) + leg.exit_btc_usd.map(|rate| leg.fx_pnl(price, rate)).unwrap_or_default();
+        }
+        let previous = std::mem::replace(&mut position.status, PositionStatus::Closed);
         position.closed_at = Some(Utc::now());
-        position.exit_price = Some(exit_price);
-        position.pnl = Some(exit_price - position.entry_price);
+        position.pnl = Some(pnl);
+        let closed = position.clone();
 
         self.open_positions.retain(|id| id != position_id);
+        self.persist(position_id, Some(previous), vec![])?;
 
-        tracing::info!(
-            "Closed position {}: PnL = {}",
-            position_id,
-            position.pnl.unwrap_or(Decimal::ZERO)
+        tracing::info!("Closed position {}: PnL = {}", position_id, pnl);
+
+        Ok(closed)
+    }
+
+    /// Track an on-chain outcome token holding as a position.
+    /// Updates the size of the open position holding the token, or opens a
+    /// speculative one if the holding was not created by this process.
+    pub fn upsert_token_position(
+        &mut self,
+        market_id: &str,
+        outcome: &str,
+        token_id: &str,
+        quantity: Decimal,
+    ) -> Result<String> {
+        let existing = self.open_positions.iter().find(|id| {
+            self.positions
+                .get(*id)
+                .is_some_and(|pos| pos.legs.iter().any(|leg| leg.token_id.as_deref() == Some(token_id)))
+        });
+
+        if let Some(id) = existing.cloned() {
+            let position = self
+                .positions
+                .get_mut(&id)
+                .ok_or_else(|| anyhow::anyhow!("Position not found: {}", id))?;
+            let previous = position.status.clone();
+            match position.legs.iter_mut().find(|leg| leg.token_id.as_deref() == Some(token_id)) {
+                Some(leg) if leg.quantity != quantity => leg.quantity = quantity,
+                _ => return Ok(id),
+            }
+            self.persist(&id, Some(previous), vec![])?;
+            return Ok(id);
+        }
+
+        self.track_holding(Leg::Btc, market_id, outcome, Some(token_id), quantity)
+    }
+
+    /// Open a speculative position for a holding found at a venue that this
+    /// process did not create, with unknown cost basis
+    pub fn track_holding(
+        &mut self,
+        venue: Leg,
+        market_id: &str,
+        outcome: &str,
+        token_id: Option<&str>,
+        quantity: Decimal,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let position = Position {
+            id: id.clone(),
+            market_id: market_id.to_string(),
+            market_question: outcome.to_string(),
+            position_type: PositionType::Speculative,
+            legs: vec![PositionLeg {
+                venue,
+                market_id: market_id.to_string(),
+                outcome: outcome.to_string(),
+                token_id: token_id.map(str::to_string),
+                side: TradeSide::Long,
+                quantity,
+                avg_cost: Decimal::ZERO,
+                fees: Decimal::ZERO,
+                tx_ids: vec![],
+                exit_price: None,
+                entry_btc_usd: None,
+                exit_btc_usd: None,
+            }],
+            status: PositionStatus::Open,
+            opened_at: Utc::now(),
+            closed_at: None,
+            pnl: None,
+            unwind_cost: None,
+        };
+
+        self.positions.insert(id.clone(), position);
+        self.open_positions.push(id.clone());
+        self.persist(&id, None, vec![])?;
+
+        tracing::warn!(
+            "Tracking untracked {} holding {} ({} {}) as position {} with unknown cost basis",
+            venue,
+            token_id.unwrap_or("-"),
+            market_id,
+            outcome,
+            id
         );
 
-        Ok(position.clone())
+        Ok(id)
+    }
+
+    /// Attach the outcome token received on one leg of a position
+    pub fn set_token_id(&mut self, position_id: &str, venue: Leg, token_id: &str) -> Result<()> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .ok_or_else(|| anyhow::anyhow!("Position not found: {}", position_id))?;
+        let leg = position
+            .leg_mut(venue)
+            .ok_or_else(|| anyhow::anyhow!("Position {} has no {} leg", position_id, venue))?;
+        leg.token_id = Some(token_id.to_string());
+        let previous = position.status.clone();
+        self.persist(position_id, Some(previous), vec![])
+    }
+
+    /// Attach a transaction to a leg, e.g. the redemption of a resolved market
+    pub fn record_tx(&mut self, position_id: &str, venue: Leg, tx_id: &str) -> Result<()> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .ok_or_else(|| anyhow::anyhow!("Position not found: {}", position_id))?;
+        let leg = position
+            .leg_mut(venue)
+            .ok_or_else(|| anyhow::anyhow!("Position {} has no {} leg", position_id, venue))?;
+        leg.tx_ids.push(tx_id.to_string());
+        let status = position.status.clone();
+        self.persist(position_id, Some(status), vec![])
+    }
+
+    /// Overwrite a leg's quantity with what its venue reports. A position
+    /// left holding nothing is marked failed and no longer tracked as open;
+    /// no PnL is booked since its exit is unknown.
+    pub fn set_leg_quantity(&mut self, position_id: &str, venue: Leg, quantity: Decimal) -> Result<()> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .ok_or_else(|| anyhow::anyhow!("Position not found: {}", position_id))?;
+        let leg = position
+            .leg_mut(venue)
+            .ok_or_else(|| anyhow::anyhow!("Position {} has no {} leg", position_id, venue))?;
+        leg.quantity = quantity;
+        let previous = position.status.clone();
+        if position.legs.iter().all(|leg| leg.quantity.is_zero()) {
+            position.status = PositionStatus::Failed;
+            position.closed_at = Some(Utc::now());
+            self.open_positions.retain(|id| id != position_id);
+        }
+        self.persist(position_id, Some(previous), vec![])
+    }
+
+    /// Update a position's status, e.g. to flag a one-legged execution
+    pub fn set_status(&mut self, position_id: &str, status: PositionStatus) -> Result<()> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .ok_or_else(|| anyhow::anyhow!("Position not found: {}", position_id))?;
+        let previous = std::mem::replace(&mut position.status, status);
+        self.persist(position_id, Some(previous), vec![])
+    }
+
+    /// Apply the outcome of unwinding a one-legged position. `hedge_odds`
+    /// quotes the market the hedge leg was bought on.
+    pub fn record_unwind(&mut self, position_id: &str, report: &UnwindReport, hedge_odds: &MarketOdds) -> Result<()> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .ok_or_else(|| anyhow::anyhow!("Position not found: {}", position_id))?;
+        let previous = position.status.clone();
+        let mut fills = Vec::new();
+
+        match report.action {
+            UnwindAction::Hedged => {
+                position.status = PositionStatus::Open;
+                position.unwind_cost = Some(report.cost);
+                let hedge_venue = report.filled_leg.other();
+                let quantity = position.leg(report.filled_leg).map(|leg| leg.quantity).unwrap_or_default();
+                let price = report.price.unwrap_or(hedge_odds.odds);
+
+                match position.leg_mut(hedge_venue) {
+                    Some(leg) => leg.add_fill(quantity, price, Decimal::ZERO, report.tx_id.clone()),
+                    None => position.legs.push(
+                        PositionLeg::long(hedge_venue, hedge_odds, quantity, price).with_tx(report.tx_id.clone()),
+                    ),
+                }
+                let leg = position.leg(hedge_venue).expect("hedge leg was just recorded");
+                fills.push(Self::fill(position_id, leg, quantity, price, report.tx_id.clone()));
+            }
+            UnwindAction::SoldBack => {
+                position.status = PositionStatus::Closed;
+                position.closed_at = Some(Utc::now());
+                if let Some(leg) = position.leg_mut(report.filled_leg) {
+                    leg.exit_price = report.price;
+                }
+                position.pnl = Some(-report.cost);
+                position.unwind_cost = Some(report.cost);
+                self.open_positions.retain(|id| id != position_id);
+            }
+            UnwindAction::Escalated => {
+                tracing::error!("Position {} left one-legged after failed unwind", position_id);
+            }
+        }
+
+        self.persist(position_id, Some(previous), fills)
     }
 
     /// Get all open positions
@@ -157,16 +590,17 @@ impl PositionManager {
         self.positions.values().cloned().collect()
     }
 
-    /// Calculate total unrealized PnL for open positions
-    pub fn calculate_unrealized_pnl(&self, current_prices: &HashMap<String, Decimal>) -> Decimal {
+    /// Unrealized trading PnL of open legs at `marks`; unmarked legs count
+    /// their fees only. See `pnl::PnlCalculator` for the FX split.
+    pub fn calculate_unrealized_pnl(&self, marks: &Marks) -> Decimal {
         self.open_positions
             .iter()
             .filter_map(|id| self.positions.get(id))
-            .map(|pos| {
-                current_prices
-                    .get(&pos.market_id)
-                    .map(|price| price - pos.entry_price)
-                    .unwrap_or(Decimal::ZERO)
+            .flat_map(|pos| pos.legs.iter())
+            .filter(|leg| leg.exit_price.is_none())
+            .map(|leg| match marks.get(&mark_key(&leg.market_id, &leg.outcome)) {
+                Some(price) => leg.trading_pnl(*price),
+                None => -leg.fees,
             })
             .sum()
     }
@@ -194,3 +628,78 @@ impl Default for PositionManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MarketSource;
+
+    fn odds(market_id: &str, outcome: &str, odds: i64) -> MarketOdds {
+        MarketOdds {
+            market_id: market_id.to_string(),
+            outcome: outcome.to_string(),
+            odds: Decimal::new(odds, 2),
+            source: MarketSource::Polymarket,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        }
+    }
+
+    #[test]
+    fn test_arbitrage_position_aggregates_legs() {
+        let poly = odds("poly", "Yes", 40);
+        let btc = odds("btc", "No", 50);
+        let opportunity = ArbitrageOpportunity::new(poly.clone(), btc.clone(), Decimal::new(10, 2), 0.9);
+        let mut manager = PositionManager::new();
+
+        // 100 Yes at 0.40 + $1 fees, 80 No at 0.50
+        let id = manager
+            .open_position(
+                &opportunity,
+                vec![
+                    PositionLeg::long(Leg::Polymarket, &poly, Decimal::from(100), Decimal::new(40, 2))
+                        .with_fees(Decimal::ONE),
+                    PositionLeg::long(Leg::Btc, &btc, Decimal::from(80), Decimal::new(50, 2)),
+                ],
+            )
+            .unwrap();
+        let position = manager.get_position(&id).unwrap();
+
+        assert_eq!(position.total_cost(), Decimal::from(81));
+        assert_eq!(position.guaranteed_payout(), Decimal::from(80));
+        assert_eq!(position.hedge_ratio(), Decimal::new(8, 1));
+        // 80 hedged pairs cost 80% of the Yes leg (41 * 0.8) plus the No leg (40)
+        assert_eq!(position.locked_edge(), Decimal::from(80) - Decimal::new(328, 1) - Decimal::from(40));
+    }
+
+    #[test]
+    fn test_one_legged_position_is_unhedged_until_hedge_recorded() {
+        let poly = odds("poly", "Yes", 40);
+        let btc = odds("btc", "No", 50);
+        let opportunity = ArbitrageOpportunity::new(poly.clone(), btc.clone(), Decimal::new(10, 2), 0.9);
+        let mut manager = PositionManager::new();
+        let id = manager
+            .open_position(
+                &opportunity,
+                vec![PositionLeg::long(Leg::Polymarket, &poly, Decimal::from(10), Decimal::new(40, 2))],
+            )
+            .unwrap();
+        assert_eq!(manager.get_position(&id).unwrap().hedge_ratio(), Decimal::ZERO);
+
+        let report = UnwindReport {
+            action: UnwindAction::Hedged,
+            filled_leg: Leg::Polymarket,
+            cost: Decimal::new(20, 2),
+            price: Some(Decimal::new(52, 2)),
+            tx_id: Some("hedge".to_string()),
+            attempts: vec![],
+        };
+        manager.record_unwind(&id, &report, &btc).unwrap();
+
+        let position = manager.get_position(&id).unwrap();
+        let hedge = position.leg(Leg::Btc).unwrap();
+        assert_eq!((hedge.quantity, hedge.avg_cost, hedge.outcome.as_str()), (Decimal::from(10), Decimal::new(52, 2), "No"));
+        assert_eq!(position.hedge_ratio(), Decimal::ONE);
+        assert_eq!(manager.store().fills(&id).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+
+use crate::chains::liquid::OutcomeTokenHolding;
+use crate::models::{ArbitrageOpportunity, MarketOdds};
+use crate::positions::PositionLeg;
+use crate::resolver::ResolutionStatus;
+use crate::trader::{OrderLookup, TradeConfig, TradeExecutor, TradeResult};
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token bucket allowing `per_minute` calls, in bursts of up to one second's worth.
+/// Callers wait their turn in order.
+pub struct RateLimiter {
+    per_sec: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(limit: u32) -> Self {
+        let per_sec = f64::from(limit.max(1)) / 60.0;
+        let burst = per_sec.ceil().max(1.0);
+        Self {
+            per_sec,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// `None` for a limit of zero, meaning unlimited
+    pub fn optional(limit: u32) -> Option<Self> {
+        (limit > 0).then(|| Self::per_minute(limit))
+    }
+
+    pub async fn acquire(&self) {
+        // Holding the lock while sleeping queues later callers behind this one
+        let mut bucket = self.bucket.lock().await;
+        self.refill(&mut bucket);
+
+        if bucket.tokens < 1.0 {
+            let wait = (1.0 - bucket.tokens) / self.per_sec;
+            tokio::time::sleep(Duration::from_secs_f64(wait)).await;
+            self.refill(&mut bucket);
+        }
+        bucket.tokens = (bucket.tokens - 1.0).max(0.0);
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_sec).min(self.burst);
+        bucket.refilled_at = now;
+    }
+}
+
+/// Applies a venue's request-rate limit to every call and its order-rate
+/// limit to calls that place or cancel orders
+pub struct RateLimitedExecutor {
+    inner: Box<dyn TradeExecutor>,
+    requests: Option<RateLimiter>,
+    orders: Option<RateLimiter>,
+}
+
+impl RateLimitedExecutor {
+    /// Limits are per minute; zero disables a limit
+    pub fn new(inner: Box<dyn TradeExecutor>, requests_per_min: u32, orders_per_min: u32) -> Self {
+        Self {
+            inner,
+            requests: RateLimiter::optional(requests_per_min),
+            orders: RateLimiter::optional(orders_per_min),
+        }
+    }
+
+    async fn request(&self) {
+        if let Some(limiter) = &self.requests {
+            limiter.acquire().await;
+        }
+    }
+
+    async fn order(&self) {
+        if let Some(limiter) = &self.orders {
+            limiter.acquire().await;
+        }
+        self.request().await;
+    }
+}
+
+#[async_trait]
+impl TradeExecutor for RateLimitedExecutor {
+    async fn execute_arbitrage(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> Result<TradeResult> {
+        self.order().await;
+        self.inner.execute_arbitrage(opportunity, config).await
+    }
+
+    async fn approve_token(&self, token: &str, amount: Decimal) -> Result<String> {
+        self.order().await;
+        self.inner.approve_token(token, amount).await
+    }
+
+    async fn get_balance(&self) -> Result<Decimal> {
+        self.request().await;
+        self.inner.get_balance().await
+    }
+
+    async fn cancel_order(&self, tx_id: &str) -> Result<bool> {
+        self.order().await;
+        self.inner.cancel_order(tx_id).await
+    }
+
+    async fn sell_back(&self, odds: &MarketOdds, size: Decimal, min_price: Decimal) -> Result<TradeResult> {
+        self.order().await;
+        self.inner.sell_back(odds, size, min_price).await
+    }
+
+    async fn outcome_holdings(&self) -> Result<Vec<OutcomeTokenHolding>> {
+        self.request().await;
+        self.inner.outcome_holdings().await
+    }
+
+    async fn reported_holdings(&self, tracked: &[PositionLeg]) -> Result<Option<Vec<OutcomeTokenHolding>>> {
+        self.request().await;
+        self.inner.reported_holdings(tracked).await
+    }
+
+    async fn find_order(&self, client_order_id: &str) -> Result<OrderLookup> {
+        self.request().await;
+        self.inner.find_order(client_order_id).await
+    }
+
+    async fn cancel_all_orders(&self) -> Result<usize> {
+        self.order().await;
+        self.inner.cancel_all_orders().await
+    }
+
+    async fn market_resolution(&self, market_id: &str) -> Result<ResolutionStatus> {
+        self.request().await;
+        self.inner.market_resolution(market_id).await
+    }
+
+    async fn redeem(&self, leg: &PositionLeg) -> Result<Option<String>> {
+        self.order().await;
+        self.inner.redeem(leg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_limiter_spaces_calls_after_burst() {
+        // 120/min: bursts of 2, then one every 500ms
+        let limiter = RateLimiter::per_minute(120);
+        let start = Instant::now();
+
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(999) && elapsed < Duration::from_millis(1100), "{:?}", elapsed);
+    }
+}
@@ -0,0 +1,395 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::alerts::{Alert, AlertSink};
+use crate::config::RebalanceConfig;
+use crate::trader::Leg;
+use crate::treasury::{Asset, Treasury};
+
+/// Inventory a venue should hold, in USD
+#[derive(Debug, Clone, Copy)]
+pub struct TargetBand {
+    pub min: Decimal,
+    pub target: Decimal,
+    pub max: Decimal,
+}
+
+/// One hop of a transfer. Fixed fees come off first, then the rate.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferStep {
+    pub description: String,
+    pub fixed_fee_usd: Decimal,
+    pub fee_rate: Decimal,
+    pub minutes: u32,
+}
+
+impl TransferStep {
+    fn new(description: &str, fixed_fee_usd: Decimal, fee_rate: Decimal, minutes: u32) -> Self {
+        Self {
+            description: description.to_string(),
+            fixed_fee_usd,
+            fee_rate,
+            minutes,
+        }
+    }
+
+    fn apply(&self, amount: Decimal) -> Decimal {
+        ((amount - self.fixed_fee_usd) * (Decimal::ONE - self.fee_rate)).max(Decimal::ZERO)
+    }
+}
+
+/// A proposed move of inventory from one venue to the other
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferPlan {
+    pub from: Leg,
+    pub to: Leg,
+    pub asset: Asset,
+    pub amount_usd: Decimal,
+    pub received_usd: Decimal,
+    pub cost_usd: Decimal,
+    pub minutes: u32,
+    pub steps: Vec<TransferStep>,
+}
+
+impl TransferPlan {
+    pub fn summary(&self) -> String {
+        let steps: Vec<&str> = self.steps.iter().map(|s| s.description.as_str()).collect();
+        format!(
+            "move ${} {} -> {} ({}): receive ${}, cost ${}, ~{} min via {}",
+            self.amount_usd.round_dp(2),
+            self.from,
+            self.to,
+            self.asset,
+            self.received_usd.round_dp(2),
+            self.cost_usd.round_dp(2),
+            self.minutes,
+            steps.join(" -> ")
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RebalanceReport {
+    pub plans: Vec<TransferPlan>,
+    /// Venues too depleted to fund either side of a trade
+    pub depleted: Vec<Leg>,
+    /// Imbalances left alone, and why
+    pub skipped: Vec<String>,
+}
+
+/// Fees and settlement times of each hop between Polygon USDC and the BTC venue
+#[derive(Debug, Clone)]
+pub struct CostModel {
+    pub exchange_fee_rate: Decimal,
+    pub polygon_gas_usd: Decimal,
+    pub usdc_withdraw_fee_usd: Decimal,
+    pub btc_withdraw_fee_usd: Decimal,
+    pub onchain_fee_usd: Decimal,
+    pub swap_fee_rate: Decimal,
+}
+
+impl CostModel {
+    pub fn from_config(config: &RebalanceConfig) -> Self {
+        Self {
+            exchange_fee_rate: config.exchange_fee_rate,
+            polygon_gas_usd: config.polygon_gas_usd,
+            usdc_withdraw_fee_usd: config.usdc_withdraw_fee_usd,
+            btc_withdraw_fee_usd: config.btc_withdraw_fee_usd,
+            onchain_fee_usd: config.onchain_fee_usd,
+            swap_fee_rate: config.swap_fee_rate,
+        }
+    }
+
+    /// Minutes for a deposit or withdrawal of `asset` to settle on its chain
+    fn settlement_minutes(asset: Asset) -> u32 {
+        match asset {
+            // Exchanges typically want a few on-chain confirmations
+            Asset::Btc | Asset::Sats => 60,
+            Asset::Lbtc => 5,
+            Asset::Rbtc | Asset::RskCollateral => 10,
+            Asset::Stx => 30,
+            Asset::Usdc => 10,
+        }
+    }
+
+    /// Hops that move USD value between the venues through an exchange
+    pub fn route(&self, from: Leg, to: Leg, btc_asset: Asset) -> Vec<TransferStep> {
+        let zero = Decimal::ZERO;
+        let settle = Self::settlement_minutes(btc_asset);
+        let convert = match btc_asset {
+            Asset::Stx => "STX",
+            Asset::RskCollateral => "RSK collateral",
+            _ => "BTC",
+        };
+
+        match (from, to) {
+            (Leg::Polymarket, Leg::Btc) => {
+                let mut steps = vec![
+                    TransferStep::new("send USDC from Polygon to the exchange", self.polygon_gas_usd, zero, 5),
+                    TransferStep::new(&format!("buy {} with USDC", convert), zero, self.exchange_fee_rate, 1),
+                ];
+                match btc_asset {
+                    Asset::Sats => {
+                        steps.push(TransferStep::new("withdraw BTC on-chain", self.btc_withdraw_fee_usd, zero, settle));
+                        steps.push(TransferStep::new(
+                            "submarine swap on-chain BTC into Lightning",
+                            self.onchain_fee_usd,
+                            self.swap_fee_rate,
+                            30,
+                        ));
+                    }
+                    asset => steps.push(TransferStep::new(
+                        &format!("withdraw {} to the venue wallet", asset),
+                        self.btc_withdraw_fee_usd,
+                        zero,
+                        settle,
+                    )),
+                }
+                steps
+            }
+            (Leg::Btc, Leg::Polymarket) => {
+                let mut steps = Vec::new();
+                match btc_asset {
+                    Asset::Sats => {
+                        steps.push(TransferStep::new(
+                            "reverse swap Lightning into on-chain BTC",
+                            self.onchain_fee_usd,
+                            self.swap_fee_rate,
+                            10,
+                        ));
+                        steps.push(TransferStep::new("deposit BTC to the exchange", self.onchain_fee_usd, zero, settle));
+                    }
+                    Asset::Btc => {
+                        steps.push(TransferStep::new("deposit BTC to the exchange", self.onchain_fee_usd, zero, settle))
+                    }
+                    asset => steps.push(TransferStep::new(
+                        &format!("deposit {} to the exchange", asset),
+                        zero,
+                        zero,
+                        settle,
+                    )),
+                }
+                steps.push(TransferStep::new(&format!("sell {} for USDC", convert), zero, self.exchange_fee_rate, 1));
+                steps.push(TransferStep::new(
+                    "withdraw USDC to Polygon",
+                    self.usdc_withdraw_fee_usd,
+                    zero,
+                    Self::settlement_minutes(Asset::Usdc),
+                ));
+                steps
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Price a transfer of `amount_usd` along `route`
+    pub fn plan(&self, from: Leg, to: Leg, btc_asset: Asset, amount_usd: Decimal) -> TransferPlan {
+        let steps = self.route(from, to, btc_asset);
+        let received_usd = steps.iter().fold(amount_usd, |amount, step| step.apply(amount));
+        TransferPlan {
+            from,
+            to,
+            asset: btc_asset,
+            amount_usd,
+            received_usd,
+            cost_usd: amount_usd - received_usd,
+            minutes: steps.iter().map(|s| s.minutes).sum(),
+            steps,
+        }
+    }
+}
+
+/// Proposes transfers that keep each venue's inventory inside its target band
+pub struct RebalancePlanner {
+    bands: [(Leg, TargetBand); 2],
+    costs: CostModel,
+    btc_asset: Asset,
+    min_transfer_usd: Decimal,
+    max_cost_rate: Decimal,
+    depleted_usd: Decimal,
+}
+
+impl RebalancePlanner {
+    pub fn new(config: &RebalanceConfig, btc_asset: Asset) -> Self {
+        let polymarket = TargetBand {
+            min: config.polymarket_min_usd,
+            target: config.polymarket_target_usd,
+            max: config.polymarket_max_usd,
+        };
+        let btc = TargetBand {
+            min: config.btc_min_usd,
+            target: config.btc_target_usd,
+            max: config.btc_max_usd,
+        };
+        Self {
+            bands: [(Leg::Polymarket, polymarket), (Leg::Btc, btc)],
+            costs: CostModel::from_config(config),
+            btc_asset,
+            min_transfer_usd: config.min_transfer_usd,
+            max_cost_rate: config.max_cost_rate,
+            depleted_usd: config.depleted_usd,
+        }
+    }
+
+    fn band(&self, leg: Leg) -> TargetBand {
+        self.bands.iter().find(|(l, _)| *l == leg).map(|(_, b)| *b).expect("band for each leg")
+    }
+
+    /// Plan from per-venue inventory in USD
+    pub fn plan(&self, polymarket_usd: Decimal, btc_usd: Decimal) -> RebalanceReport {
+        let inventory = |leg: Leg| match leg {
+            Leg::Polymarket => polymarket_usd,
+            Leg::Btc => btc_usd,
+        };
+        let mut report = RebalanceReport::default();
+
+        for (poor, _) in self.bands {
+            let rich = poor.other();
+            let (poor_band, rich_band) = (self.band(poor), self.band(rich));
+            let (poor_usd, rich_usd) = (inventory(poor), inventory(rich));
+
+            if poor_usd < self.depleted_usd {
+                report.depleted.push(poor);
+            }
+            let below_min = poor_usd < poor_band.min;
+            if !below_min && rich_usd <= rich_band.max {
+                continue;
+            }
+
+            // Topping up a depleted venue may draw the donor down to its minimum;
+            // shedding excess only down to its target
+            let donor_floor = if below_min { rich_band.min } else { rich_band.target };
+            let amount = (poor_band.target - poor_usd).min(rich_usd - donor_floor);
+            if amount < self.min_transfer_usd {
+                report.skipped.push(format!(
+                    "{} at ${} is outside its band but {} at ${} has nothing to spare",
+                    if below_min { poor } else { rich },
+                    if below_min { poor_usd } else { rich_usd },
+                    rich,
+                    rich_usd
+                ));
+                continue;
+            }
+
+            let plan = self.costs.plan(rich, poor, self.btc_asset, amount);
+            if plan.cost_usd > amount * self.max_cost_rate {
+                report.skipped.push(format!("too expensive: {}", plan.summary()));
+                continue;
+            }
+            report.plans.push(plan);
+        }
+        report
+    }
+
+    /// Plan from the treasury's free balances and alert on depleted venues
+    pub async fn check(&self, treasury: &Treasury, alerts: &dyn AlertSink) -> Result<RebalanceReport> {
+        let polymarket = treasury.free_usd(Leg::Polymarket).unwrap_or_default();
+        let btc = treasury.free_usd(Leg::Btc).unwrap_or_default();
+        let report = self.plan(polymarket, btc);
+
+        for leg in &report.depleted {
+            let planned = report.plans.iter().any(|p| p.to == *leg);
+            let message = format!(
+                "{} holds ${} free, below ${}: it cannot fund both sides of a trade{}",
+                leg,
+                if *leg == Leg::Polymarket { polymarket } else { btc }.round_dp(2),
+                self.depleted_usd,
+                if planned { "; a transfer is proposed" } else { " and no transfer can refill it" }
+            );
+            alerts.send(&Alert::warning("Venue depleted", message, None)).await?;
+        }
+        for plan in &report.plans {
+            tracing::info!("Rebalance proposed: {}", plan.summary());
+        }
+        for skipped in &report.skipped {
+            tracing::info!("Rebalance skipped: {}", skipped);
+        }
+        Ok(report)
+    }
+}
+
+/// Carries out a transfer plan
+#[async_trait]
+pub trait TransferExecutor: Send + Sync {
+    async fn execute(&self, plan: &TransferPlan) -> Result<()>;
+}
+
+/// Hands each plan to an operator through the alert sink; nothing moves automatically
+pub struct ManualTransfers {
+    alerts: Box<dyn AlertSink>,
+}
+
+impl ManualTransfers {
+    pub fn new(alerts: Box<dyn AlertSink>) -> Self {
+        Self { alerts }
+    }
+}
+
+#[async_trait]
+impl TransferExecutor for ManualTransfers {
+    async fn execute(&self, plan: &TransferPlan) -> Result<()> {
+        self.alerts
+            .send(&Alert::warning("Rebalance needed", plan.summary(), None))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn planner(asset: Asset) -> RebalancePlanner {
+        RebalancePlanner::new(&RebalanceConfig::default(), asset)
+    }
+
+    #[test]
+    fn test_lightning_route_costs_every_hop() {
+        let costs = CostModel::from_config(&RebalanceConfig::default());
+        let plan = costs.plan(Leg::Polymarket, Leg::Btc, Asset::Sats, Decimal::from(1000));
+
+        assert_eq!(plan.steps.len(), 4);
+        assert_eq!(plan.steps[3].description, "submarine swap on-chain BTC into Lightning");
+        // (((1000 - 0.05) * 0.999) - 5 - 3) * 0.995
+        let expected = ((Decimal::new(99995, 2) * Decimal::new(999, 3)) - Decimal::from(8)) * Decimal::new(995, 3);
+        assert_eq!(plan.received_usd, expected);
+        assert_eq!(plan.cost_usd, Decimal::from(1000) - expected);
+        assert_eq!(plan.minutes, 5 + 1 + 60 + 30);
+
+        let back = costs.plan(Leg::Btc, Leg::Polymarket, Asset::Lbtc, Decimal::from(1000));
+        assert_eq!(back.steps.first().unwrap().description, "deposit L-BTC to the exchange");
+        assert_eq!(back.minutes, 5 + 1 + 10);
+    }
+
+    #[test]
+    fn test_depleted_venue_is_refilled_down_to_donor_minimum() {
+        let planner = planner(Asset::Sats);
+
+        // Polymarket at $80 wants $2,420 to reach target; btc can spare only 2800 - 500
+        let report = planner.plan(Decimal::from(80), Decimal::from(2800));
+        assert_eq!(report.plans.len(), 1);
+        let plan = &report.plans[0];
+        assert_eq!((plan.from, plan.to, plan.amount_usd), (Leg::Btc, Leg::Polymarket, Decimal::from(2300)));
+        assert_eq!(report.depleted, vec![Leg::Polymarket]);
+
+        // Both venues low: nothing to move
+        let report = planner.plan(Decimal::from(50), Decimal::from(520));
+        assert!(report.plans.is_empty());
+        assert_eq!(report.depleted, vec![Leg::Polymarket]);
+        assert_eq!(report.skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_excess_sheds_to_target_and_expensive_plans_are_skipped() {
+        let report = planner(Asset::Rbtc).plan(Decimal::from(6000), Decimal::from(1500));
+        assert_eq!(report.plans.len(), 1);
+        assert_eq!(report.plans[0].amount_usd, Decimal::from(1000));
+        assert!(report.depleted.is_empty());
+
+        // Only $120 can come out of Lightning, and swap and on-chain fees eat more than 2% of it
+        let report = planner(Asset::Sats).plan(Decimal::from(400), Decimal::from(620));
+        assert!(report.plans.is_empty());
+        assert!(report.skipped[0].starts_with("too expensive"));
+    }
+}
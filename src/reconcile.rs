@@ -0,0 +1,364 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::alerts::{Alert, AlertSink};
+use crate::chains::liquid::OutcomeTokenHolding;
+use crate::config::ReconcileConfig;
+use crate::positions::{Position, PositionLeg};
+use crate::trader::{ArbitrageExecutor, Leg};
+
+/// What to do about positions that disagree with their venue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconcilePolicy {
+    /// Report mismatches and leave positions alone
+    Alert,
+    /// Adopt the venue's holdings, then report what changed
+    Correct,
+}
+
+impl ReconcilePolicy {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "alert" => Ok(Self::Alert),
+            "correct" => Ok(Self::Correct),
+            other => Err(anyhow::anyhow!("Unknown reconcile policy: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    /// Tracked, but the venue reports no holding
+    Missing,
+    /// Held at the venue, but not tracked
+    Extra,
+    /// Held and tracked in different quantities
+    QuantityDiffers,
+}
+
+/// Disagreement between open positions and a venue on one outcome
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Mismatch {
+    pub venue: Leg,
+    pub market_id: String,
+    pub outcome: String,
+    pub kind: MismatchKind,
+    /// Shares tracked across open positions
+    pub tracked: Decimal,
+    /// Shares the venue reports
+    pub reported: Decimal,
+    /// Open positions holding the outcome, newest first
+    pub position_ids: Vec<String>,
+    /// Token the venue reported the holding under
+    pub token_id: Option<String>,
+}
+
+impl Mismatch {
+    /// One line of the diff report: `-` missing, `+` extra, `~` differing
+    pub fn describe(&self) -> String {
+        let marker = match self.kind {
+            MismatchKind::Missing => '-',
+            MismatchKind::Extra => '+',
+            MismatchKind::QuantityDiffers => '~',
+        };
+        format!(
+            "{} {} {} {}: tracked {}, venue {}",
+            marker, self.venue, self.market_id, self.outcome, self.tracked, self.reported
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconciliationReport {
+    pub mismatches: Vec<Mismatch>,
+    /// Venues that could not report holdings
+    pub unchecked: Vec<Leg>,
+    /// Positions opened, resized or dropped by the correct policy
+    pub corrected: Vec<String>,
+}
+
+impl ReconciliationReport {
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn diff(&self) -> String {
+        self.mismatches.iter().map(Mismatch::describe).collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// Compares open positions with the holdings each venue reports, and
+/// alerts on or corrects the differences
+pub struct Reconciler {
+    policy: ReconcilePolicy,
+    tolerance: Decimal,
+}
+
+impl Reconciler {
+    pub fn new(policy: ReconcilePolicy, tolerance: Decimal) -> Self {
+        Self { policy, tolerance }
+    }
+
+    pub fn from_config(config: &ReconcileConfig) -> Result<Self> {
+        Ok(Self::new(ReconcilePolicy::from_name(&config.policy)?, config.quantity_tolerance))
+    }
+
+    /// Mismatches between the open legs held at `venue` and its holdings
+    pub fn compare(&self, venue: Leg, positions: &[Position], holdings: &[OutcomeTokenHolding]) -> Vec<Mismatch> {
+        type Key = (String, String);
+        type Holders = Vec<(DateTime<Utc>, String)>;
+        let mut tracked: BTreeMap<Key, (Decimal, Holders)> = BTreeMap::new();
+        for position in positions {
+            for leg in position.legs.iter().filter(|leg| leg.venue == venue && leg.exit_price.is_none()) {
+                let entry = tracked.entry((leg.market_id.clone(), leg.outcome.clone())).or_default();
+                entry.0 += leg.quantity;
+                entry.1.push((position.opened_at, position.id.clone()));
+            }
+        }
+        let mut reported: BTreeMap<Key, (Decimal, Option<String>)> = BTreeMap::new();
+        for holding in holdings {
+            let entry = reported.entry((holding.market_id.clone(), holding.outcome.clone())).or_default();
+            entry.0 += holding.quantity;
+            entry.1 = Some(holding.asset_id.clone());
+        }
+
+        let keys: BTreeSet<&Key> = tracked.keys().chain(reported.keys()).collect();
+        keys.into_iter()
+            .filter_map(|key| {
+                let (tracked_qty, mut holders) = tracked.get(key).cloned().unwrap_or_default();
+                let (reported_qty, token_id) = reported.get(key).cloned().unwrap_or_default();
+                if (tracked_qty - reported_qty).abs() <= self.tolerance {
+                    return None;
+                }
+                let kind = if reported_qty.is_zero() {
+                    MismatchKind::Missing
+                } else if tracked_qty.is_zero() {
+                    MismatchKind::Extra
+                } else {
+                    MismatchKind::QuantityDiffers
+                };
+                holders.sort_by(|a, b| b.cmp(a));
+                Some(Mismatch {
+                    venue,
+                    market_id: key.0.clone(),
+                    outcome: key.1.clone(),
+                    kind,
+                    tracked: tracked_qty,
+                    reported: reported_qty,
+                    position_ids: holders.into_iter().map(|(_, id)| id).collect(),
+                    token_id,
+                })
+            })
+            .collect()
+    }
+
+    /// Reconcile every venue that can report holdings. Mismatches raise one
+    /// alert carrying the diff, after any corrections the policy allows.
+    pub async fn check(&self, executor: &ArbitrageExecutor, alerts: &dyn AlertSink) -> ReconciliationReport {
+        let mut report = ReconciliationReport::default();
+        let open = executor.position_manager().get_open_positions();
+
+        for venue in [Leg::Polymarket, Leg::Btc] {
+            let tracked: Vec<PositionLeg> = open
+                .iter()
+                .flat_map(|position| position.legs.iter())
+                .filter(|leg| leg.venue == venue && leg.exit_price.is_none())
+                .cloned()
+                .collect();
+            match executor.executor(venue).reported_holdings(&tracked).await {
+                Ok(Some(holdings)) => report.mismatches.extend(self.compare(venue, &open, &holdings)),
+                Ok(None) => report.unchecked.push(venue),
+                Err(e) => {
+                    tracing::warn!("Failed to fetch {} holdings: {}", venue, e);
+                    report.unchecked.push(venue);
+                }
+            }
+        }
+        if report.is_clean() {
+            return report;
+        }
+
+        if self.policy == ReconcilePolicy::Correct {
+            for mismatch in &report.mismatches {
+                let corrected = self.correct(executor, mismatch);
+                report.corrected.extend(corrected);
+            }
+            report.corrected.sort();
+            report.corrected.dedup();
+        }
+
+        let title = match self.policy {
+            ReconcilePolicy::Alert => "Position mismatch",
+            ReconcilePolicy::Correct => "Positions corrected",
+        };
+        let message = format!(
+            "{} outcome(s) differ from venue holdings:\n{}",
+            report.mismatches.len(),
+            report.diff()
+        );
+        if let Err(e) = alerts.send(&Alert::warning(title, message, None)).await {
+            tracing::error!("Failed to send reconciliation alert: {}", e);
+        }
+        report
+    }
+
+    /// Bring tracked quantities in line with the venue, resizing the newest
+    /// positions first. Returns the positions changed.
+    fn correct(&self, executor: &ArbitrageExecutor, mismatch: &Mismatch) -> Vec<String> {
+        let mut positions = executor.position_manager();
+        if mismatch.kind == MismatchKind::Extra {
+            return match positions.track_holding(
+                mismatch.venue,
+                &mismatch.market_id,
+                &mismatch.outcome,
+                mismatch.token_id.as_deref(),
+                mismatch.reported,
+            ) {
+                Ok(id) => vec![id],
+                Err(e) => {
+                    tracing::error!("Failed to track {} holding {}: {}", mismatch.venue, mismatch.market_id, e);
+                    vec![]
+                }
+            };
+        }
+
+        let mut excess = mismatch.tracked - mismatch.reported;
+        let mut changed = Vec::new();
+        for id in &mismatch.position_ids {
+            if excess.is_zero() {
+                break;
+            }
+            let Some(current) = positions
+                .get_position(id)
+                .and_then(|position| position.leg(mismatch.venue).map(|leg| leg.quantity))
+            else {
+                continue;
+            };
+            let quantity = (current - excess).max(Decimal::ZERO);
+            excess -= current - quantity;
+            match positions.set_leg_quantity(id, mismatch.venue, quantity) {
+                Ok(()) => changed.push(id.clone()),
+                Err(e) => tracing::error!("Failed to resize the {} leg of {}: {}", mismatch.venue, id, e),
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::LogAlertSink;
+    use crate::models::{ArbitrageOpportunity, MarketOdds, MarketSource};
+    use crate::positions::PositionStatus;
+    use crate::trader::{TradeConfig, TradeExecutor, TradeResult};
+    use async_trait::async_trait;
+
+    struct Venue {
+        holdings: Vec<OutcomeTokenHolding>,
+    }
+
+    impl Venue {
+        fn holding(market_id: &str, outcome: &str, quantity: i64) -> OutcomeTokenHolding {
+            OutcomeTokenHolding {
+                asset_id: format!("{}-{}", market_id, outcome),
+                market_id: market_id.to_string(),
+                outcome: outcome.to_string(),
+                quantity: Decimal::from(quantity),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TradeExecutor for Venue {
+        async fn execute_arbitrage(&self, _opportunity: &ArbitrageOpportunity, _config: &TradeConfig) -> Result<TradeResult> {
+            Err(anyhow::anyhow!("not trading"))
+        }
+
+        async fn approve_token(&self, _token: &str, _amount: Decimal) -> Result<String> {
+            Ok(String::new())
+        }
+
+        async fn get_balance(&self) -> Result<Decimal> {
+            Ok(Decimal::ZERO)
+        }
+
+        async fn reported_holdings(&self, _tracked: &[PositionLeg]) -> Result<Option<Vec<OutcomeTokenHolding>>> {
+            Ok(Some(self.holdings.clone()))
+        }
+    }
+
+    /// Yes on Polymarket hedged by No on the BTC venue, 100 shares each
+    fn executor(poly: Vec<OutcomeTokenHolding>, btc: Vec<OutcomeTokenHolding>) -> (ArbitrageExecutor, String) {
+        let executor =
+            ArbitrageExecutor::with_executors(Box::new(Venue { holdings: poly }), Box::new(Venue { holdings: btc }));
+        let odds = |source, market_id: &str, outcome: &str| MarketOdds {
+            market_id: market_id.to_string(),
+            outcome: outcome.to_string(),
+            odds: Decimal::new(45, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        let opportunity = ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket, "poly", "Yes"),
+            odds(MarketSource::BitcoinPredictionMarket, "btc", "No"),
+            Decimal::new(10, 2),
+            0.9,
+        );
+        let legs = vec![
+            PositionLeg::long(Leg::Polymarket, &opportunity.polymarket_odds, Decimal::from(100), Decimal::new(45, 2)),
+            PositionLeg::long(Leg::Btc, &opportunity.btc_market_odds, Decimal::from(100), Decimal::new(45, 2)),
+        ];
+        let id = executor.position_manager().open_position(&opportunity, legs).unwrap();
+        (executor, id)
+    }
+
+    #[tokio::test]
+    async fn test_alert_policy_classifies_without_touching_positions() {
+        let (executor, id) = executor(
+            vec![Venue::holding("poly", "Yes", 80)],
+            vec![Venue::holding("other", "Yes", 5)],
+        );
+        let reconciler = Reconciler::new(ReconcilePolicy::Alert, Decimal::new(1, 4));
+
+        let report = reconciler.check(&executor, &LogAlertSink).await;
+        let kinds: Vec<_> = report.mismatches.iter().map(|m| (m.venue, m.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (Leg::Polymarket, MismatchKind::QuantityDiffers),
+                (Leg::Btc, MismatchKind::Missing),
+                (Leg::Btc, MismatchKind::Extra),
+            ]
+        );
+        assert_eq!(report.mismatches[1].position_ids, vec![id.clone()]);
+        assert!(report.diff().contains("- btc btc No: tracked 100, venue 0"));
+        assert!(report.corrected.is_empty());
+        let position = executor.position_manager().get_position(&id).unwrap();
+        assert_eq!(position.leg(Leg::Polymarket).unwrap().quantity, Decimal::from(100));
+    }
+
+    #[tokio::test]
+    async fn test_correct_policy_adopts_venue_holdings() {
+        let (executor, id) = executor(
+            vec![Venue::holding("poly", "Yes", 80)],
+            vec![Venue::holding("other", "Yes", 5)],
+        );
+        let reconciler = Reconciler::new(ReconcilePolicy::Correct, Decimal::new(1, 4));
+
+        let report = reconciler.check(&executor, &LogAlertSink).await;
+        assert_eq!(report.corrected.len(), 2);
+        assert!(report.corrected.contains(&id));
+        assert_eq!(executor.position_manager().get_open_positions().len(), 2);
+        let position = executor.position_manager().get_position(&id).unwrap();
+        assert_eq!(position.leg(Leg::Polymarket).unwrap().quantity, Decimal::from(80));
+        assert_eq!(position.leg(Leg::Btc).unwrap().quantity, Decimal::ZERO);
+        assert_eq!(position.status, PositionStatus::Open);
+        assert!(reconciler.check(&executor, &LogAlertSink).await.is_clean());
+    }
+}
@@ -0,0 +1,396 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::alerts::{Alert, AlertSink};
+use crate::positions::{Position, PositionType};
+use crate::trader::{ArbitrageExecutor, Leg};
+
+/// Resolution state of a market at its venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ResolutionStatus {
+    Open,
+    Resolved { winning_outcome: String },
+    /// The proposed outcome is challenged; nothing settles until it clears
+    Disputed,
+}
+
+impl ResolutionStatus {
+    /// Payout per share of `outcome`: 1 if it won, 0 if it lost, `None`
+    /// while unresolved
+    pub fn payout(&self, outcome: &str) -> Option<Decimal> {
+        match self {
+            Self::Resolved { winning_outcome } if winning_outcome.eq_ignore_ascii_case(outcome) => Some(Decimal::ONE),
+            Self::Resolved { .. } => Some(Decimal::ZERO),
+            Self::Open | Self::Disputed => None,
+        }
+    }
+}
+
+/// Why a position's resolution needs an operator
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IncidentKind {
+    /// A leg's market is disputed at its venue
+    Disputed { leg: Leg, market_id: String },
+    /// Hedged legs should pay out exactly once; these won on both or neither
+    Divergent { winners: Vec<Leg> },
+}
+
+/// A resolution-divergence incident
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolutionIncident {
+    pub position_id: String,
+    pub kind: IncidentKind,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl ResolutionIncident {
+    fn describe(&self) -> String {
+        match &self.kind {
+            IncidentKind::Disputed { leg, market_id } => {
+                format!("{} market {} of position {} is disputed", leg, market_id, self.position_id)
+            }
+            IncidentKind::Divergent { winners } if winners.is_empty() => {
+                format!("Both legs of position {} resolved as losers", self.position_id)
+            }
+            IncidentKind::Divergent { .. } => format!("Both legs of position {} resolved as winners", self.position_id),
+        }
+    }
+}
+
+/// A position closed at its resolution payouts
+#[derive(Debug, Clone, Serialize)]
+pub struct SettledPosition {
+    pub position_id: String,
+    pub payouts: Vec<(Leg, Decimal)>,
+    /// Redemption txs or payment hashes of the winning legs
+    pub redemptions: Vec<(Leg, String)>,
+    pub pnl: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolutionReport {
+    pub settled: Vec<SettledPosition>,
+    /// Incidents first seen on this pass
+    pub incidents: Vec<ResolutionIncident>,
+    /// Positions still waiting on a venue
+    pub pending: usize,
+}
+
+/// Settles open positions as their markets resolve: polls each leg's venue,
+/// redeems winning legs and closes the position at the payout prices.
+///
+/// Redemptions are remembered until the position closes so a failure on one
+/// leg doesn't redeem the other twice on the next pass.
+#[derive(Default)]
+pub struct Resolver {
+    redeemed: Mutex<HashMap<(String, Leg), Option<String>>>,
+    flagged: Mutex<HashSet<(String, String)>>,
+    incidents: Mutex<Vec<ResolutionIncident>>,
+    /// Divergent positions an operator has checked and allowed to settle
+    acknowledged: Mutex<HashSet<String>>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every incident flagged so far
+    pub fn incidents(&self) -> Vec<ResolutionIncident> {
+        self.incidents.lock().expect("resolver lock poisoned").clone()
+    }
+
+    /// Let a divergent position settle at its venue payouts on the next pass,
+    /// once an operator has checked it
+    pub fn acknowledge(&self, position_id: &str) {
+        self.acknowledged.lock().expect("resolver lock poisoned").insert(position_id.to_string());
+    }
+
+    pub async fn check(&self, executor: &ArbitrageExecutor, alerts: &dyn AlertSink) -> ResolutionReport {
+        let mut report = ResolutionReport::default();
+        let mut statuses: HashMap<(Leg, String), Option<ResolutionStatus>> = HashMap::new();
+        let open = executor.position_manager().get_open_positions();
+
+        for position in open {
+            let mut payouts = Vec::new();
+            for leg in &position.legs {
+                let key = (leg.venue, leg.market_id.clone());
+                if !statuses.contains_key(&key) {
+                    let status = match executor.executor(leg.venue).market_resolution(&leg.market_id).await {
+                        Ok(status) => Some(status),
+                        Err(e) => {
+                            tracing::debug!("No resolution for {} market {}: {}", leg.venue, leg.market_id, e);
+                            None
+                        }
+                    };
+                    statuses.insert(key.clone(), status);
+                }
+
+                match &statuses[&key] {
+                    Some(ResolutionStatus::Disputed) => {
+                        let kind = IncidentKind::Disputed {
+                            leg: leg.venue,
+                            market_id: leg.market_id.clone(),
+                        };
+                        self.flag(&position, kind, alerts, &mut report).await;
+                    }
+                    Some(status) => payouts.extend(status.payout(&leg.outcome).map(|p| (leg.venue, p))),
+                    None => {}
+                }
+            }
+
+            if payouts.is_empty() || payouts.len() < position.legs.len() {
+                report.pending += 1;
+                continue;
+            }
+            if position.position_type == PositionType::Arbitrage && payouts.len() == 2 {
+                let winners: Vec<Leg> = payouts.iter().filter(|(_, p)| !p.is_zero()).map(|(leg, _)| *leg).collect();
+                if winners.len() != 1 {
+                    self.flag(&position, IncidentKind::Divergent { winners }, alerts, &mut report).await;
+                    // Like a dispute, the payouts can't be trusted until someone looks
+                    if !self.acknowledged.lock().expect("resolver lock poisoned").contains(&position.id) {
+                        report.pending += 1;
+                        continue;
+                    }
+                }
+            }
+
+            match self.settle(executor, &position, payouts, alerts).await {
+                Some(settled) => report.settled.push(settled),
+                None => report.pending += 1,
+            }
+        }
+
+        report
+    }
+
+    /// Redeem the winning legs, then close the position at its payouts.
+    /// `None` if a redemption failed and the position stays open.
+    async fn settle(
+        &self,
+        executor: &ArbitrageExecutor,
+        position: &Position,
+        payouts: Vec<(Leg, Decimal)>,
+        alerts: &dyn AlertSink,
+    ) -> Option<SettledPosition> {
+        let mut redemptions = Vec::new();
+        for leg in position.legs.iter().filter(|leg| payouts.contains(&(leg.venue, Decimal::ONE))) {
+            let key = (position.id.clone(), leg.venue);
+            let done = self.redeemed.lock().expect("resolver lock poisoned").get(&key).cloned();
+            let tx = match done {
+                Some(tx) => tx,
+                None => match executor.executor(leg.venue).redeem(leg).await {
+                    Ok(tx) => {
+                        self.redeemed.lock().expect("resolver lock poisoned").insert(key, tx.clone());
+                        tx
+                    }
+                    Err(e) => {
+                        let alert = Alert::warning(
+                            "Redemption failed",
+                            format!("Could not redeem the {} leg of {}: {}", leg.venue, position.id, e),
+                            Some(position.id.clone()),
+                        );
+                        if let Err(e) = alerts.send(&alert).await {
+                            tracing::error!("Failed to send redemption alert: {}", e);
+                        }
+                        return None;
+                    }
+                },
+            };
+            redemptions.extend(tx.map(|tx| (leg.venue, tx)));
+        }
+
+        for (leg, tx) in &redemptions {
+            if let Err(e) = executor.position_manager().record_tx(&position.id, *leg, tx) {
+                tracing::error!("Failed to record redemption {} on {}: {}", tx, position.id, e);
+            }
+        }
+        let btc_usd = executor.btc_usd(Leg::Btc).await;
+        let closed = match executor.close_position(&position.id, &payouts, btc_usd) {
+            Ok(closed) => closed,
+            Err(e) => {
+                tracing::error!("Failed to settle resolved position {}: {}", position.id, e);
+                return None;
+            }
+        };
+        self.redeemed.lock().expect("resolver lock poisoned").retain(|(id, _), _| id != &position.id);
+        self.acknowledged.lock().expect("resolver lock poisoned").remove(&position.id);
+
+        Some(SettledPosition {
+            position_id: position.id.clone(),
+            payouts,
+            redemptions,
+            pnl: closed.pnl,
+        })
+    }
+
+    /// Record and alert on an incident the first time it is seen
+    async fn flag(&self, position: &Position, kind: IncidentKind, alerts: &dyn AlertSink, report: &mut ResolutionReport) {
+        let fingerprint = (position.id.clone(), format!("{:?}", kind));
+        if !self.flagged.lock().expect("resolver lock poisoned").insert(fingerprint) {
+            return;
+        }
+
+        let incident = ResolutionIncident {
+            position_id: position.id.clone(),
+            kind,
+            detected_at: Utc::now(),
+        };
+        let alert = Alert::critical("Resolution divergence", incident.describe(), Some(position.id.clone()));
+        if let Err(e) = alerts.send(&alert).await {
+            tracing::error!("Failed to send resolution alert: {}", e);
+        }
+        self.incidents.lock().expect("resolver lock poisoned").push(incident.clone());
+        report.incidents.push(incident);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::LogAlertSink;
+    use crate::models::{ArbitrageOpportunity, MarketOdds, MarketSource};
+    use crate::positions::{PositionLeg, PositionStatus};
+    use crate::trader::{TradeConfig, TradeExecutor, TradeResult};
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Clones share state, so tests keep a handle on the boxed venue
+    #[derive(Clone)]
+    struct Venue {
+        status: Arc<Mutex<ResolutionStatus>>,
+        redemptions: Arc<AtomicUsize>,
+    }
+
+    impl Venue {
+        fn new(status: ResolutionStatus) -> Self {
+            Self {
+                status: Arc::new(Mutex::new(status)),
+                redemptions: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+
+        fn redemptions(&self) -> usize {
+            self.redemptions.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl TradeExecutor for Venue {
+        async fn execute_arbitrage(&self, _opportunity: &ArbitrageOpportunity, _config: &TradeConfig) -> Result<TradeResult> {
+            Err(anyhow::anyhow!("not trading"))
+        }
+
+        async fn approve_token(&self, _token: &str, _amount: Decimal) -> Result<String> {
+            Ok(String::new())
+        }
+
+        async fn get_balance(&self) -> Result<Decimal> {
+            Ok(Decimal::ZERO)
+        }
+
+        async fn market_resolution(&self, _market_id: &str) -> Result<ResolutionStatus> {
+            Ok(self.status.lock().unwrap().clone())
+        }
+
+        async fn redeem(&self, _leg: &PositionLeg) -> Result<Option<String>> {
+            let n = self.redemptions.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(format!("redeem-{}", n)))
+        }
+    }
+
+    fn resolved(outcome: &str) -> ResolutionStatus {
+        ResolutionStatus::Resolved {
+            winning_outcome: outcome.to_string(),
+        }
+    }
+
+    /// Yes on Polymarket hedged by No on the BTC venue, 100 shares at 0.45 each
+    fn open_position(executor: &ArbitrageExecutor) -> String {
+        let odds = |source, market_id: &str, outcome: &str| MarketOdds {
+            market_id: market_id.to_string(),
+            outcome: outcome.to_string(),
+            odds: Decimal::new(45, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        let opportunity = ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket, "poly", "Yes"),
+            odds(MarketSource::BitcoinPredictionMarket, "btc", "No"),
+            Decimal::new(10, 2),
+            0.9,
+        );
+        let legs = vec![
+            PositionLeg::long(Leg::Polymarket, &opportunity.polymarket_odds, Decimal::from(100), Decimal::new(45, 2)),
+            PositionLeg::long(Leg::Btc, &opportunity.btc_market_odds, Decimal::from(100), Decimal::new(45, 2)),
+        ];
+        executor.position_manager().open_position(&opportunity, legs).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_settles_once_both_venues_resolve() {
+        let poly = Venue::new(resolved("Yes"));
+        let btc = Venue::new(ResolutionStatus::Open);
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly.clone()), Box::new(btc.clone()));
+        let id = open_position(&executor);
+        let resolver = Resolver::new();
+
+        let report = resolver.check(&executor, &LogAlertSink).await;
+        assert_eq!((report.settled.len(), report.pending), (0, 1));
+        assert_eq!(poly.redemptions(), 0);
+
+        // Polymarket Yes won, so the BTC-side No lost: only Polymarket redeems
+        *btc.status.lock().unwrap() = resolved("Yes");
+        let report = resolver.check(&executor, &LogAlertSink).await;
+        assert_eq!(report.settled.len(), 1);
+        assert!(report.incidents.is_empty());
+        assert_eq!((poly.redemptions(), btc.redemptions()), (1, 0));
+
+        let position = executor.position_manager().get_position(&id).unwrap();
+        assert_eq!(position.status, PositionStatus::Closed);
+        assert_eq!(position.pnl, Some(Decimal::from(10)));
+        assert!(position.leg(Leg::Polymarket).unwrap().tx_ids.contains(&"redeem-0".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_flags_disputes_and_divergent_legs() {
+        let executor = ArbitrageExecutor::with_executors(
+            Box::new(Venue::new(ResolutionStatus::Disputed)),
+            Box::new(Venue::new(resolved("No"))),
+        );
+        let disputed = open_position(&executor);
+        let resolver = Resolver::new();
+
+        let report = resolver.check(&executor, &LogAlertSink).await;
+        assert_eq!(report.pending, 1);
+        assert_eq!(report.incidents[0].position_id, disputed);
+        // Already flagged: no second alert
+        assert!(resolver.check(&executor, &LogAlertSink).await.incidents.is_empty());
+
+        // Yes and No both won: flagged and held open until acknowledged
+        let executor = ArbitrageExecutor::with_executors(
+            Box::new(Venue::new(resolved("Yes"))),
+            Box::new(Venue::new(resolved("No"))),
+        );
+        let divergent = open_position(&executor);
+        let report = resolver.check(&executor, &LogAlertSink).await;
+        assert_eq!((report.settled.len(), report.pending), (0, 1));
+        assert_eq!(
+            report.incidents[0].kind,
+            IncidentKind::Divergent { winners: vec![Leg::Polymarket, Leg::Btc] }
+        );
+        assert_eq!(resolver.incidents().len(), 2);
+        assert_eq!(executor.position_manager().get_position(&divergent).unwrap().status, PositionStatus::Open);
+
+        resolver.acknowledge(&divergent);
+        assert_eq!(resolver.check(&executor, &LogAlertSink).await.settled.len(), 1);
+    }
+}
@@ -0,0 +1,454 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::config::RiskConfig;
+use crate::models::{ArbitrageOpportunity, MarketOdds};
+use crate::ledger::PnlLedger;
+use crate::pnl::{mark_key, Marks};
+use crate::positions::PositionManager;
+use crate::trader::{Leg, TradeConfig};
+
+/// Why the risk engine turned a trade down
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum RiskRejection {
+    MaxOpenPositions { open: usize, limit: u32 },
+    DailyLoss { loss: Decimal, limit: Decimal },
+    MarketExposure { leg: Leg, market_id: String, exposure: Decimal, limit: Decimal },
+    VenueExposure { leg: Leg, exposure: Decimal, limit: Decimal },
+    LowConfidence { confidence: f64, minimum: f64 },
+    StaleQuote { leg: Leg, age_secs: i64, limit_secs: u64 },
+    ResolutionMismatch { polymarket: DateTime<Utc>, btc: DateTime<Utc> },
+}
+
+impl RiskRejection {
+    /// Stable name for logs and counters
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MaxOpenPositions { .. } => "max_open_positions",
+            Self::DailyLoss { .. } => "daily_loss",
+            Self::MarketExposure { .. } => "market_exposure",
+            Self::VenueExposure { .. } => "venue_exposure",
+            Self::LowConfidence { .. } => "low_confidence",
+            Self::StaleQuote { .. } => "stale_quote",
+            Self::ResolutionMismatch { .. } => "resolution_mismatch",
+        }
+    }
+}
+
+impl std::fmt::Display for RiskRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MaxOpenPositions { open, limit } => write!(f, "{} open positions (limit {})", open, limit),
+            Self::DailyLoss { loss, limit } => write!(f, "daily loss {} reached limit {}", loss, limit),
+            Self::MarketExposure { leg, market_id, exposure, limit } => {
+                write!(f, "exposure on {} {} would be {} (limit {})", leg, market_id, exposure, limit)
+            }
+            Self::VenueExposure { leg, exposure, limit } => {
+                write!(f, "{} exposure would be {} (limit {})", leg, exposure, limit)
+            }
+            Self::LowConfidence { confidence, minimum } => {
+                write!(f, "confidence {:.2} below {:.2}", confidence, minimum)
+            }
+            Self::StaleQuote { leg, age_secs, limit_secs } => {
+                write!(f, "{} quote is {}s old (limit {}s)", leg, age_secs, limit_secs)
+            }
+            Self::ResolutionMismatch { polymarket, btc } => {
+                write!(f, "markets resolve at {} and {}", polymarket, btc)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RiskDecision {
+    Approved,
+    /// Every check that failed
+    Rejected(Vec<RiskRejection>),
+}
+
+impl RiskDecision {
+    pub fn is_approved(&self) -> bool {
+        *self == Self::Approved
+    }
+}
+
+/// Exposure one leg of a trade adds to its venue and market
+#[derive(Debug, Clone)]
+struct Exposure {
+    venue: Leg,
+    market_id: String,
+    notional: Decimal,
+}
+
+/// Pre-trade checks against `RiskConfig`, run between detection and execution.
+/// An approved trade holds its exposure until `release`, so trades in flight
+/// count against the limits like open positions.
+pub struct RiskEngine {
+    config: RiskConfig,
+    /// Latest marks by market and outcome, for unrealized PnL
+    marks: Mutex<Marks>,
+    rejections: Mutex<BTreeMap<&'static str, u64>>,
+    ledger: Option<PnlLedger>,
+    /// Exposure of approved trades not yet settled, by idempotency key
+    in_flight: Mutex<HashMap<String, Vec<Exposure>>>,
+}
+
+impl RiskEngine {
+    pub fn new(config: RiskConfig) -> Self {
+        Self {
+            config,
+            marks: Mutex::new(Marks::new()),
+            rejections: Mutex::new(BTreeMap::new()),
+            ledger: None,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take realized PnL from the ledger, by trading day, instead of from
+    /// positions closed since midnight UTC
+    pub fn with_ledger(mut self, ledger: PnlLedger) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    pub fn ledger(&self) -> Option<&PnlLedger> {
+        self.ledger.as_ref()
+    }
+
+    pub fn update_mark(&self, market_id: &str, outcome: &str, price: Decimal) {
+        self.marks
+            .lock()
+            .expect("risk marks lock poisoned")
+            .insert(mark_key(market_id, outcome), price);
+    }
+
+    /// Rejections so far, by reason code
+    pub fn rejection_counts(&self) -> BTreeMap<&'static str, u64> {
+        self.rejections.lock().expect("risk counters lock poisoned").clone()
+    }
+
+    /// Realized PnL today plus unrealized PnL of open positions at the
+    /// latest marks. With a ledger, today is the current trading day and
+    /// fees and unwind costs count when paid.
+    pub fn daily_pnl(&self, positions: &PositionManager) -> Decimal {
+        let unrealized = positions.calculate_unrealized_pnl(&self.marks.lock().expect("risk marks lock poisoned"));
+        if let Some(ledger) = self.ledger.as_ref() {
+            // Unrealized gross of fees and unwind costs: the ledger books those the day they are paid
+            let paid: Decimal = positions
+                .get_open_positions()
+                .iter()
+                .map(|p| {
+                    let fees: Decimal = p.legs.iter().filter(|l| l.exit_price.is_none()).map(|l| l.fees).sum();
+                    fees + p.unwind_cost.unwrap_or_default()
+                })
+                .sum();
+            return ledger.day_total(Utc::now()) + unrealized + paid;
+        }
+
+        let today = Utc::now().date_naive();
+        let realized: Decimal = positions
+            .get_all_positions()
+            .iter()
+            .filter(|p| p.closed_at.is_some_and(|at| at.date_naive() == today))
+            .filter_map(|p| p.pnl)
+            .sum();
+
+        realized + unrealized
+    }
+
+    /// What can still be lost today before `max_daily_loss` is reached
+    pub fn remaining_loss_budget(&self, positions: &PositionManager) -> Decimal {
+        (self.config.max_daily_loss + self.daily_pnl(positions)).max(Decimal::ZERO)
+    }
+
+    /// Check a trade and, if approved, hold its exposure until `release`
+    pub fn check(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+        positions: &PositionManager,
+    ) -> RiskDecision {
+        // Check and reserve under one lock so concurrent trades can't both take the same headroom
+        let mut in_flight = self.in_flight.lock().expect("risk in-flight lock poisoned");
+        in_flight.remove(&config.idempotency_key);
+
+        let mut rejections = Vec::new();
+        let risk = &self.config;
+        let open = positions.get_open_positions();
+
+        let count = open.len() + in_flight.len();
+        if count >= risk.max_open_positions as usize {
+            rejections.push(RiskRejection::MaxOpenPositions { open: count, limit: risk.max_open_positions });
+        }
+
+        let loss = -self.daily_pnl(positions);
+        if loss >= risk.max_daily_loss {
+            if risk.stop_on_max_loss {
+                rejections.push(RiskRejection::DailyLoss { loss, limit: risk.max_daily_loss });
+            } else {
+                tracing::warn!("Daily loss {} is past {}, trading on", loss, risk.max_daily_loss);
+            }
+        }
+
+        // Open legs count against their venue and market at cost, trades in flight at their limits
+        let held: Vec<Exposure> = open
+            .iter()
+            .flat_map(|p| p.legs.iter())
+            .map(|l| Exposure { venue: l.venue, market_id: l.market_id.clone(), notional: l.cost_basis() })
+            .chain(in_flight.values().flatten().cloned())
+            .collect();
+        let legs = [(Leg::Polymarket, &opportunity.polymarket_odds), (Leg::Btc, &opportunity.btc_market_odds)];
+        let adding = legs.map(|(venue, odds)| Exposure {
+            venue,
+            market_id: odds.market_id.clone(),
+            notional: config.limit_price(odds.odds) * config.size,
+        });
+        let total = |matches: &dyn Fn(&Exposure) -> bool| -> Decimal {
+            held.iter().chain(&adding).filter(|e| matches(e)).map(|e| e.notional).sum()
+        };
+
+        // Market ids are only unique within a venue
+        for market in &adding {
+            let exposure = total(&|e| e.venue == market.venue && e.market_id == market.market_id);
+            if exposure > risk.max_market_exposure {
+                rejections.push(RiskRejection::MarketExposure {
+                    leg: market.venue,
+                    market_id: market.market_id.clone(),
+                    exposure,
+                    limit: risk.max_market_exposure,
+                });
+            }
+        }
+        for (leg, _) in legs {
+            let exposure = total(&|e| e.venue == leg);
+            if exposure > risk.max_venue_exposure {
+                rejections.push(RiskRejection::VenueExposure { leg, exposure, limit: risk.max_venue_exposure });
+            }
+        }
+
+        if opportunity.confidence < risk.min_confidence {
+            rejections.push(RiskRejection::LowConfidence {
+                confidence: opportunity.confidence,
+                minimum: risk.min_confidence,
+            });
+        }
+
+        let now = Utc::now();
+        for (leg, odds) in legs {
+            let age = (now - odds.timestamp).num_seconds();
+            if age > risk.max_quote_age_secs as i64 {
+                rejections.push(RiskRejection::StaleQuote { leg, age_secs: age, limit_secs: risk.max_quote_age_secs });
+            }
+        }
+
+        if let Some(rejection) = self.resolution_mismatch(&opportunity.polymarket_odds, &opportunity.btc_market_odds) {
+            rejections.push(rejection);
+        }
+
+        if rejections.is_empty() {
+            in_flight.insert(config.idempotency_key.clone(), adding.to_vec());
+            return RiskDecision::Approved;
+        }
+
+        let mut counts = self.rejections.lock().expect("risk counters lock poisoned");
+        for rejection in &rejections {
+            *counts.entry(rejection.code()).or_default() += 1;
+        }
+        tracing::warn!(
+            "Risk rejected {}: {}",
+            config.idempotency_key,
+            rejections.iter().map(|r| format!("{} ({})", r.code(), r)).collect::<Vec<_>>().join("; ")
+        );
+        RiskDecision::Rejected(rejections)
+    }
+
+    /// The trade settled into positions, which now carry its exposure, or
+    /// was never sent: stop holding what `check` reserved
+    pub fn release(&self, idempotency_key: &str) {
+        self.in_flight.lock().expect("risk in-flight lock poisoned").remove(idempotency_key);
+    }
+
+    /// Markets that resolve far apart are not the same event. Unknown
+    /// resolution times are not rejected.
+    fn resolution_mismatch(&self, polymarket: &MarketOdds, btc: &MarketOdds) -> Option<RiskRejection> {
+        let (poly_at, btc_at) = (polymarket.resolves_at?, btc.resolves_at?);
+        let gap = (poly_at - btc_at).num_hours().unsigned_abs();
+        (gap > self.config.max_resolution_gap_hours).then_some(RiskRejection::ResolutionMismatch {
+            polymarket: poly_at,
+            btc: btc_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::LedgerKind;
+    use crate::models::MarketSource;
+    use crate::positions::PositionLeg;
+    use crate::trader::TimeInForce;
+    use tokio::time::Duration;
+
+    fn odds(source: MarketSource, market_id: &str) -> MarketOdds {
+        MarketOdds {
+            market_id: market_id.to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(50, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        }
+    }
+
+    fn opportunity(market_id: &str) -> ArbitrageOpportunity {
+        ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket, market_id),
+            odds(MarketSource::BitcoinPredictionMarket, "btc-1"),
+            Decimal::new(5, 2),
+            0.9,
+        )
+    }
+
+    fn trade_config(size: i64) -> TradeConfig {
+        TradeConfig {
+            size: Decimal::from(size),
+            max_size: Decimal::from(10_000),
+            limit_tolerance: Decimal::ZERO,
+            time_in_force: TimeInForce::ImmediateOrCancel,
+            polymarket_timeout: Duration::from_secs(1),
+            btc_timeout: Duration::from_secs(1),
+            dry_run: false,
+            idempotency_key: "risk-test".to_string(),
+        }
+    }
+
+    /// 400 shares at 0.50 on each venue
+    fn open_position(positions: &mut PositionManager, market_id: &str) {
+        let opportunity = opportunity(market_id);
+        let legs = [(Leg::Polymarket, &opportunity.polymarket_odds), (Leg::Btc, &opportunity.btc_market_odds)]
+            .into_iter()
+            .map(|(leg, odds)| PositionLeg::long(leg, odds, Decimal::from(400), Decimal::new(50, 2)))
+            .collect();
+        positions.open_position(&opportunity, legs).unwrap();
+    }
+
+    fn engine() -> RiskEngine {
+        RiskEngine::new(RiskConfig {
+            max_daily_loss: Decimal::from(100),
+            max_open_positions: 2,
+            max_market_exposure: Decimal::from(500),
+            max_venue_exposure: Decimal::from(520),
+            ..RiskConfig::default()
+        })
+    }
+
+    fn rejection_codes(decision: RiskDecision) -> Vec<&'static str> {
+        match decision {
+            RiskDecision::Approved => vec![],
+            RiskDecision::Rejected(rejections) => rejections.iter().map(|r| r.code()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_clean_trade_is_approved() {
+        let decision = engine().check(&opportunity("m1"), &trade_config(100), &PositionManager::new());
+        assert!(decision.is_approved());
+    }
+
+    #[test]
+    fn test_position_count_and_exposure_limits() {
+        let engine = engine();
+        let mut positions = PositionManager::new();
+        // Two positions holding $200 on each venue
+        for _ in 0..2 {
+            open_position(&mut positions, "m1");
+        }
+
+        let decision = engine.check(&opportunity("m1"), &trade_config(300), &positions);
+        // $400 held plus $150 more on both m1 and btc-1
+        assert_eq!(
+            rejection_codes(decision),
+            vec!["max_open_positions", "market_exposure", "market_exposure", "venue_exposure", "venue_exposure"]
+        );
+        assert_eq!(engine.rejection_counts()["venue_exposure"], 2);
+    }
+
+    #[test]
+    fn test_in_flight_trades_hold_exposure_until_released() {
+        let engine = engine();
+        let positions = PositionManager::new();
+        let mut first = trade_config(600);
+        first.idempotency_key = "first".to_string();
+
+        // $300 per leg in flight leaves no room for another $300 on btc-1
+        assert!(engine.check(&opportunity("m1"), &first, &positions).is_approved());
+        let decision = engine.check(&opportunity("m2"), &trade_config(600), &positions);
+        assert_eq!(rejection_codes(decision), vec!["market_exposure", "venue_exposure", "venue_exposure"]);
+
+        engine.release("first");
+        assert!(engine.check(&opportunity("m2"), &trade_config(600), &positions).is_approved());
+    }
+
+    #[test]
+    fn test_market_exposure_is_per_venue() {
+        let engine = engine();
+        let mut positions = PositionManager::new();
+        // $200 held on btc-1 at the BTC venue
+        open_position(&mut positions, "m1");
+
+        // A Polymarket market sharing the id is a different market: $200 there
+        // and $400 on the BTC venue's btc-1 are both within the $500 limit
+        let decision = engine.check(&opportunity("btc-1"), &trade_config(400), &positions);
+        assert!(decision.is_approved());
+    }
+
+    #[test]
+    fn test_daily_loss_counts_unrealized() {
+        let engine = engine();
+        let mut positions = PositionManager::new();
+        open_position(&mut positions, "m1");
+
+        engine.update_mark("m1", "Yes", Decimal::new(20, 2));
+        let decision = engine.check(&opportunity("m2"), &trade_config(10), &positions);
+        assert_eq!(rejection_codes(decision), vec!["daily_loss"]);
+
+        let lenient = RiskEngine::new(RiskConfig { stop_on_max_loss: false, ..engine.config.clone() });
+        lenient.update_mark("m1", "Yes", Decimal::new(20, 2));
+        assert!(lenient.check(&opportunity("m2"), &trade_config(10), &positions).is_approved());
+    }
+
+    #[test]
+    fn test_ledger_drives_loss_budget() {
+        let path = std::env::temp_dir().join(format!("risk-ledger-{}.jsonl", uuid::Uuid::new_v4()));
+        let ledger = PnlLedger::open(&path, Default::default()).unwrap();
+        let engine = RiskEngine::new(engine().config.clone()).with_ledger(ledger);
+        let positions = PositionManager::new();
+
+        let ledger = engine.ledger().unwrap();
+        ledger.record(LedgerKind::Realized, Decimal::from(-90), Some("p1")).unwrap();
+        assert_eq!(engine.remaining_loss_budget(&positions), Decimal::from(10));
+        assert!(engine.check(&opportunity("m2"), &trade_config(10), &positions).is_approved());
+
+        ledger.record(LedgerKind::Fee, Decimal::from(-15), Some("p2")).unwrap();
+        assert_eq!(engine.remaining_loss_budget(&positions), Decimal::ZERO);
+        let decision = engine.check(&opportunity("m2"), &trade_config(10), &positions);
+        assert_eq!(rejection_codes(decision), vec!["daily_loss"]);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_quote_quality_checks() {
+        let mut opportunity = opportunity("m1");
+        opportunity.confidence = 0.5;
+        opportunity.btc_market_odds.timestamp = Utc::now() - chrono::Duration::minutes(5);
+        opportunity.polymarket_odds.resolves_at = Some(Utc::now());
+        opportunity.btc_market_odds.resolves_at = Some(Utc::now() + chrono::Duration::days(3));
+
+        let decision = engine().check(&opportunity, &trade_config(10), &PositionManager::new());
+        assert_eq!(rejection_codes(decision), vec!["low_confidence", "stale_quote", "resolution_mismatch"]);
+    }
+}
@@ -0,0 +1,378 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, Notify, Semaphore};
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+use crate::config::TradingConfig;
+use crate::models::ArbitrageOpportunity;
+use crate::trader::{ArbitrageExecutor, TradeConfig, TradeResult};
+
+/// Expected USD edge of a trade: implied profit on the size, weighted by confidence
+pub fn ranked_edge(opportunity: &ArbitrageOpportunity, config: &TradeConfig) -> Decimal {
+    let confidence = Decimal::from_f64(opportunity.confidence).unwrap_or_default();
+    opportunity.implied_profit * config.size * confidence
+}
+
+/// Markets an opportunity trades on, qualified by venue
+fn market_keys(opportunity: &ArbitrageOpportunity) -> [String; 2] {
+    [
+        format!("polymarket:{}", opportunity.polymarket_odds.market_id),
+        format!("btc:{}", opportunity.btc_market_odds.market_id),
+    ]
+}
+
+struct QueuedTrade {
+    edge: Decimal,
+    /// Submission order, to break ties first-come first-served
+    seq: u64,
+    opportunity: ArbitrageOpportunity,
+    config: TradeConfig,
+    respond: oneshot::Sender<Result<TradeResult>>,
+}
+
+impl PartialEq for QueuedTrade {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedTrade {}
+
+impl PartialOrd for QueuedTrade {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedTrade {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.edge.cmp(&other.edge).then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+#[derive(Default)]
+struct Queue {
+    trades: BinaryHeap<QueuedTrade>,
+    next_seq: u64,
+}
+
+/// Runs trades concurrently, up to `max_concurrent_trades` at a time. Waiting
+/// trades start best ranked edge first, never two on the same market at
+/// once, and are rejected once their quotes are older than `max_queue_age`.
+pub struct TradeScheduler {
+    executor: Arc<ArbitrageExecutor>,
+    slots: Arc<Semaphore>,
+    queue: Mutex<Queue>,
+    busy_markets: Mutex<HashSet<String>>,
+    wake: Notify,
+    max_queue_age: Duration,
+}
+
+impl TradeScheduler {
+    pub fn new(executor: ArbitrageExecutor, max_concurrent: usize, max_queue_age: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            executor: Arc::new(executor),
+            slots: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            queue: Mutex::new(Queue::default()),
+            busy_markets: Mutex::new(HashSet::new()),
+            wake: Notify::new(),
+            max_queue_age,
+        })
+    }
+
+    pub fn from_config(executor: ArbitrageExecutor, config: &TradingConfig) -> Arc<Self> {
+        Self::new(
+            executor,
+            config.max_concurrent_trades as usize,
+            Duration::from_secs(config.max_queue_age_secs),
+        )
+    }
+
+    pub fn executor(&self) -> &ArbitrageExecutor {
+        &self.executor
+    }
+
+    /// Trades waiting for a slot or a busy market
+    pub fn queued(&self) -> usize {
+        self.queue.lock().expect("trade queue lock poisoned").trades.len()
+    }
+
+    /// Queue a trade and wait for its result. Needs `start` to have been called.
+    pub async fn submit(&self, opportunity: ArbitrageOpportunity, config: TradeConfig) -> Result<TradeResult> {
+        let (respond, result) = oneshot::channel();
+        {
+            let mut queue = self.queue.lock().expect("trade queue lock poisoned");
+            let seq = queue.next_seq;
+            queue.next_seq += 1;
+            queue.trades.push(QueuedTrade {
+                edge: ranked_edge(&opportunity, &config),
+                seq,
+                opportunity,
+                config,
+                respond,
+            });
+        }
+        self.wake.notify_one();
+
+        result
+            .await
+            .map_err(|_| anyhow::anyhow!("Trade scheduler stopped before running the trade"))?
+    }
+
+    /// Dispatch queued trades in a background task
+    pub fn start(self: &Arc<Self>) -> JoinHandle<()> {
+        let scheduler = self.clone();
+        tokio::spawn(async move { scheduler.dispatch().await })
+    }
+
+    async fn dispatch(self: Arc<Self>) {
+        loop {
+            let Ok(slot) = self.slots.clone().acquire_owned().await else {
+                return;
+            };
+            let trade = loop {
+                if let Some(trade) = self.next_ready() {
+                    break trade;
+                }
+                self.wake.notified().await;
+            };
+
+            let scheduler = self.clone();
+            tokio::spawn(async move {
+                let markets = market_keys(&trade.opportunity);
+                let result = scheduler.executor.execute(&trade.opportunity, &trade.config).await;
+
+                {
+                    let mut busy = scheduler.busy_markets.lock().expect("busy markets lock poisoned");
+                    for market in &markets {
+                        busy.remove(market);
+                    }
+                }
+                drop(slot);
+                scheduler.wake.notify_one();
+                let _ = trade.respond.send(result);
+            });
+        }
+    }
+
+    /// Best-edge trade whose markets are free, claiming them. Stale trades
+    /// found on the way are rejected.
+    fn next_ready(&self) -> Option<QueuedTrade> {
+        let mut queue = self.queue.lock().expect("trade queue lock poisoned");
+        let mut busy = self.busy_markets.lock().expect("busy markets lock poisoned");
+        let max_age = chrono::Duration::from_std(self.max_queue_age).unwrap_or(chrono::Duration::MAX);
+        let now = Utc::now();
+
+        let mut blocked = Vec::new();
+        let mut ready = None;
+        while let Some(trade) = queue.trades.pop() {
+            let age = now - trade.opportunity.detected_at;
+            if age > max_age {
+                tracing::warn!(
+                    "Rejecting stale trade {}: quotes are {}s old",
+                    trade.config.idempotency_key,
+                    age.num_seconds()
+                );
+                let _ = trade.respond.send(Err(anyhow::anyhow!(
+                    "Trade {} went stale in the queue ({}s old)",
+                    trade.config.idempotency_key,
+                    age.num_seconds()
+                )));
+                continue;
+            }
+
+            let markets = market_keys(&trade.opportunity);
+            if markets.iter().any(|m| busy.contains(m)) {
+                blocked.push(trade);
+                continue;
+            }
+            busy.extend(markets);
+            ready = Some(trade);
+            break;
+        }
+
+        queue.trades.extend(blocked);
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MarketOdds, MarketSource};
+    use crate::trader::{TimeInForce, TradeExecutor, TradeStatus};
+    use async_trait::async_trait;
+    use rust_decimal::Decimal;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+    /// Fills after a delay, tracking how many orders are in flight at once
+    struct SlowExecutor {
+        delay: Duration,
+        in_flight: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+        order: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl TradeExecutor for SlowExecutor {
+        async fn execute_arbitrage(
+            &self,
+            opportunity: &ArbitrageOpportunity,
+            _config: &TradeConfig,
+        ) -> Result<TradeResult> {
+            let now = self.in_flight.fetch_add(1, AtomicOrdering::SeqCst) + 1;
+            self.peak.fetch_max(now, AtomicOrdering::SeqCst);
+            self.order.lock().unwrap().push(opportunity.btc_market_odds.market_id.clone());
+            tokio::time::sleep(self.delay).await;
+            self.in_flight.fetch_sub(1, AtomicOrdering::SeqCst);
+
+            Ok(TradeResult {
+                polymarket_tx: None,
+                btc_tx: Some("tx".to_string()),
+                status: TradeStatus::Success,
+                executed_at: Utc::now(),
+                error: None,
+                position_id: None,
+                token_id: None,
+                fill_price: None,
+                filled_size: None,
+                fees: None,
+            })
+        }
+
+        async fn approve_token(&self, _token: &str, _amount: Decimal) -> Result<String> {
+            Ok("N/A".to_string())
+        }
+
+        async fn get_balance(&self) -> Result<Decimal> {
+            Ok(Decimal::ZERO)
+        }
+    }
+
+    struct Probe {
+        peak: Arc<AtomicUsize>,
+        order: Arc<Mutex<Vec<String>>>,
+    }
+
+    fn slow_executor(delay: Duration, probe: &Probe) -> SlowExecutor {
+        SlowExecutor {
+            delay,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            peak: probe.peak.clone(),
+            order: probe.order.clone(),
+        }
+    }
+
+    /// Only the BTC leg is observed; the Polymarket leg fills instantly
+    fn scheduler(max_concurrent: usize, delay: Duration) -> (Arc<TradeScheduler>, Probe) {
+        let probe = Probe {
+            peak: Arc::new(AtomicUsize::new(0)),
+            order: Arc::new(Mutex::new(Vec::new())),
+        };
+        let unobserved = Probe {
+            peak: Arc::new(AtomicUsize::new(0)),
+            order: Arc::new(Mutex::new(Vec::new())),
+        };
+        let executor = ArbitrageExecutor::with_executors(
+            Box::new(slow_executor(Duration::ZERO, &unobserved)),
+            Box::new(slow_executor(delay, &probe)),
+        );
+        (TradeScheduler::new(executor, max_concurrent, Duration::from_secs(30)), probe)
+    }
+
+    fn submit(
+        scheduler: &Arc<TradeScheduler>,
+        opportunity: ArbitrageOpportunity,
+        key: &str,
+    ) -> JoinHandle<Result<TradeResult>> {
+        let scheduler = scheduler.clone();
+        let config = trade_config(key);
+        tokio::spawn(async move { scheduler.submit(opportunity, config).await })
+    }
+
+    fn opportunity(market: &str, profit: i64) -> ArbitrageOpportunity {
+        let odds = |source, market_id: &str| MarketOdds {
+            market_id: market_id.to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(45, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket, &format!("pm-{}", market)),
+            odds(MarketSource::BitcoinPredictionMarket, market),
+            Decimal::new(profit, 2),
+            0.9,
+        )
+    }
+
+    fn trade_config(key: &str) -> TradeConfig {
+        TradeConfig {
+            size: Decimal::from(10),
+            max_size: Decimal::from(100),
+            limit_tolerance: Decimal::ZERO,
+            time_in_force: TimeInForce::ImmediateOrCancel,
+            polymarket_timeout: Duration::from_secs(1),
+            btc_timeout: Duration::from_secs(1),
+            dry_run: false,
+            idempotency_key: key.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_is_capped() {
+        let (scheduler, probe) = scheduler(2, Duration::from_millis(50));
+        scheduler.start();
+
+        let trades: Vec<_> = (0..5)
+            .map(|i| submit(&scheduler, opportunity(&format!("m{}", i), 5), &i.to_string()))
+            .collect();
+        for trade in trades {
+            trade.await.unwrap().unwrap();
+        }
+        assert_eq!(probe.peak.load(AtomicOrdering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_same_market_never_runs_twice_at_once() {
+        let (scheduler, probe) = scheduler(4, Duration::from_millis(50));
+        scheduler.start();
+
+        let trades: Vec<_> = (0..3)
+            .map(|i| submit(&scheduler, opportunity("same", 5), &i.to_string()))
+            .collect();
+        for trade in trades {
+            trade.await.unwrap().unwrap();
+        }
+        assert_eq!(probe.peak.load(AtomicOrdering::SeqCst), 1);
+        assert_eq!(probe.order.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_best_edge_runs_first_and_stale_trades_are_rejected() {
+        let (scheduler, probe) = scheduler(1, Duration::from_millis(20));
+
+        // Queue everything before dispatching starts so order is decided by edge
+        let mut stale = opportunity("stale", 50);
+        stale.detected_at = Utc::now() - chrono::Duration::minutes(5);
+        let low = submit(&scheduler, opportunity("low", 2), "low");
+        let stale = submit(&scheduler, stale, "stale");
+        let high = submit(&scheduler, opportunity("high", 9), "high");
+        while scheduler.queued() < 3 {
+            tokio::task::yield_now().await;
+        }
+        scheduler.start();
+
+        assert!(low.await.unwrap().is_ok());
+        assert!(stale.await.unwrap().unwrap_err().to_string().contains("stale"));
+        assert!(high.await.unwrap().is_ok());
+        assert_eq!(*probe.order.lock().unwrap(), vec!["high".to_string(), "low".to_string()]);
+    }
+}
@@ -1,13 +1,32 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use rust_decimal::Decimal;
-use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use tokio::time::Duration;
 
-use crate::models::ArbitrageOpportunity;
-use crate::config::{Config};
-use crate::positions::{PositionManager, PositionStatus};
+use crate::api::ordinals::OrdinalsMarketplace;
+use crate::api::polymarket::PolymarketBookSource;
+use crate::chains::evm::{abi, AbiToken, EvmChain, EvmWallet};
+use crate::chains::lightning::{LightningMarket, LndClient};
+use crate::chains::liquid::{LiquidMarket, OutcomeTokenHolding};
+use crate::chains::ordinals::OrdinalsTrader;
+use crate::chains::rsk::RskMarket;
+use crate::chains::stacks::{StacksMarket, StacksTxStatus};
+use crate::models::{ArbitrageOpportunity, MarketOdds};
+use crate::config::{Config, TradingConfig, TreasuryConfig};
+use crate::journal::{JournalEvent, TradeJournal, UnsettledExecution};
+use crate::position_store::SqlitePositionStore;
+use crate::ledger::{LedgerKind, PnlLedger};
+use crate::positions::{Position, PositionLeg, PositionManager, PositionStatus};
+use crate::ratelimit::RateLimitedExecutor;
+use crate::resolver::ResolutionStatus;
+use crate::risk::{RiskDecision, RiskEngine};
+use crate::halt::{CircuitBreakers, KillSwitch};
+use crate::treasury::{Asset, Treasury};
+use crate::alerts::{sink_from_config, Alert};
+use crate::approval::{ApprovalDecision, ApprovalQueue};
+use crate::unwind::{OpenLeg, UnwindAction, UnwindEngine, UnwindPolicy, UnwindVenues};
 
 /// Result of a trade execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +37,18 @@ pub struct TradeResult {
     pub executed_at: DateTime<Utc>,
     pub error: Option<String>,
     pub position_id: Option<String>,
+    /// Token received on the BTC leg (Liquid asset id, inscription id)
+    #[serde(default)]
+    pub token_id: Option<String>,
+    /// Average fill price, when the venue reports one
+    #[serde(default)]
+    pub fill_price: Option<Decimal>,
+    /// Shares filled, when the venue reports them
+    #[serde(default)]
+    pub filled_size: Option<Decimal>,
+    /// Fees paid in USD, when the venue reports them
+    #[serde(default)]
+    pub fees: Option<Decimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -28,6 +59,147 @@ pub enum TradeStatus {
     Pending,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TimeInForce {
+    /// Rest until filled or cancelled
+    GoodTilCancelled,
+    /// Fill what is available now, cancel the rest
+    ImmediateOrCancel,
+    /// Fill the whole size now or nothing
+    FillOrKill,
+}
+
+impl TimeInForce {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "gtc" => Ok(Self::GoodTilCancelled),
+            "ioc" => Ok(Self::ImmediateOrCancel),
+            "fok" => Ok(Self::FillOrKill),
+            other => Err(anyhow::anyhow!("Unknown time in force: {}", other)),
+        }
+    }
+}
+
+/// Per-opportunity overrides of the configured trade parameters
+#[derive(Debug, Clone, Default)]
+pub struct TradeOverrides {
+    pub size: Option<Decimal>,
+    pub limit_tolerance: Option<Decimal>,
+    pub time_in_force: Option<TimeInForce>,
+    pub dry_run: Option<bool>,
+    pub idempotency_key: Option<String>,
+}
+
+/// Parameters for executing one opportunity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeConfig {
+    /// Shares to buy on each leg
+    pub size: Decimal,
+    /// Largest position the configuration allows, bounding `size`
+    pub max_size: Decimal,
+    /// How far above the quoted odds a limit may be set, as a fraction
+    pub limit_tolerance: Decimal,
+    pub time_in_force: TimeInForce,
+    pub polymarket_timeout: Duration,
+    pub btc_timeout: Duration,
+    /// Log the plan without sending any order
+    pub dry_run: bool,
+    /// Stable key for this opportunity, so a retry never trades it twice
+    pub idempotency_key: String,
+}
+
+impl TradeConfig {
+    /// Build from the configured defaults, then apply per-opportunity overrides.
+    /// The result is validated.
+    pub fn from_config(
+        config: &Config,
+        opportunity: &ArbitrageOpportunity,
+        overrides: &TradeOverrides,
+    ) -> Result<Self> {
+        let trading = &config.trading;
+        let trade = Self {
+            size: overrides.size.unwrap_or(config.general.max_position_size),
+            max_size: config.general.max_position_size,
+            limit_tolerance: overrides.limit_tolerance.unwrap_or(config.general.max_slippage),
+            time_in_force: match overrides.time_in_force {
+                Some(tif) => tif,
+                None => TimeInForce::from_name(&trading.time_in_force)?,
+            },
+            polymarket_timeout: Duration::from_secs(trading.polymarket_leg_timeout_secs),
+            btc_timeout: Duration::from_secs(trading.btc_leg_timeout_secs),
+            dry_run: overrides.dry_run.unwrap_or(trading.dry_run),
+            idempotency_key: overrides
+                .idempotency_key
+                .clone()
+                .unwrap_or_else(|| idempotency_key(opportunity, trading.idempotency_window_secs)),
+        };
+
+        trade.validate()?;
+        Ok(trade)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.size <= Decimal::ZERO {
+            return Err(anyhow::anyhow!("Trade size must be positive, got {}", self.size));
+        }
+        if self.size > self.max_size {
+            return Err(anyhow::anyhow!(
+                "Trade size {} exceeds max position size {}",
+                self.size,
+                self.max_size
+            ));
+        }
+        if self.limit_tolerance < Decimal::ZERO || self.limit_tolerance >= Decimal::ONE {
+            return Err(anyhow::anyhow!(
+                "Limit tolerance must be in [0, 1), got {}",
+                self.limit_tolerance
+            ));
+        }
+        if self.polymarket_timeout.is_zero() || self.btc_timeout.is_zero() {
+            return Err(anyhow::anyhow!("Leg timeouts must be non-zero"));
+        }
+        if self.idempotency_key.trim().is_empty() {
+            return Err(anyhow::anyhow!("Idempotency key must not be empty"));
+        }
+        Ok(())
+    }
+
+    /// Worst price accepted for a leg quoted at `odds`
+    pub fn limit_price(&self, odds: Decimal) -> Decimal {
+        (odds * (Decimal::ONE + self.limit_tolerance)).min(Decimal::ONE)
+    }
+
+    pub fn leg_timeout(&self, leg: Leg) -> Duration {
+        match leg {
+            Leg::Polymarket => self.polymarket_timeout,
+            Leg::Btc => self.btc_timeout,
+        }
+    }
+
+    /// Client order id for a leg, so a venue can be asked about the order
+    /// even if we never saw its response
+    pub fn client_order_id(&self, leg: Leg) -> String {
+        format!("{}-{}", self.idempotency_key, leg)
+    }
+}
+
+/// Deterministic key for an opportunity: re-detections of the same pair of
+/// outcomes within one `window_secs` window map to the same key
+fn idempotency_key(opportunity: &ArbitrageOpportunity, window_secs: u64) -> String {
+    use sha2::{Digest, Sha256};
+
+    let window = window_secs.max(1) as i64;
+    let fingerprint = format!(
+        "{}|{}|{}|{}|{}",
+        opportunity.polymarket_odds.market_id,
+        opportunity.polymarket_odds.outcome,
+        opportunity.btc_market_odds.market_id,
+        opportunity.btc_market_odds.outcome,
+        opportunity.detected_at.timestamp().div_euclid(window)
+    );
+    hex::encode(&Sha256::digest(fingerprint.as_bytes())[..16])
+}
+
 /// Trait for trade execution across different protocols
 #[async_trait]
 pub trait TradeExecutor: Send + Sync {
@@ -44,8 +216,208 @@ pub trait TradeExecutor: Send + Sync {
     ) -> Result<String>;
 
     async fn get_balance(&self) -> Result<Decimal>;
+
+    /// Cancel a resting order. Returns `false` if the venue has nothing to
+    /// cancel (e.g. an on-chain transaction that is already broadcast).
+    async fn cancel_order(&self, _tx_id: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Sell a filled position back at no less than `min_price`, to unwind
+    /// a one-legged arbitrage
+    async fn sell_back(
+        &self,
+        _odds: &MarketOdds,
+        _size: Decimal,
+        _min_price: Decimal,
+    ) -> Result<TradeResult> {
+        Err(anyhow::anyhow!("Sell-back not supported by this venue"))
+    }
+
+    /// Outcome tokens held in the executor's wallet
+    async fn outcome_holdings(&self) -> Result<Vec<OutcomeTokenHolding>> {
+        Ok(vec![])
+    }
+
+    /// Holdings the venue reports, for reconciliation against the `tracked`
+    /// legs held there. `None` if the venue cannot report holdings.
+    async fn reported_holdings(&self, _tracked: &[PositionLeg]) -> Result<Option<Vec<OutcomeTokenHolding>>> {
+        Ok(None)
+    }
+
+    /// Look up an order by the client order id it was submitted with
+    async fn find_order(&self, _client_order_id: &str) -> Result<OrderLookup> {
+        Ok(OrderLookup::Unsupported)
+    }
+
+    /// Cancel every resting order at the venue. Returns how many were cancelled.
+    async fn cancel_all_orders(&self) -> Result<usize> {
+        Err(anyhow::anyhow!("Cancelling all orders is not supported by this venue"))
+    }
+
+    /// Whether a market has resolved at the venue, and to which outcome
+    async fn market_resolution(&self, _market_id: &str) -> Result<ResolutionStatus> {
+        Err(anyhow::anyhow!("Resolution tracking not supported by this venue"))
+    }
+
+    /// Collect the payout of a winning leg once its market has resolved.
+    /// Returns the redemption tx or payment hash, `None` if the venue pays
+    /// out without being asked.
+    async fn redeem(&self, _leg: &PositionLeg) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Venue state of an order looked up by client order id
+#[derive(Debug, Clone)]
+pub enum OrderLookup {
+    Found(TradeResult),
+    /// The venue has no order with this id: it was never submitted
+    NotFound,
+    /// The venue cannot look orders up by client id
+    Unsupported,
+}
+
+/// Order in which the two legs of an arbitrage are sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionStrategy {
+    /// Send the thin leg first and hedge once it has been accepted
+    ThinSideFirst,
+    /// Send both legs at once; cancel the survivor's order if the other fails
+    Concurrent,
+    /// Send the thin leg first and hedge only once it has filled
+    HedgeAfterFill,
+}
+
+impl ExecutionStrategy {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "thin_side_first" => Ok(Self::ThinSideFirst),
+            "concurrent" => Ok(Self::Concurrent),
+            "hedge_after_fill" => Ok(Self::HedgeAfterFill),
+            other => Err(anyhow::anyhow!("Unknown execution strategy: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Leg {
+    Polymarket,
+    Btc,
+}
+
+impl Leg {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "polymarket" => Ok(Self::Polymarket),
+            "btc" => Ok(Self::Btc),
+            other => Err(anyhow::anyhow!("Unknown leg: {}", other)),
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Self::Polymarket => Self::Btc,
+            Self::Btc => Self::Polymarket,
+        }
+    }
+}
+
+impl std::fmt::Display for Leg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Polymarket => write!(f, "polymarket"),
+            Self::Btc => write!(f, "btc"),
+        }
+    }
+}
+
+/// How an arbitrage is executed: strategy and leg order
+#[derive(Debug, Clone)]
+pub struct ExecutionPlan {
+    pub strategy: ExecutionStrategy,
+    pub thin_leg: Leg,
+}
+
+impl ExecutionPlan {
+    pub fn from_config(config: &TradingConfig) -> Result<Self> {
+        Ok(Self {
+            strategy: ExecutionStrategy::from_name(&config.execution_strategy)?,
+            thin_leg: Leg::from_name(&config.thin_leg)?,
+        })
+    }
+}
+
+impl Default for ExecutionPlan {
+    fn default() -> Self {
+        Self::from_config(&TradingConfig::default())
+            .expect("default trading config has a valid execution plan")
+    }
+}
+
+/// What happened on one leg of an arbitrage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LegOutcome {
+    /// The executor returned a result
+    Completed(TradeResult),
+    /// The executor returned an error
+    Errored(String),
+    /// No result within the leg timeout; an order may still be live
+    TimedOut,
+    /// Not sent because the first leg did not reach the required state
+    Skipped,
+    /// The leg's order was cancelled after the other leg failed
+    Cancelled(TradeResult),
+    /// The venue cannot report what became of the order; an operator must check it
+    Unknown(String),
+}
+
+impl LegOutcome {
+    pub fn status(&self) -> TradeStatus {
+        match self {
+            Self::Completed(result) => result.status.clone(),
+            Self::TimedOut | Self::Unknown(_) => TradeStatus::Pending,
+            Self::Errored(_) | Self::Skipped | Self::Cancelled(_) => TradeStatus::Failed,
+        }
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.status() == TradeStatus::Success
+    }
+
+    /// Accepted by the venue: filled or still working
+    pub fn is_accepted(&self) -> bool {
+        self.status() != TradeStatus::Failed
+    }
+
+    pub fn result(&self) -> Option<&TradeResult> {
+        match self {
+            Self::Completed(result) | Self::Cancelled(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<String> {
+        match self {
+            Self::Completed(result) => result.error.clone(),
+            Self::Errored(e) => Some(e.clone()),
+            Self::TimedOut => Some("timed out".to_string()),
+            Self::Skipped => Some("not sent".to_string()),
+            Self::Cancelled(_) => Some("cancelled after the other leg failed".to_string()),
+            Self::Unknown(reason) => Some(format!("order state unknown: {}", reason)),
+        }
+    }
 }
 
+/// USDC.e collateral used by Polymarket on Polygon
+const POLYGON_USDC: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+const USDC_DECIMALS: u32 = 6;
+
+/// Polymarket CTF Exchange, the spender for USDC approvals
+const POLYMARKET_CTF_EXCHANGE: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+/// Gnosis Conditional Tokens contract holding Polymarket outcome tokens
+const CONDITIONAL_TOKENS: &str = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+
 /// Polymarket trade executor
 pub struct PolymarketTradeExecutor {
     config: crate::config::PolymarketConfig,
@@ -61,6 +433,13 @@ impl PolymarketTradeExecutor {
         self.config.private_key.is_some()
             && !self.config.private_key.as_ref().unwrap().is_empty()
     }
+
+    fn wallet(&self) -> Result<EvmWallet> {
+        let private_key = self.config.private_key.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Polymarket wallet not configured"))?;
+        let chain = EvmChain::polygon_from_network(&self.config.network)?;
+        EvmWallet::new(chain, &self.config.rpc_url, private_key)
+    }
 }
 
 #[async_trait]
@@ -68,10 +447,11 @@ impl TradeExecutor for PolymarketTradeExecutor {
     async fn execute_arbitrage(
         &self,
         opportunity: &ArbitrageOpportunity,
-        _config: &TradeConfig,
+        config: &TradeConfig,
     ) -> Result<TradeResult> {
         tracing::info!(
-            "Executing Polymarket trade: {} at {}",
+            "Executing Polymarket trade {}: {} at {}",
+            config.client_order_id(Leg::Polymarket),
             opportunity.polymarket_odds.outcome,
             opportunity.polymarket_odds.odds
         );
@@ -84,6 +464,10 @@ impl TradeExecutor for PolymarketTradeExecutor {
                 executed_at: Utc::now(),
                 error: Some("Polymarket wallet not configured".to_string()),
                 position_id: None,
+                token_id: None,
+                fill_price: None,
+                filled_size: None,
+                fees: None,
             });
         }
 
@@ -101,6 +485,10 @@ impl TradeExecutor for PolymarketTradeExecutor {
             executed_at: Utc::now(),
             error: Some("Not implemented yet".to_string()),
             position_id: None,
+            token_id: None,
+            fill_price: None,
+            filled_size: None,
+            fees: None,
         })
     }
 
@@ -115,9 +503,22 @@ impl TradeExecutor for PolymarketTradeExecutor {
             return Err(anyhow::anyhow!("Polymarket wallet not configured"));
         }
 
-        // TODO: Implement actual approval transaction
-        // Contract call to approve USDC to Polymarket CLOB
-        Ok("0x0000000000000000000000000000000000000000".to_string())
+        let wallet = self.wallet()?;
+        let token = wallet.chain().parse_address(token)?;
+        let spender = wallet.chain().parse_address(POLYMARKET_CTF_EXCHANGE)?;
+        let decimals = if token == wallet.chain().parse_address(POLYGON_USDC)? {
+            USDC_DECIMALS
+        } else {
+            18
+        };
+
+        let tx_hash = wallet.erc20_approve(&token, &spender, amount, decimals).await?;
+        match wallet.wait_for_receipt(&tx_hash).await? {
+            Some(receipt) if !receipt.success => {
+                Err(anyhow::anyhow!("Approval {} reverted", tx_hash))
+            }
+            _ => Ok(tx_hash),
+        }
     }
 
     async fn get_balance(&self) -> Result<Decimal> {
@@ -125,35 +526,150 @@ impl TradeExecutor for PolymarketTradeExecutor {
             return Ok(Decimal::ZERO);
         }
 
-        // TODO: Query actual balance from Polygon
-        Ok(Decimal::ZERO)
+        let wallet = self.wallet()?;
+        let usdc = wallet.chain().parse_address(POLYGON_USDC)?;
+        wallet.erc20_balance(&usdc, USDC_DECIMALS).await
+    }
+
+    /// CTF balances of the tracked legs' outcome tokens. ERC-1155 balances
+    /// can't be enumerated, so tokens no leg tracks are not reported.
+    async fn reported_holdings(&self, tracked: &[PositionLeg]) -> Result<Option<Vec<OutcomeTokenHolding>>> {
+        if !self.is_configured() {
+            return Ok(None);
+        }
+
+        let wallet = self.wallet()?;
+        let ctf = wallet.chain().parse_address(CONDITIONAL_TOKENS)?;
+        let source = PolymarketBookSource::new();
+        let mut holdings: Vec<OutcomeTokenHolding> = Vec::new();
+        for leg in tracked {
+            let token_id = match &leg.token_id {
+                Some(token_id) => token_id.clone(),
+                None => source.token_id(&leg.market_id, &leg.outcome).await?,
+            };
+            if holdings.iter().any(|h| h.asset_id == token_id) {
+                continue;
+            }
+            let id = abi::uint256_from_decimal(&token_id)
+                .ok_or_else(|| anyhow::anyhow!("Invalid CTF token id: {}", token_id))?;
+            let quantity = wallet.erc1155_balance(&ctf, id, USDC_DECIMALS).await?;
+            if quantity > Decimal::ZERO {
+                holdings.push(OutcomeTokenHolding {
+                    asset_id: token_id,
+                    market_id: leg.market_id.clone(),
+                    outcome: leg.outcome.clone(),
+                    quantity,
+                });
+            }
+        }
+        Ok(Some(holdings))
+    }
+
+    async fn market_resolution(&self, market_id: &str) -> Result<ResolutionStatus> {
+        PolymarketBookSource::new().resolution(market_id).await
+    }
+
+    /// Redeem the market's outcome tokens for USDC through the CTF. Both
+    /// index sets are passed; the contract pays only the winning one.
+    async fn redeem(&self, leg: &PositionLeg) -> Result<Option<String>> {
+        let condition_id = PolymarketBookSource::new().condition_id(&leg.market_id).await?;
+        let condition: [u8; 32] = hex::decode(condition_id.trim_start_matches("0x"))?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Condition id {} is not 32 bytes", condition_id))?;
+
+        let wallet = self.wallet()?;
+        let data = abi::encode_call(
+            "redeemPositions(address,bytes32,bytes32,uint256[])",
+            &[
+                AbiToken::Address(wallet.chain().parse_address(POLYGON_USDC)?),
+                AbiToken::FixedBytes32([0u8; 32]),
+                AbiToken::FixedBytes32(condition),
+                AbiToken::UintArray(vec![1, 2]),
+            ],
+        );
+        let ctf = wallet.chain().parse_address(CONDITIONAL_TOKENS)?;
+        let tx_hash = wallet.send_call(&ctf, 0, data).await?;
+        match wallet.wait_for_receipt(&tx_hash).await? {
+            Some(receipt) if !receipt.success => Err(anyhow::anyhow!("Redemption {} reverted", tx_hash)),
+            _ => Ok(Some(tx_hash)),
+        }
     }
 }
 
 /// Bitcoin trade executor
 pub struct BtcTradeExecutor {
     config: crate::config::BitcoinConfig,
+    prices: TreasuryConfig,
 }
 
 impl BtcTradeExecutor {
     pub fn new(config: crate::config::BitcoinConfig) -> Self {
-        Self { config }
+        Self { config, prices: TreasuryConfig::default() }
+    }
+
+    /// USD rates used to convert a leg's cost into the protocol's collateral
+    pub fn with_prices(mut self, prices: TreasuryConfig) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    /// Longest a leg can block waiting for its transaction to confirm. A
+    /// leg timeout shorter than this drops the txid of a broadcast order.
+    pub fn confirmation_wait(&self) -> Duration {
+        match self.config.protocol.as_str() {
+            "stacks" => crate::chains::stacks::client::CONFIRMATION_TIMEOUT,
+            // Collateral approval, then the buy
+            "rsk" => crate::chains::evm::client::RECEIPT_TIMEOUT * 2,
+            "liquid" => crate::chains::liquid::CONFIRMATION_TIMEOUT,
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Collateral paid for `config.size` shares at the limit price, in the
+    /// protocol's asset. Uses the treasury's rate, so it matches what the
+    /// treasury reserves for the leg.
+    pub fn collateral_amount(&self, config: &TradeConfig, odds: Decimal) -> Result<Decimal> {
+        let asset = Asset::for_protocol(&self.config.protocol);
+        let price = asset.usd_price(&self.prices);
+        if price <= Decimal::ZERO {
+            return Err(anyhow::anyhow!("No USD price for {}", asset));
+        }
+        Ok(config.size * config.limit_price(odds) / price)
     }
 
     /// Check if wallet is configured
     pub fn is_configured(&self) -> bool {
         match self.config.protocol.as_str() {
             "lightning" => self.config.lightning.as_ref()
-                .and_then(|l| Some(!l.endpoint.is_empty()))
-                .unwrap_or(false),
+                .and_then(|l| l.endpoint.as_ref())
+                .is_some_and(|e| !e.is_empty()),
             "ordinals" => self.config.ordinals.as_ref()
-                .map(|o| !o.address.is_empty())
+                .map(|o| {
+                    o.address.is_some()
+                        && o.endpoint.is_some()
+                        && o.parent_inscription.is_some()
+                        && o.signer_url.is_some()
+                })
+                .unwrap_or(false),
+            "stacks" => self.config.stacks.as_ref()
+                .map(|s| {
+                    s.private_key.as_ref().is_some_and(|k| !k.is_empty())
+                        && s.market_contract.is_some()
+                })
                 .unwrap_or(false),
-            "stacks" | "rsk" | "liquid" => self.config.stacks.as_ref()
-                .and_then(|s| s.private_key.as_ref())
-                .or_else(|| self.config.rsk.as_ref().and_then(|r| r.private_key.as_ref()))
-                .or_else(|| self.config.liquid.as_ref().and_then(|l| l.private_key.as_ref()))
-                .map(|k| !k.is_empty())
+            "rsk" => self.config.rsk.as_ref()
+                .map(|r| {
+                    r.rpc_url.is_some()
+                        && r.private_key.as_ref().is_some_and(|k| !k.is_empty())
+                        && r.market_contract.is_some()
+                })
+                .unwrap_or(false),
+            "liquid" => self.config.liquid.as_ref()
+                .map(|l| {
+                    l.rpc_url.is_some()
+                        && l.settlement_address.is_some()
+                        && l.collateral_asset.is_some()
+                })
                 .unwrap_or(false),
             _ => false,
         }
@@ -165,7 +681,7 @@ impl TradeExecutor for BtcTradeExecutor {
     async fn execute_arbitrage(
         &self,
         opportunity: &ArbitrageOpportunity,
-        _config: &TradeConfig,
+        config: &TradeConfig,
     ) -> Result<TradeResult> {
         tracing::info!(
             "Executing BTC trade: {} at {}",
@@ -181,24 +697,20 @@ impl TradeExecutor for BtcTradeExecutor {
                 executed_at: Utc::now(),
                 error: Some(format!("BTC {} wallet not configured", self.config.protocol)),
                 position_id: None,
+                token_id: None,
+                fill_price: None,
+                filled_size: None,
+                fees: None,
             });
         }
 
         // Implement based on protocol
         match self.config.protocol.as_str() {
             "lightning" => self.execute_lightning_trade(opportunity).await,
-            "ordinals" => self.execute_ordinals_trade(opportunity).await,
-            "stacks" => self.execute_stacks_trade(opportunity).await,
-            "rsk" => self.execute_rsk_trade(opportunity).await,
-            "liquid" => self.execute_liquid_trade(opportunity).await,
-            _ => self.execute_generic_trade(opportunity).await,
-        }
-    }
-            "lightning" => self.execute_lightning_trade(opportunity).await,
-            "ordinals" => self.execute_ordinals_trade(opportunity).await,
-            "stacks" => self.execute_stacks_trade(opportunity).await,
-            "rsk" => self.execute_rsk_trade(opportunity).await,
-            "liquid" => self.execute_liquid_trade(opportunity).await,
+            "ordinals" => self.execute_ordinals_trade(opportunity, config).await,
+            "stacks" => self.execute_stacks_trade(opportunity, config).await,
+            "rsk" => self.execute_rsk_trade(opportunity, config).await,
+            "liquid" => self.execute_liquid_trade(opportunity, config).await,
             _ => self.execute_generic_trade(opportunity).await,
         }
     }
@@ -217,8 +729,88 @@ impl TradeExecutor for BtcTradeExecutor {
             return Ok(Decimal::ZERO);
         }
 
-        // TODO: Query actual balance based on protocol
-        Ok(Decimal::ZERO)
+        match self.config.protocol.as_str() {
+            "stacks" => {
+                let stacks = self.config.stacks.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Stacks config missing"))?;
+                StacksMarket::from_config(stacks)?.get_balance().await
+            }
+            "rsk" => {
+                let rsk = self.config.rsk.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("RSK config missing"))?;
+                RskMarket::from_config(rsk)?.collateral_balance().await
+            }
+            "liquid" => {
+                let liquid = self.config.liquid.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Liquid config missing"))?;
+                LiquidMarket::from_config(liquid)?.lbtc_balance().await
+            }
+            "lightning" => {
+                let lightning = self.config.lightning.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Lightning config missing"))?;
+                LndClient::from_config(lightning)?.channel_balance().await
+            }
+            "ordinals" => {
+                let ordinals = self.config.ordinals.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Ordinals config missing"))?;
+                OrdinalsTrader::from_config(ordinals)?.balance().await
+            }
+            other => Err(anyhow::anyhow!("Balance not supported for protocol {}", other)),
+        }
+    }
+
+    /// Liquid-issued outcome tokens currently held by the wallet
+    async fn outcome_holdings(&self) -> Result<Vec<OutcomeTokenHolding>> {
+        match self.config.liquid.as_ref() {
+            Some(liquid) if self.config.protocol == "liquid" => {
+                LiquidMarket::from_config(liquid)?.outcome_holdings().await
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Predyx positions on Lightning and outcome assets on Liquid
+    async fn reported_holdings(&self, _tracked: &[PositionLeg]) -> Result<Option<Vec<OutcomeTokenHolding>>> {
+        match self.config.protocol.as_str() {
+            "lightning" => {
+                let lightning = self.config.lightning.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Lightning config missing"))?;
+                LightningMarket::from_config(lightning).positions().await.map(Some)
+            }
+            "liquid" => self.outcome_holdings().await.map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    async fn market_resolution(&self, market_id: &str) -> Result<ResolutionStatus> {
+        match self.config.protocol.as_str() {
+            "lightning" => {
+                let lightning = self.config.lightning.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Lightning config missing"))?;
+                LightningMarket::from_config(lightning).resolution(market_id).await
+            }
+            "ordinals" => {
+                let ordinals = self.config.ordinals.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Ordinals config missing"))?;
+                let (Some(endpoint), Some(parent)) = (&ordinals.endpoint, &ordinals.parent_inscription) else {
+                    return Err(anyhow::anyhow!("ord server endpoint not configured"));
+                };
+                let marketplace = OrdinalsMarketplace::new(endpoint.clone(), parent.clone());
+                Ok(marketplace.fetch_market_state(market_id).await?.resolution())
+            }
+            other => Err(anyhow::anyhow!("Resolution tracking not supported for {}", other)),
+        }
+    }
+
+    /// Lightning markets pay out when claimed; other protocols settle
+    /// winning tokens with the market operator
+    async fn redeem(&self, leg: &PositionLeg) -> Result<Option<String>> {
+        match self.config.lightning.as_ref() {
+            Some(lightning) if self.config.protocol == "lightning" => {
+                LightningMarket::from_config(lightning).claim_payout(&leg.market_id).await.map(Some)
+            }
+            _ => Ok(None),
+        }
     }
 }
 
@@ -239,79 +831,218 @@ impl BtcTradeExecutor {
             executed_at: Utc::now(),
             error: Some("Lightning trades not implemented".to_string()),
             position_id: None,
+            token_id: None,
+            fill_price: None,
+            filled_size: None,
+            fees: None,
         })
     }
 
-    async fn execute_ordinals_trade(&self, _opportunity: &ArbitrageOpportunity) -> Result<TradeResult> {
+    async fn execute_ordinals_trade(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> Result<TradeResult> {
         tracing::info!("Executing Ordinals trade");
 
-        // TODO: Implement Ordinals trade
-        // Steps:
-        // 1. Buy/sell ordinal inscription
-        // 2. Wait for blockchain confirmation
-        // 3. Track transaction
+        let ordinals = self.config.ordinals.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Ordinals config missing"))?;
+        let endpoint = ordinals.endpoint.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("ord server endpoint not configured"))?;
+        let parent = ordinals.parent_inscription.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Ordinals parent inscription not configured"))?;
+
+        let odds = &opportunity.btc_market_odds;
+        let marketplace = OrdinalsMarketplace::new(endpoint.clone(), parent.clone());
+        let state = marketplace.fetch_market_state(&odds.market_id).await?;
+        let offer = state.best_offer(&odds.outcome)
+            .ok_or_else(|| anyhow::anyhow!("No open offer for {} {}", odds.market_id, odds.outcome))?;
+
+        // Each inscription is one unit; refuse offers priced above the limit
+        if state.offer_odds(offer) > config.limit_price(odds.odds) {
+            return Ok(TradeResult {
+                polymarket_tx: None,
+                btc_tx: None,
+                status: TradeStatus::Failed,
+                executed_at: Utc::now(),
+                error: Some(format!(
+                    "Best offer {} sats is above limit {}",
+                    offer.price_sats,
+                    config.limit_price(odds.odds)
+                )),
+                position_id: None,
+                token_id: None,
+                fill_price: None,
+                filled_size: None,
+                fees: None,
+            });
+        }
+        let trader = OrdinalsTrader::from_config(ordinals)?;
+        let txid = trader.buy(offer).await?;
 
+        // Broadcast only; the inscription is ours once the tx confirms
         Ok(TradeResult {
             polymarket_tx: None,
-            btc_tx: Some("ord_mock_inscription_id".to_string()),
+            btc_tx: Some(txid),
             status: TradeStatus::Pending,
             executed_at: Utc::now(),
-            error: Some("Ordinals trades not implemented".to_string()),
+            error: None,
             position_id: None,
+            token_id: Some(offer.inscription_id.clone()),
+            fill_price: None,
+            filled_size: None,
+            fees: None,
         })
     }
 
-    async fn execute_stacks_trade(&self, _opportunity: &ArbitrageOpportunity) -> Result<TradeResult> {
+    async fn execute_stacks_trade(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> Result<TradeResult> {
         tracing::info!("Executing Stacks trade");
 
-        // TODO: Implement Stacks smart contract call
-        // Steps:
-        // 1. Construct contract call
-        // 2. Sign with STX private key
-        // 3. Broadcast transaction
+        let stacks = self.config.stacks.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Stacks config missing"))?;
+        let market = StacksMarket::from_config(stacks)?;
+
+        let odds = &opportunity.btc_market_odds;
+        let stx = self.collateral_amount(config, odds.odds)?;
+        let (txid, status) = market
+            .buy_outcome(&odds.market_id, &odds.outcome, stx, config.limit_price(odds.odds))
+            .await?;
+
+        let (status, error) = match status {
+            StacksTxStatus::Anchored { block_height, result } => {
+                tracing::info!("Stacks tx {} anchored at block {}", txid, block_height);
+                match result {
+                    Some(repr) if repr.starts_with("(err") => {
+                        (TradeStatus::Failed, Some(format!("Contract returned {}", repr)))
+                    }
+                    _ => (TradeStatus::Success, None),
+                }
+            }
+            StacksTxStatus::Pending => (
+                TradeStatus::Pending,
+                Some("Stacks tx not anchored before timeout".to_string()),
+            ),
+            StacksTxStatus::Failed(reason) => (TradeStatus::Failed, Some(reason)),
+        };
 
         Ok(TradeResult {
             polymarket_tx: None,
-            btc_tx: Some("stacks_mock_tx".to_string()),
-            status: TradeStatus::Pending,
+            btc_tx: Some(txid),
+            status,
             executed_at: Utc::now(),
-            error: Some("Stacks trades not implemented".to_string()),
+            error,
             position_id: None,
+            token_id: None,
+            fill_price: None,
+            filled_size: None,
+            fees: None,
         })
     }
 
-    async fn execute_rsk_trade(&self, _opportunity: &ArbitrageOpportunity) -> Result<TradeResult> {
+    async fn execute_rsk_trade(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> Result<TradeResult> {
         tracing::info!("Executing RSK trade");
 
-        // TODO: Implement RSK smart contract call
-        // Similar to Ethereum but on RSK network
+        let rsk = self.config.rsk.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("RSK config missing"))?;
+        let market = RskMarket::from_config(rsk)?;
+
+        let odds = &opportunity.btc_market_odds;
+        let amount = self.collateral_amount(config, odds.odds)?;
+        let outcome = market
+            .buy_outcome(&odds.market_id, &odds.outcome, amount, config.limit_price(odds.odds))
+            .await?;
+
+        let (status, error) = match &outcome.receipt {
+            Some(receipt) if receipt.success => (TradeStatus::Success, None),
+            Some(receipt) => (
+                TradeStatus::Failed,
+                Some(format!("RSK tx reverted in block {}", receipt.block_number)),
+            ),
+            None => (
+                TradeStatus::Pending,
+                Some("RSK tx not mined before timeout".to_string()),
+            ),
+        };
 
         Ok(TradeResult {
             polymarket_tx: None,
-            btc_tx: Some("rsk_mock_tx".to_string()),
-            status: TradeStatus::Pending,
+            btc_tx: Some(outcome.tx_hash),
+            status,
             executed_at: Utc::now(),
-            error: Some("RSK trades not implemented".to_string()),
+            error,
             position_id: None,
+            token_id: None,
+            fill_price: None,
+            filled_size: None,
+            fees: None,
         })
     }
 
-    async fn execute_liquid_trade(&self, _opportunity: &ArbitrageOpportunity) -> Result<TradeResult> {
-        tracing::info!("Executing Liquid trade");
+    async fn execute_liquid_trade(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> Result<TradeResult> {
+        tracing::info!(
+            "Executing Liquid trade: {} {}",
+            opportunity.btc_market_odds.market_id,
+            opportunity.btc_market_odds.outcome
+        );
+
+        let liquid = self.config.liquid.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Liquid config missing"))?;
+        let market = LiquidMarket::from_config(liquid)?;
+
+        let collateral = self.collateral_amount(config, opportunity.btc_market_odds.odds)?;
+        let (txid, confirmations) = market.settle(collateral).await?;
 
-        // TODO: Implement Liquid transaction
-        // Confidential transaction on Liquid sidechain
+        let (status, error) = if confirmations >= market.min_confirmations() {
+            (TradeStatus::Success, None)
+        } else {
+            (
+                TradeStatus::Pending,
+                Some(format!(
+                    "Liquid tx has {}/{} confirmations",
+                    confirmations,
+                    market.min_confirmations()
+                )),
+            )
+        };
 
         Ok(TradeResult {
             polymarket_tx: None,
-            btc_tx: Some("liquid_mock_tx".to_string()),
-            status: TradeStatus::Pending,
+            btc_tx: Some(txid),
+            status,
             executed_at: Utc::now(),
-            error: Some("Liquid trades not implemented".to_string()),
+            error,
             position_id: None,
+            token_id: self.outcome_token_for(&opportunity.btc_market_odds),
+            fill_price: None,
+            filled_size: None,
+            fees: None,
         })
     }
 
+    /// Outcome token a BTC-side buy is settled in, if the protocol issues one
+    pub fn outcome_token_for(&self, odds: &crate::models::MarketOdds) -> Option<String> {
+        match self.config.protocol.as_str() {
+            "liquid" => self.config.liquid.as_ref()?
+                .outcome_assets
+                .iter()
+                .find(|a| a.market_id == odds.market_id && a.outcome == odds.outcome)
+                .map(|a| a.asset_id.clone()),
+            _ => None,
+        }
+    }
+
     async fn execute_generic_trade(&self, _opportunity: &ArbitrageOpportunity) -> Result<TradeResult> {
         tracing::info!("Executing generic BTC trade");
 
@@ -322,111 +1053,1832 @@ impl BtcTradeExecutor {
             executed_at: Utc::now(),
             error: Some("Generic BTC trades not implemented".to_string()),
             position_id: None,
+            token_id: None,
+            fill_price: None,
+            filled_size: None,
+            fees: None,
         })
     }
 }
 
 /// Orchestrates arbitrage execution across both markets with position tracking
 pub struct ArbitrageExecutor {
-    polymarket_executor: PolymarketTradeExecutor,
-    btc_executor: BtcTradeExecutor,
-    position_manager: PositionManager,
+    polymarket_executor: Box<dyn TradeExecutor>,
+    btc_executor: Box<dyn TradeExecutor>,
+    position_manager: std::sync::Mutex<PositionManager>,
+    plan: ExecutionPlan,
+    unwind: UnwindEngine,
+    journal: Option<std::sync::Mutex<TradeJournal>>,
+    approvals: Option<std::sync::Arc<ApprovalQueue>>,
+    risk: Option<RiskEngine>,
+    breakers: Option<CircuitBreakers>,
+    treasury: Option<Treasury>,
 }
 
 impl ArbitrageExecutor {
     pub fn new(
         polymarket_config: crate::config::PolymarketConfig,
         btc_config: crate::config::BitcoinConfig,
+    ) -> Self {
+        Self::with_executors(
+            Box::new(PolymarketTradeExecutor::new(polymarket_config)),
+            Box::new(BtcTradeExecutor::new(btc_config)),
+        )
+    }
+
+    pub fn with_executors(
+        polymarket_executor: Box<dyn TradeExecutor>,
+        btc_executor: Box<dyn TradeExecutor>,
     ) -> Self {
         Self {
-            polymarket_executor: PolymarketTradeExecutor::new(polymarket_config),
-            btc_executor: BtcTradeExecutor::new(btc_config),
-            position_manager: PositionManager::new(),
+            polymarket_executor,
+            btc_executor,
+            position_manager: std::sync::Mutex::new(PositionManager::new()),
+            plan: ExecutionPlan::default(),
+            unwind: UnwindEngine::default(),
+            journal: None,
+            approvals: None,
+            risk: None,
+            breakers: None,
+            treasury: None,
         }
     }
 
-    /// Execute both sides of arbitrage trade
-    pub async fn execute(
-        &mut self,
-        opportunity: &ArbitrageOpportunity,
-        config: &TradeConfig,
-    ) -> Result<TradeResult> {
-        tracing::info!(
-            "Executing arbitrage: {:.2}% profit",
-            opportunity.implied_profit * Decimal::from(100)
+    /// Live executors, or paper ones when `paper_trading` is set, with the
+    /// configured plan, unwind policy and journal
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let unwind = UnwindEngine::new(
+            UnwindPolicy::from_config(&config.risk),
+            sink_from_config(&config.risk),
         );
-
-        // Execute trades
-        let poly_result = self
-            .polymarket_executor
-            .execute_arbitrage(opportunity, config)
-            .await?;
-
-        let btc_result = self
-            .btc_executor
-            .execute_arbitrage(opportunity, config)
-            .await?;
-
-        // Determine overall status
-        let status = if poly_result.status == TradeStatus::Success
-            && btc_result.status == TradeStatus::Success
-        {
-            TradeStatus::Success
-        } else if poly_result.status == TradeStatus::Pending
-            || btc_result.status == TradeStatus::Pending
-        {
-            TradeStatus::Pending
-        } else if poly_result.status == TradeStatus::Success
-            || btc_result.status == TradeStatus::Success
-        {
-            TradeStatus::Partial
+        let trading = &config.trading;
+        let (polymarket, btc): (Box<dyn TradeExecutor>, Box<dyn TradeExecutor>) = if trading.paper_trading {
+            crate::paper::paper_executors(config)?
         } else {
-            TradeStatus::Failed
+            (
+                Box::new(RateLimitedExecutor::new(
+                    Box::new(PolymarketTradeExecutor::new(config.polymarket.clone())),
+                    trading.polymarket_requests_per_min,
+                    trading.polymarket_orders_per_min,
+                )),
+                Box::new(RateLimitedExecutor::new(
+                    Box::new(BtcTradeExecutor::new(config.bitcoin.clone()).with_prices(config.treasury.clone())),
+                    trading.btc_requests_per_min,
+                    trading.btc_orders_per_min,
+                )),
+            )
         };
 
-        // Create position if both sides attempted
-        let position_id = if poly_result.polymarket_tx.is_some() || btc_result.btc_tx.is_some() {
-            let entry_price = (opportunity.polymarket_odds.odds + opportunity.btc_market_odds.odds) / Decimal::from(2);
-            self.position_manager
-                .open_position(
-                    opportunity,
-                    entry_price,
-                    config.max_position_size,
-                    poly_result.polymarket_tx.clone(),
-                    btc_result.btc_tx.clone(),
-                )
-                .ok()
+        let executor = Self::with_executors(polymarket, btc)
+            .with_plan(ExecutionPlan::from_config(&config.trading)?)
+            .with_unwind(unwind)
+            .with_risk(RiskEngine::new(config.risk.clone()).with_ledger(PnlLedger::from_config(&config.risk)?))
+            .with_journal(TradeJournal::open(&config.trading.journal_path)?)
+            .with_positions(PositionManager::with_store(Box::new(SqlitePositionStore::open(
+                &config.trading.positions_db_path,
+            )?))?)
+            .with_breakers(CircuitBreakers::new(
+                std::sync::Arc::new(KillSwitch::open(&config.trading.halt_state_path)?),
+                config.risk.clone(),
+            ));
+        let executor = if config.treasury.enabled {
+            executor.with_treasury(Treasury::from_config(config))
         } else {
-            None
+            executor
         };
 
-        Ok(TradeResult {
-            polymarket_tx: poly_result.polymarket_tx,
-            btc_tx: btc_result.btc_tx,
-            status,
-            executed_at: Utc::now(),
-            error: poly_result.error.or(btc_result.error),
-            position_id,
+        Ok(if config.trading.require_confirmation {
+            executor.with_approvals(std::sync::Arc::new(ApprovalQueue::from_config(config)))
+        } else {
+            executor
         })
     }
 
-    /// Get position manager reference
-    pub fn position_manager(&self) -> &PositionManager {
-        &self.position_manager
+    pub fn with_plan(mut self, plan: ExecutionPlan) -> Self {
+        self.plan = plan;
+        self
     }
 
-    /// Get mutable position manager reference
-    pub fn position_manager_mut(&mut self) -> &mut PositionManager {
-        &mut self.position_manager
+    pub fn with_unwind(mut self, unwind: UnwindEngine) -> Self {
+        self.unwind = unwind;
+        self
     }
-}
 
-impl Default for ArbitrageExecutor {
-    fn default() -> Self {
-        Self {
-            polymarket_executor: PolymarketTradeExecutor::new(crate::config::PolymarketConfig::default()),
-            btc_executor: BtcTradeExecutor::new(crate::config::BitcoinConfig::default()),
-            position_manager: PositionManager::new(),
-        }
+    /// Track positions in `positions`, e.g. one backed by a persistent store
+    pub fn with_positions(mut self, positions: PositionManager) -> Self {
+        self.position_manager = std::sync::Mutex::new(positions);
+        self
+    }
+
+    /// Journal every leg intent before it is sent
+    pub fn with_journal(mut self, journal: TradeJournal) -> Self {
+        self.journal = Some(std::sync::Mutex::new(journal));
+        self
+    }
+
+    /// Hold every trade until it is approved through `queue`
+    pub fn with_approvals(mut self, queue: std::sync::Arc<ApprovalQueue>) -> Self {
+        self.approvals = Some(queue);
+        self
+    }
+
+    /// Run pre-trade risk checks before approval and execution
+    pub fn with_risk(mut self, risk: RiskEngine) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Refuse to trade while the breakers' kill switch is halted, and feed
+    /// them quotes, leg outcomes and daily PnL
+    pub fn with_breakers(mut self, breakers: CircuitBreakers) -> Self {
+        self.breakers = Some(breakers);
+        self
+    }
+
+    /// Size trades to what both wallets can fund and reserve funds per leg
+    pub fn with_treasury(mut self, treasury: Treasury) -> Self {
+        self.treasury = Some(treasury);
+        self
+    }
+
+    pub fn plan(&self) -> &ExecutionPlan {
+        &self.plan
+    }
+
+    pub fn approvals(&self) -> Option<&std::sync::Arc<ApprovalQueue>> {
+        self.approvals.as_ref()
+    }
+
+    pub fn risk(&self) -> Option<&RiskEngine> {
+        self.risk.as_ref()
+    }
+
+    pub fn treasury(&self) -> Option<&Treasury> {
+        self.treasury.as_ref()
+    }
+
+    pub fn breakers(&self) -> Option<&CircuitBreakers> {
+        self.breakers.as_ref()
+    }
+
+    pub fn kill_switch(&self) -> Option<&std::sync::Arc<KillSwitch>> {
+        self.breakers.as_ref().map(|b| b.kill_switch())
+    }
+
+    /// Why trading is halted, if it is
+    fn halted(&self) -> Option<String> {
+        self.kill_switch().and_then(|switch| switch.state().active).map(|halt| halt.reason)
+    }
+
+    /// Ask both venues to cancel their resting orders, e.g. when trading is
+    /// halted. A venue that can't is logged; its orders must be cleared by hand.
+    pub async fn cancel_all_orders(&self) -> usize {
+        let mut cancelled = 0;
+        for leg in [Leg::Polymarket, Leg::Btc] {
+            match self.executor(leg).cancel_all_orders().await {
+                Ok(n) => cancelled += n,
+                Err(e) => tracing::error!("Could not cancel resting {} orders; check the venue: {}", leg, e),
+            }
+        }
+        if cancelled > 0 {
+            tracing::warn!("Cancelled {} resting orders", cancelled);
+        }
+        cancelled
+    }
+
+    fn journal(&self, key: &str, event: JournalEvent) -> Result<()> {
+        match &self.journal {
+            Some(journal) => journal.lock().expect("trade journal lock poisoned").append(key, event),
+            None => Ok(()),
+        }
+    }
+
+    fn journaled(&self, key: &str) -> bool {
+        match &self.journal {
+            Some(journal) => journal.lock().expect("trade journal lock poisoned").contains(key),
+            None => false,
+        }
+    }
+
+    pub fn executor(&self, leg: Leg) -> &dyn TradeExecutor {
+        match leg {
+            Leg::Polymarket => self.polymarket_executor.as_ref(),
+            Leg::Btc => self.btc_executor.as_ref(),
+        }
+    }
+
+    /// Settle held orders that have ended, then refresh the treasury's
+    /// missing or stale balances from the venues
+    pub async fn refresh_treasury(&self) {
+        if let Some(treasury) = self.treasury.as_ref() {
+            self.settle_pending(treasury).await;
+            self.refresh_balances(treasury).await;
+        }
+    }
+
+    async fn refresh_balances(&self, treasury: &Treasury) {
+        if let Err(e) = treasury.refresh_btc_usd().await {
+            tracing::warn!("Failed to refresh BTC/USD rate: {}", e);
+        }
+        for leg in [Leg::Polymarket, Leg::Btc] {
+            if treasury.is_stale(leg) {
+                if let Err(e) = treasury.refresh(leg, self.executor(leg)).await {
+                    tracing::warn!("Failed to refresh {} balance: {}", leg, e);
+                }
+            }
+        }
+    }
+
+    /// Current BTC/USD rate for a leg held in BTC-denominated collateral
+    pub async fn btc_usd(&self, leg: Leg) -> Option<Decimal> {
+        let treasury = self.treasury.as_ref()?;
+        if let Err(e) = treasury.refresh_btc_usd().await {
+            tracing::warn!("Failed to refresh BTC/USD rate: {}", e);
+        }
+        treasury.btc_usd(leg)
+    }
+
+    /// Cap the trade at the size both venues can fund
+    async fn fund(&self, opportunity: &ArbitrageOpportunity, config: &TradeConfig) -> Result<TradeConfig, String> {
+        let Some(treasury) = self.treasury.as_ref() else {
+            return Ok(config.clone());
+        };
+        self.refresh_balances(treasury).await;
+
+        let size = treasury.fundable_size(opportunity, config);
+        if size <= Decimal::ZERO {
+            let free: Vec<String> = [Leg::Polymarket, Leg::Btc]
+                .iter()
+                .map(|leg| {
+                    let funds = treasury.funds(*leg);
+                    format!("{} {} {}", leg, funds.free, funds.asset)
+                })
+                .collect();
+            return Err(format!("Insufficient funds: {} free", free.join(", ")));
+        }
+        if size < config.size {
+            tracing::info!("Sizing {} down from {} to {} to fit balances", config.idempotency_key, config.size, size);
+        }
+        Ok(TradeConfig { size, ..config.clone() })
+    }
+
+    /// Hold a leg's funds until its order resolves
+    async fn reserve(&self, leg: Leg, opportunity: &ArbitrageOpportunity, config: &TradeConfig) -> Result<()> {
+        let Some(treasury) = self.treasury.as_ref() else {
+            return Ok(());
+        };
+        self.refresh_balances(treasury).await;
+
+        let cost = treasury
+            .leg_cost(leg, opportunity, config, config.size)
+            .ok_or_else(|| anyhow::anyhow!("No USD price for {}", treasury.asset(leg)))?;
+        treasury.reserve(&config.client_order_id(leg), leg, cost)
+    }
+
+    /// Spend, keep or release a leg's reservation according to its outcome
+    fn settle_reservation(&self, leg: Leg, config: &TradeConfig, outcome: &LegOutcome) {
+        let Some(treasury) = self.treasury.as_ref() else {
+            return;
+        };
+        let client_order_id = config.client_order_id(leg);
+        match outcome {
+            LegOutcome::Completed(result) => match result.status {
+                TradeStatus::Success | TradeStatus::Partial => treasury.spend(&client_order_id),
+                TradeStatus::Failed => treasury.release(&client_order_id),
+                // Still resting at the venue
+                TradeStatus::Pending => treasury.hold(&client_order_id),
+            },
+            LegOutcome::Errored(_) | LegOutcome::Cancelled(_) | LegOutcome::Skipped => treasury.release(&client_order_id),
+            // The order may still be live; keep its funds held
+            LegOutcome::TimedOut | LegOutcome::Unknown(_) => treasury.hold(&client_order_id),
+        }
+    }
+
+    /// Spend or release the funds of held orders the venue now reports as
+    /// ended. Orders it can't look up are released after the pending timeout.
+    async fn settle_pending(&self, treasury: &Treasury) {
+        for leg in [Leg::Polymarket, Leg::Btc] {
+            for client_order_id in treasury.pending(leg) {
+                let status = match self.executor(leg).find_order(&client_order_id).await {
+                    Ok(OrderLookup::Found(result)) => Some(result.status),
+                    Ok(OrderLookup::NotFound) => Some(TradeStatus::Failed),
+                    Ok(OrderLookup::Unsupported) => None,
+                    Err(e) => {
+                        tracing::warn!("Failed to look up held {} order {}: {}", leg, client_order_id, e);
+                        None
+                    }
+                };
+                match status {
+                    Some(TradeStatus::Success | TradeStatus::Partial) => treasury.spend(&client_order_id),
+                    Some(TradeStatus::Failed) => treasury.release(&client_order_id),
+                    Some(TradeStatus::Pending) => {}
+                    None if treasury.is_expired(&client_order_id) => {
+                        tracing::warn!("Releasing funds held for {} order {}: its state is unknown", leg, client_order_id);
+                        treasury.release(&client_order_id);
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    /// Run one leg under its timeout, capturing errors instead of returning early
+    async fn run_leg(
+        &self,
+        leg: Leg,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> LegOutcome {
+        self.send_leg(&config.idempotency_key, leg, opportunity, config).await
+    }
+
+    /// Run one leg journaled under the execution `key`. The leg's client
+    /// order id comes from `config`, so an unwind retry gets its own.
+    async fn send_leg(
+        &self,
+        key: &str,
+        leg: Leg,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> LegOutcome {
+        if let Some(reason) = self.halted() {
+            tracing::warn!("Not sending {} leg of {}: trading halted", leg, key);
+            return LegOutcome::Errored(format!("trading halted: {}", reason));
+        }
+        if let Err(e) = self.reserve(leg, opportunity, config).await {
+            tracing::error!("Not sending {} leg of {}: {}", leg, key, e);
+            return LegOutcome::Errored(format!("insufficient funds: {}", e));
+        }
+
+        let intent = JournalEvent::Intent {
+            leg,
+            client_order_id: config.client_order_id(leg),
+            opportunity: Box::new(opportunity.clone()),
+            config: config.clone(),
+        };
+        if let Err(e) = self.journal(key, intent) {
+            tracing::error!("Not sending {} leg of {}: {}", leg, key, e);
+            let outcome = LegOutcome::Errored(format!("journal write failed: {}", e));
+            self.settle_reservation(leg, config, &outcome);
+            return outcome;
+        }
+
+        let execution = self.executor(leg).execute_arbitrage(opportunity, config);
+        let outcome = match tokio::time::timeout(config.leg_timeout(leg), execution).await {
+            Ok(Ok(result)) => LegOutcome::Completed(result),
+            Ok(Err(e)) => {
+                tracing::error!("{} leg failed: {}", leg, e);
+                LegOutcome::Errored(e.to_string())
+            }
+            Err(_) => {
+                tracing::error!("{} leg timed out after {:?}", leg, config.leg_timeout(leg));
+                LegOutcome::TimedOut
+            }
+        };
+
+        self.settle_reservation(leg, config, &outcome);
+        if let Some(breakers) = self.breakers.as_ref() {
+            breakers.record_leg(leg, &outcome);
+        }
+        if let Err(e) = self.journal(key, JournalEvent::LegResult { leg, outcome: outcome.clone() }) {
+            tracing::error!("Failed to journal {} leg result of {}: {}", leg, key, e);
+        }
+        outcome
+    }
+
+    /// Whether the plan sends the second leg given the first leg's outcome
+    fn hedges(&self, first: &LegOutcome) -> bool {
+        match (self.plan.strategy, first) {
+            // It may never have filled; a hedge could open the exposure it meant to close
+            (_, LegOutcome::Unknown(_)) => false,
+            (ExecutionStrategy::HedgeAfterFill, _) => first.is_filled(),
+            _ => first.is_accepted(),
+        }
+    }
+
+    /// Cancel the working order of a leg whose counterpart failed
+    async fn cancel_orphan(&self, config: &TradeConfig, poly: LegOutcome, btc: LegOutcome) -> (LegOutcome, LegOutcome) {
+        match (poly.is_accepted(), btc.is_accepted()) {
+            (true, false) => (self.cancel_leg(Leg::Polymarket, config, poly).await, btc),
+            (false, true) => (poly, self.cancel_leg(Leg::Btc, config, btc).await),
+            _ => (poly, btc),
+        }
+    }
+
+    /// Cancel a leg's working order after the other leg failed
+    async fn cancel_leg(&self, leg: Leg, config: &TradeConfig, outcome: LegOutcome) -> LegOutcome {
+        let LegOutcome::Completed(result) = outcome else {
+            return outcome;
+        };
+        if result.status != TradeStatus::Pending {
+            return LegOutcome::Completed(result);
+        }
+
+        let tx_id = match leg {
+            Leg::Polymarket => result.polymarket_tx.clone(),
+            Leg::Btc => result.btc_tx.clone(),
+        };
+        let Some(tx_id) = tx_id else {
+            return LegOutcome::Completed(result);
+        };
+
+        match self.executor(leg).cancel_order(&tx_id).await {
+            Ok(true) => {
+                tracing::info!("Cancelled {} order {}", leg, tx_id);
+                let cancelled = LegOutcome::Cancelled(result);
+                self.settle_reservation(leg, config, &cancelled);
+                cancelled
+            }
+            Ok(false) => LegOutcome::Completed(result),
+            Err(e) => {
+                tracing::error!("Failed to cancel {} order {}: {}", leg, tx_id, e);
+                LegOutcome::Completed(result)
+            }
+        }
+    }
+
+    /// Execute both legs per the plan. Returns `(polymarket, btc)` outcomes.
+    async fn execute_legs(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> (LegOutcome, LegOutcome) {
+        match self.plan.strategy {
+            ExecutionStrategy::ThinSideFirst | ExecutionStrategy::HedgeAfterFill => {
+                let first = self.plan.thin_leg;
+                let first_outcome = self.run_leg(first, opportunity, config).await;
+
+                let second_outcome = if self.hedges(&first_outcome) {
+                    self.run_leg(first.other(), opportunity, config).await
+                } else {
+                    tracing::warn!(
+                        "{} leg is {:?}; not sending the {} leg",
+                        first,
+                        first_outcome.status(),
+                        first.other()
+                    );
+                    LegOutcome::Skipped
+                };
+
+                match first {
+                    Leg::Polymarket => (first_outcome, second_outcome),
+                    Leg::Btc => (second_outcome, first_outcome),
+                }
+            }
+            ExecutionStrategy::Concurrent => {
+                let (poly, btc) = tokio::join!(
+                    self.run_leg(Leg::Polymarket, opportunity, config),
+                    self.run_leg(Leg::Btc, opportunity, config),
+                );
+
+                self.cancel_orphan(config, poly, btc).await
+            }
+        }
+    }
+
+    /// Execute both sides of arbitrage trade
+    pub async fn execute(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> Result<TradeResult> {
+        config.validate()?;
+
+        tracing::info!(
+            "Executing arbitrage {} ({:?}): {:.2}% profit",
+            config.idempotency_key,
+            self.plan.strategy,
+            opportunity.implied_profit * Decimal::from(100)
+        );
+
+        if config.dry_run {
+            tracing::info!(
+                "Dry run: would buy {} on polymarket {} {} at <= {} and btc {} {} at <= {}",
+                config.size,
+                opportunity.polymarket_odds.market_id,
+                opportunity.polymarket_odds.outcome,
+                config.limit_price(opportunity.polymarket_odds.odds),
+                opportunity.btc_market_odds.market_id,
+                opportunity.btc_market_odds.outcome,
+                config.limit_price(opportunity.btc_market_odds.odds)
+            );
+            return Ok(TradeResult {
+                polymarket_tx: None,
+                btc_tx: None,
+                status: TradeStatus::Pending,
+                executed_at: Utc::now(),
+                error: Some("Dry run: no orders sent".to_string()),
+                position_id: None,
+                token_id: None,
+                fill_price: None,
+                filled_size: None,
+                fees: None,
+            });
+        }
+
+        if self.journaled(&config.idempotency_key) {
+            return Err(anyhow::anyhow!(
+                "Opportunity {} was already submitted; not trading it twice",
+                config.idempotency_key
+            ));
+        }
+
+        if let Some(breakers) = self.breakers.as_ref() {
+            breakers.record_quote(&opportunity.polymarket_odds);
+            breakers.record_quote(&opportunity.btc_market_odds);
+            if let Some(halt) = breakers.kill_switch().state().active {
+                return Ok(not_executed(format!("Trading halted: {}", halt.reason)));
+            }
+        }
+
+        let config = &match self.fund(opportunity, config).await {
+            Ok(funded) => funded,
+            Err(reason) => return Ok(not_executed(reason)),
+        };
+
+        if let Some(risk) = self.risk.as_ref() {
+            if let RiskDecision::Rejected(rejections) = risk.check(opportunity, config, &self.position_manager()) {
+                let reasons: Vec<String> = rejections.iter().map(|r| r.to_string()).collect();
+                return Ok(not_executed(format!("Rejected by risk checks: {}", reasons.join("; "))));
+            }
+        }
+
+        let result = async {
+            if let Some(approvals) = self.approvals.as_ref() {
+                let decision = approvals.request(opportunity, config).await;
+                if decision != ApprovalDecision::Approved {
+                    return Ok(not_executed(format!("Not executed: {}", decision)));
+                }
+                // The halt may have come while the request waited
+                if let Some(reason) = self.halted() {
+                    return Ok(not_executed(format!("Trading halted: {}", reason)));
+                }
+            }
+
+            let (poly_leg, btc_leg) = self.execute_legs(opportunity, config).await;
+            self.settle(opportunity, config, poly_leg, btc_leg).await
+        }
+        .await;
+        // Any position is recorded by now and carries the exposure the check held
+        if let Some(risk) = self.risk.as_ref() {
+            risk.release(&config.idempotency_key);
+        }
+
+        if let (Some(breakers), Some(risk)) = (self.breakers.as_ref(), self.risk.as_ref()) {
+            let pnl = risk.daily_pnl(&self.position_manager());
+            breakers.record_daily_pnl(pnl);
+        }
+        result
+    }
+
+    /// Record positions for the leg outcomes, unwind one-legged exposure and
+    /// mark the execution settled in the journal
+    async fn settle(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+        poly_leg: LegOutcome,
+        btc_leg: LegOutcome,
+    ) -> Result<TradeResult> {
+        let mut status = combined_status(&poly_leg.status(), &btc_leg.status());
+
+        let poly_result = poly_leg.result();
+        let btc_result = btc_leg.result();
+        let mut polymarket_tx = poly_result.and_then(|r| r.polymarket_tx.clone());
+        let mut btc_tx = btc_result.and_then(|r| r.btc_tx.clone());
+        let token_id = btc_result.and_then(|r| r.token_id.clone());
+
+        // Record anything that may have reached a venue, including legs that
+        // timed out without a tx id
+        let position_id = if poly_leg.is_accepted() || btc_leg.is_accepted() {
+            let legs = [
+                (Leg::Polymarket, &poly_leg, &opportunity.polymarket_odds, polymarket_tx.clone(), None),
+                (Leg::Btc, &btc_leg, &opportunity.btc_market_odds, btc_tx.clone(), token_id.clone()),
+            ]
+            .into_iter()
+            .filter(|(_, outcome, ..)| outcome.is_accepted())
+            .map(|(leg, outcome, odds, tx_id, token_id)| {
+                // Without a venue report, assume the full size at the quote
+                let result = outcome.result();
+                let quantity = result.and_then(|r| r.filled_size).unwrap_or(config.size);
+                let price = result.and_then(|r| r.fill_price).unwrap_or(odds.odds);
+                PositionLeg::long(leg, odds, quantity, price)
+                    .with_fees(result.and_then(|r| r.fees).unwrap_or_default())
+                    .with_tx(tx_id)
+                    .with_token_id(token_id)
+                    .with_entry_btc_usd(self.treasury.as_ref().and_then(|t| t.btc_usd(leg)))
+            })
+            .collect::<Vec<PositionLeg>>();
+            let fees: Decimal = legs.iter().map(|leg| leg.fees).sum();
+
+            let mut positions = self.position_manager();
+            let id = positions.open_position(opportunity, legs)?;
+            self.book(LedgerKind::Fee, -fees, &id);
+            if status == TradeStatus::Partial {
+                positions.set_status(&id, PositionStatus::Partial)?;
+                tracing::warn!(
+                    "Position {} is one-legged: polymarket {:?}, btc {:?}",
+                    id,
+                    poly_leg.status(),
+                    btc_leg.status()
+                );
+            }
+            Some(id)
+        } else {
+            None
+        };
+
+        let mut errors: Vec<String> = [(Leg::Polymarket, &poly_leg), (Leg::Btc, &btc_leg)]
+            .iter()
+            .filter(|(_, outcome)| !outcome.is_filled())
+            .filter_map(|(leg, outcome)| outcome.error().map(|e| format!("{}: {}", leg, e)))
+            .collect();
+
+        // One leg filled and the other failed outright: we hold directional
+        // exposure the unwind can hedge at full size
+        let filled_leg = match (poly_leg.status(), btc_leg.status()) {
+            (TradeStatus::Success, TradeStatus::Failed) => Some(Leg::Polymarket),
+            (TradeStatus::Failed, TradeStatus::Success) => Some(Leg::Btc),
+            _ => None,
+        };
+        if let Some(filled_leg) = filled_leg {
+            let open = OpenLeg {
+                filled_leg,
+                venues: self,
+                position_id: position_id.clone(),
+            };
+            let report = match self.halted() {
+                Some(reason) => {
+                    let attempts = vec![format!("not hedged: trading halted: {}", reason)];
+                    self.unwind.escalate(open, opportunity, config, attempts).await
+                }
+                None => self.unwind.unwind(open, opportunity, config).await,
+            };
+
+            if let Some(id) = position_id.as_ref() {
+                let hedge_odds = match filled_leg.other() {
+                    Leg::Polymarket => &opportunity.polymarket_odds,
+                    Leg::Btc => &opportunity.btc_market_odds,
+                };
+                self.position_manager().record_unwind(id, &report, hedge_odds)?;
+                self.book(LedgerKind::UnwindCost, -report.cost, id);
+            }
+            match report.action {
+                UnwindAction::Hedged => {
+                    status = TradeStatus::Success;
+                    match filled_leg.other() {
+                        Leg::Polymarket => polymarket_tx = report.tx_id.clone(),
+                        Leg::Btc => btc_tx = report.tx_id.clone(),
+                    }
+                }
+                UnwindAction::SoldBack => status = TradeStatus::Failed,
+                UnwindAction::Escalated => {}
+            }
+            errors.push(format!("unwind {:?} (cost {}): {}", report.action, report.cost, report.attempts.join("; ")));
+        }
+
+        let settled = JournalEvent::Settled { position_id: position_id.clone() };
+        if let Err(e) = self.journal(&config.idempotency_key, settled) {
+            tracing::error!("Failed to journal settlement of {}: {}", config.idempotency_key, e);
+        }
+
+        Ok(TradeResult {
+            polymarket_tx,
+            btc_tx,
+            status,
+            executed_at: Utc::now(),
+            error: if errors.is_empty() { None } else { Some(errors.join("; ")) },
+            position_id,
+            token_id,
+            fill_price: None,
+            filled_size: None,
+            fees: None,
+        })
+    }
+
+    /// Reconcile journaled executions that never settled, e.g. after a crash.
+    /// Legs without a recorded result are looked up at the venue by client
+    /// order id; a hedge the plan would have sent is sent now; a working order
+    /// whose counterpart failed is cancelled. Positions and unwinds are then
+    /// recorded as `execute` would have.
+    pub async fn recover(&self) -> Result<Vec<TradeResult>> {
+        let unsettled = match &self.journal {
+            Some(journal) => journal.lock().expect("trade journal lock poisoned").unsettled(),
+            None => return Ok(vec![]),
+        };
+
+        let mut recovered = Vec::new();
+        for execution in unsettled {
+            tracing::warn!("Recovering unsettled execution {}", execution.key);
+            let (opportunity, config) = (&execution.opportunity, &execution.config);
+
+            let mut poly_leg = self.recover_leg(Leg::Polymarket, &execution).await;
+            let mut btc_leg = self.recover_leg(Leg::Btc, &execution).await;
+            match (&poly_leg, &btc_leg) {
+                (LegOutcome::Skipped, first) if self.hedges(first) => {
+                    poly_leg = self.run_leg(Leg::Polymarket, opportunity, config).await;
+                }
+                (first, LegOutcome::Skipped) if self.hedges(first) => {
+                    btc_leg = self.run_leg(Leg::Btc, opportunity, config).await;
+                }
+                _ => {}
+            }
+            let (poly_leg, btc_leg) = self.cancel_orphan(config, poly_leg, btc_leg).await;
+
+            recovered.push(self.settle(opportunity, config, poly_leg, btc_leg).await?);
+        }
+        Ok(recovered)
+    }
+
+    /// Journaled outcome of a leg, asking the venue when the result is unknown
+    async fn recover_leg(&self, leg: Leg, execution: &UnsettledExecution) -> LegOutcome {
+        let Some(journaled) = execution.legs.get(&leg) else {
+            return LegOutcome::Skipped;
+        };
+        match &journaled.outcome {
+            Some(LegOutcome::TimedOut) | None => {}
+            Some(outcome) => return outcome.clone(),
+        }
+
+        let outcome = match self.executor(leg).find_order(&journaled.client_order_id).await {
+            Ok(OrderLookup::Found(result)) => LegOutcome::Completed(result),
+            Ok(OrderLookup::NotFound) => LegOutcome::Errored("not found at venue after restart".to_string()),
+            Ok(OrderLookup::Unsupported) => {
+                let alert = Alert::critical(
+                    "Order state unknown",
+                    format!(
+                        "{} cannot look up order {} of {} after restart; check it at the venue",
+                        leg, journaled.client_order_id, execution.key
+                    ),
+                    None,
+                );
+                if let Err(e) = self.unwind.alerts().send(&alert).await {
+                    tracing::error!("Failed to deliver recovery alert: {}", e);
+                }
+                LegOutcome::Unknown(format!("{} cannot look up orders", leg))
+            }
+            Err(e) => {
+                tracing::error!("Failed to look up {} order {}: {}", leg, journaled.client_order_id, e);
+                LegOutcome::TimedOut
+            }
+        };
+
+        let result = JournalEvent::LegResult { leg, outcome: outcome.clone() };
+        if let Err(e) = self.journal(&execution.key, result) {
+            tracing::error!("Failed to journal recovered {} leg of {}: {}", leg, execution.key, e);
+        }
+        outcome
+    }
+
+    /// Represent Liquid outcome tokens held by the wallet as positions
+    pub async fn sync_liquid_positions(&self) -> Result<Vec<String>> {
+        let holdings = self.btc_executor.outcome_holdings().await?;
+        let mut positions = self.position_manager();
+
+        holdings
+            .iter()
+            .map(|holding| {
+                positions.upsert_token_position(
+                    &holding.market_id,
+                    &holding.outcome,
+                    &holding.asset_id,
+                    holding.quantity,
+                )
+            })
+            .collect()
+    }
+
+    /// Close a position at per-leg exit prices and book its realized PnL
+    pub fn close_position(&self, position_id: &str, exits: &[(Leg, Decimal)], btc_usd: Option<Decimal>) -> Result<Position> {
+        let closed = self.position_manager().close_position(position_id, exits, btc_usd)?;
+        // Fees and unwind costs were booked when they were paid
+        let fees: Decimal = closed.legs.iter().map(|leg| leg.fees).sum();
+        let gross = closed.pnl.unwrap_or_default() + fees + closed.unwind_cost.unwrap_or_default();
+        self.book(LedgerKind::Realized, gross, position_id);
+        Ok(closed)
+    }
+
+    /// Book an amount in the risk engine's PnL ledger, if it keeps one
+    fn book(&self, kind: LedgerKind, amount: Decimal, position_id: &str) {
+        if let Some(ledger) = self.risk.as_ref().and_then(|risk| risk.ledger()) {
+            if let Err(e) = ledger.record(kind, amount, Some(position_id)) {
+                tracing::error!("Failed to book {:?} of {} for {}: {}", kind, amount, position_id, e);
+            }
+        }
+    }
+
+    /// Lock the position manager. Do not hold the guard across an await.
+    pub fn position_manager(&self) -> std::sync::MutexGuard<'_, PositionManager> {
+        self.position_manager.lock().expect("position manager lock poisoned")
+    }
+
+    /// Get mutable position manager reference
+    pub fn position_manager_mut(&mut self) -> &mut PositionManager {
+        self.position_manager.get_mut().expect("position manager lock poisoned")
+    }
+}
+
+#[async_trait]
+impl UnwindVenues for ArbitrageExecutor {
+    async fn hedge(
+        &self,
+        execution_key: &str,
+        leg: Leg,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> LegOutcome {
+        self.send_leg(execution_key, leg, opportunity, config).await
+    }
+
+    async fn sell_back(
+        &self,
+        leg: Leg,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+        min_price: Decimal,
+    ) -> LegOutcome {
+        if let Some(reason) = self.halted() {
+            tracing::warn!("Not selling back {} leg of {}: trading halted", leg, config.idempotency_key);
+            return LegOutcome::Errored(format!("trading halted: {}", reason));
+        }
+        let odds = match leg {
+            Leg::Polymarket => &opportunity.polymarket_odds,
+            Leg::Btc => &opportunity.btc_market_odds,
+        };
+
+        // Not counted by the breakers: most live venues cannot sell back
+        let execution = self.executor(leg).sell_back(odds, config.size, min_price);
+        match tokio::time::timeout(config.leg_timeout(leg), execution).await {
+            Ok(Ok(result)) => LegOutcome::Completed(result),
+            Ok(Err(e)) => LegOutcome::Errored(e.to_string()),
+            Err(_) => LegOutcome::TimedOut,
+        }
+    }
+}
+
+impl Default for ArbitrageExecutor {
+    fn default() -> Self {
+        Self::new(
+            crate::config::PolymarketConfig::default(),
+            crate::config::BitcoinConfig::default(),
+        )
+    }
+}
+
+/// Result for a trade turned down before any order was sent
+fn not_executed(error: String) -> TradeResult {
+    TradeResult {
+        polymarket_tx: None,
+        btc_tx: None,
+        status: TradeStatus::Failed,
+        executed_at: Utc::now(),
+        error: Some(error),
+        position_id: None,
+        token_id: None,
+        fill_price: None,
+        filled_size: None,
+        fees: None,
+    }
+}
+
+/// Overall status of a two-leg execution. `Partial` means we may hold
+/// unhedged exposure; `Pending` that nothing failed but an order is still working.
+fn combined_status(poly: &TradeStatus, btc: &TradeStatus) -> TradeStatus {
+    use TradeStatus::{Failed, Partial, Pending, Success};
+    match (poly, btc) {
+        (Success, Success) => Success,
+        (Failed, Failed) => Failed,
+        // Filled, partly filled or still working with nothing on the other side;
+        // a working order is an orphan that may yet fill unhedged
+        (Success | Partial | Pending, Failed) | (Failed, Success | Partial | Pending) => Partial,
+        // Both legs hold shares, but not the same amount
+        (Partial, Success | Partial) | (Success, Partial) => Partial,
+        (Pending, Success | Partial | Pending) | (Success | Partial, Pending) => Pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::{Alert, AlertSink};
+    use crate::models::MarketSource;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Scripted leg: returns `status` after `delay`, or errors
+    struct MockExecutor {
+        leg: Leg,
+        status: Option<TradeStatus>,
+        delay: Duration,
+        cancellable: bool,
+        /// Orders only fill at or above this limit
+        fills_at: Option<Decimal>,
+        sell_back_price: Option<Decimal>,
+        /// What `find_order` reports; unsupported when `None`
+        lookup: Option<TradeStatus>,
+        balance: Decimal,
+        calls: Arc<AtomicUsize>,
+        cancels: Arc<AtomicUsize>,
+    }
+
+    impl MockExecutor {
+        fn new(leg: Leg, status: Option<TradeStatus>) -> Self {
+            Self {
+                leg,
+                status,
+                delay: Duration::ZERO,
+                cancellable: false,
+                fills_at: None,
+                sell_back_price: None,
+                lookup: None,
+                balance: Decimal::ZERO,
+                calls: Arc::new(AtomicUsize::new(0)),
+                cancels: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TradeExecutor for MockExecutor {
+        async fn execute_arbitrage(
+            &self,
+            opportunity: &ArbitrageOpportunity,
+            _config: &TradeConfig,
+        ) -> Result<TradeResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+
+            let mut status = self.status.clone().ok_or_else(|| anyhow::anyhow!("venue rejected order"))?;
+            let limit = match self.leg {
+                Leg::Polymarket => opportunity.polymarket_odds.odds,
+                Leg::Btc => opportunity.btc_market_odds.odds,
+            };
+            if self.fills_at.is_some_and(|price| limit < price) {
+                status = TradeStatus::Failed;
+            }
+            let tx = Some(format!("{}-tx", self.leg));
+            Ok(TradeResult {
+                polymarket_tx: if self.leg == Leg::Polymarket { tx.clone() } else { None },
+                btc_tx: if self.leg == Leg::Btc { tx } else { None },
+                status,
+                executed_at: Utc::now(),
+                error: None,
+                position_id: None,
+                token_id: None,
+                fill_price: None,
+                filled_size: None,
+                fees: None,
+            })
+        }
+
+        async fn approve_token(&self, _token: &str, _amount: Decimal) -> Result<String> {
+            Ok("N/A".to_string())
+        }
+
+        async fn get_balance(&self) -> Result<Decimal> {
+            Ok(self.balance)
+        }
+
+        async fn cancel_order(&self, _tx_id: &str) -> Result<bool> {
+            self.cancels.fetch_add(1, Ordering::SeqCst);
+            Ok(self.cancellable)
+        }
+
+        async fn find_order(&self, _client_order_id: &str) -> Result<OrderLookup> {
+            Ok(match &self.lookup {
+                Some(status) => OrderLookup::Found(TradeResult {
+                    polymarket_tx: None,
+                    btc_tx: None,
+                    status: status.clone(),
+                    executed_at: Utc::now(),
+                    error: None,
+                    position_id: None,
+                    token_id: None,
+                    fill_price: None,
+                    filled_size: None,
+                    fees: None,
+                }),
+                None => OrderLookup::Unsupported,
+            })
+        }
+
+        async fn sell_back(&self, _odds: &MarketOdds, _size: Decimal, min_price: Decimal) -> Result<TradeResult> {
+            let price = self
+                .sell_back_price
+                .filter(|price| *price >= min_price)
+                .ok_or_else(|| anyhow::anyhow!("no bid above {}", min_price))?;
+            Ok(TradeResult {
+                polymarket_tx: None,
+                btc_tx: Some("btc-sell-tx".to_string()),
+                status: TradeStatus::Success,
+                executed_at: Utc::now(),
+                error: None,
+                position_id: None,
+                token_id: None,
+                fill_price: Some(price),
+                filled_size: None,
+                fees: None,
+            })
+        }
+    }
+
+    struct CountingAlerts(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl AlertSink for CountingAlerts {
+        async fn send(&self, _alert: &Alert) -> Result<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn unwind_engine(alerts: Arc<AtomicUsize>) -> UnwindEngine {
+        UnwindEngine::new(
+            UnwindPolicy {
+                max_loss: Decimal::from(5),
+                price_step: Decimal::new(1, 2),
+                max_attempts: 3,
+            },
+            Box::new(CountingAlerts(alerts)),
+        )
+    }
+
+    fn opportunity() -> ArbitrageOpportunity {
+        let odds = |source| MarketOdds {
+            market_id: "m1".to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(45, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        ArbitrageOpportunity::new(
+            odds(MarketSource::Polymarket),
+            odds(MarketSource::BitcoinPredictionMarket),
+            Decimal::new(5, 2),
+            0.9,
+        )
+    }
+
+    fn trade_config() -> TradeConfig {
+        TradeConfig {
+            size: Decimal::from(100),
+            max_size: Decimal::from(1000),
+            limit_tolerance: Decimal::ZERO,
+            time_in_force: TimeInForce::ImmediateOrCancel,
+            polymarket_timeout: Duration::from_millis(200),
+            btc_timeout: Duration::from_millis(200),
+            dry_run: false,
+            idempotency_key: "test".to_string(),
+        }
+    }
+
+    fn plan(strategy: ExecutionStrategy) -> ExecutionPlan {
+        ExecutionPlan {
+            strategy,
+            thin_leg: Leg::Btc,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_thin_side_first_skips_hedge_when_thin_leg_fails() {
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let btc = MockExecutor::new(Leg::Btc, None);
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert_eq!(result.status, TradeStatus::Failed);
+        assert_eq!(poly_calls.load(Ordering::SeqCst), 0);
+        assert!(result.position_id.is_none());
+        assert!(result.error.unwrap().contains("venue rejected order"));
+    }
+
+    #[tokio::test]
+    async fn test_hedge_failure_records_partial_position() {
+        let poly = MockExecutor::new(Leg::Polymarket, None);
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert_eq!(result.status, TradeStatus::Partial);
+        let position = executor
+            .position_manager()
+            .get_position(result.position_id.as_ref().unwrap())
+            .unwrap();
+        assert_eq!(position.status, PositionStatus::Partial);
+        assert_eq!(position.leg(Leg::Btc).unwrap().tx_ids, vec!["btc-tx".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_hedge_after_fill_waits_for_fill() {
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Pending));
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::HedgeAfterFill));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        // The unfilled BTC leg is recorded but never hedged
+        assert_eq!(poly_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(result.status, TradeStatus::Partial);
+        assert!(result.position_id.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_leg_timeout_is_recorded() {
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let mut btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        btc.delay = Duration::from_secs(5);
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert_eq!(result.status, TradeStatus::Pending);
+        assert!(result.position_id.is_some());
+        assert!(result.error.unwrap().contains("btc: timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_cancels_survivor_on_fail() {
+        let mut poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Pending));
+        poly.cancellable = true;
+        let poly_cancels = poly.cancels.clone();
+        let btc = MockExecutor::new(Leg::Btc, None);
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::Concurrent));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert_eq!(poly_cancels.load(Ordering::SeqCst), 1);
+        assert_eq!(result.status, TradeStatus::Failed);
+        assert!(result.position_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unwind_hedges_at_worse_limit() {
+        let mut poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        poly.fills_at = Some(Decimal::new(47, 2));
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let alerts = Arc::new(AtomicUsize::new(0));
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_unwind(unwind_engine(alerts.clone()));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert_eq!(result.status, TradeStatus::Success);
+        assert_eq!(result.polymarket_tx.as_deref(), Some("polymarket-tx"));
+        let position = executor
+            .position_manager()
+            .get_position(result.position_id.as_ref().unwrap())
+            .unwrap();
+        assert_eq!(position.status, PositionStatus::Open);
+        // Two cents worse on 100 shares
+        assert_eq!(position.unwind_cost, Some(Decimal::from(2)));
+        assert_eq!(alerts.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unwind_sells_back_when_hedge_exceeds_budget() {
+        let mut poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        poly.fills_at = Some(Decimal::new(60, 2));
+        let mut btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        btc.sell_back_price = Some(Decimal::new(42, 2));
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_unwind(unwind_engine(Arc::new(AtomicUsize::new(0))));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert_eq!(result.status, TradeStatus::Failed);
+        let position = executor
+            .position_manager()
+            .get_position(result.position_id.as_ref().unwrap())
+            .unwrap();
+        assert_eq!(position.status, PositionStatus::Closed);
+        assert_eq!(position.unwind_cost, Some(Decimal::from(3)));
+        assert_eq!(position.pnl, Some(Decimal::from(-3)));
+        assert!(executor.position_manager().get_open_positions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unwind_escalates_when_hedge_exceeds_budget() {
+        // The hedge only fills 15 cents worse, $15 over a $5 budget
+        let mut poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        poly.fills_at = Some(Decimal::new(60, 2));
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let alerts = Arc::new(AtomicUsize::new(0));
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_unwind(unwind_engine(alerts.clone()));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert_eq!(result.status, TradeStatus::Partial);
+        // The first hedge and three retries within budget
+        assert_eq!(poly_calls.load(Ordering::SeqCst), 4);
+        assert_eq!(alerts.load(Ordering::SeqCst), 1);
+        let position = executor
+            .position_manager()
+            .get_position(result.position_id.as_ref().unwrap())
+            .unwrap();
+        assert_eq!(position.status, PositionStatus::Partial);
+        assert_eq!(position.unwind_cost, None);
+    }
+
+    #[tokio::test]
+    async fn test_unwind_escalates_when_nothing_works() {
+        let poly = MockExecutor::new(Leg::Polymarket, None);
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let alerts = Arc::new(AtomicUsize::new(0));
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_unwind(unwind_engine(alerts.clone()));
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert_eq!(result.status, TradeStatus::Partial);
+        assert_eq!(alerts.load(Ordering::SeqCst), 1);
+        assert!(result.error.unwrap().contains("unwind Escalated"));
+    }
+
+    #[test]
+    fn test_trade_config_from_config_and_overrides() {
+        let mut config = Config::default();
+        config.general.max_position_size = Decimal::from(500);
+        config.general.max_slippage = Decimal::new(2, 2);
+        config.trading.time_in_force = "fok".to_string();
+
+        let opportunity = opportunity();
+        let trade = TradeConfig::from_config(&config, &opportunity, &TradeOverrides::default()).unwrap();
+        assert_eq!(trade.size, Decimal::from(500));
+        assert_eq!(trade.time_in_force, TimeInForce::FillOrKill);
+        assert_eq!(trade.limit_price(Decimal::new(50, 2)), Decimal::new(51, 2));
+        assert_eq!(trade.leg_timeout(Leg::Btc), Duration::from_secs(config.trading.btc_leg_timeout_secs));
+
+        // Same opportunity, same key
+        let again = TradeConfig::from_config(&config, &opportunity, &TradeOverrides::default()).unwrap();
+        assert_eq!(trade.idempotency_key, again.idempotency_key);
+
+        let overrides = TradeOverrides {
+            size: Some(Decimal::from(50)),
+            dry_run: Some(true),
+            ..Default::default()
+        };
+        let trade = TradeConfig::from_config(&config, &opportunity, &overrides).unwrap();
+        assert_eq!(trade.size, Decimal::from(50));
+        assert!(trade.dry_run);
+    }
+
+    #[test]
+    fn test_redetected_opportunity_keeps_its_key() {
+        use chrono::TimeZone;
+
+        let mut first = opportunity();
+        first.detected_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 5).unwrap();
+        let mut again = opportunity();
+        again.detected_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 4, 30).unwrap();
+        again.polymarket_odds.odds = Decimal::new(44, 2);
+        assert_eq!(idempotency_key(&first, 300), idempotency_key(&again, 300));
+
+        let mut later = opportunity();
+        later.detected_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 5, 0).unwrap();
+        assert_ne!(idempotency_key(&first, 300), idempotency_key(&later, 300));
+    }
+
+    #[test]
+    fn test_trade_config_validation() {
+        let config = Config::default();
+        let too_big = TradeOverrides {
+            size: Some(config.general.max_position_size + Decimal::ONE),
+            ..Default::default()
+        };
+        assert!(TradeConfig::from_config(&config, &opportunity(), &too_big).is_err());
+
+        let mut trade = trade_config();
+        trade.limit_tolerance = Decimal::ONE;
+        assert!(trade.validate().is_err());
+
+        let mut trade = trade_config();
+        trade.btc_timeout = Duration::ZERO;
+        assert!(trade.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_sends_nothing() {
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let btc_calls = btc.calls.clone();
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc));
+        let mut config = trade_config();
+        config.dry_run = true;
+        let result = executor.execute(&opportunity(), &config).await.unwrap();
+
+        assert_eq!(poly_calls.load(Ordering::SeqCst) + btc_calls.load(Ordering::SeqCst), 0);
+        assert!(result.position_id.is_none());
+    }
+
+    #[test]
+    fn test_plan_from_config() {
+        let mut config = TradingConfig {
+            execution_strategy: "hedge_after_fill".to_string(),
+            thin_leg: "polymarket".to_string(),
+            ..Default::default()
+        };
+
+        let plan = ExecutionPlan::from_config(&config).unwrap();
+        assert_eq!(plan.strategy, ExecutionStrategy::HedgeAfterFill);
+        assert_eq!(plan.thin_leg, Leg::Polymarket);
+
+        config.execution_strategy = "yolo".to_string();
+        assert!(ExecutionPlan::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_liquid_leg_pays_collateral_not_shares() {
+        use crate::chains::liquid::LBTC_ASSET_ID;
+        use wiremock::matchers::{body_partial_json, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let respond = |result: serde_json::Value| {
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "result": result, "error": null, "id": "polyarb" }))
+        };
+        // 100 shares at 0.45 is $45, or 0.00075 L-BTC at $60,000
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "method": "createrawtransaction",
+                "params": [[], [{ "lq1qqsettlement": 0.00075, "asset": LBTC_ASSET_ID }]]
+            })))
+            .respond_with(respond(serde_json::json!("raw")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        for (rpc, result) in [
+            ("getaddressinfo", serde_json::json!({ "confidential_key": "02ab" })),
+            ("fundrawtransaction", serde_json::json!({ "hex": "funded", "fee": 0.0000025 })),
+            ("blindrawtransaction", serde_json::json!("blinded")),
+            ("signrawtransactionwithwallet", serde_json::json!({ "hex": "signed", "complete": true })),
+            ("sendrawtransaction", serde_json::json!("liquidtxid")),
+            ("gettransaction", serde_json::json!({ "confirmations": 1 })),
+        ] {
+            Mock::given(method("POST"))
+                .and(body_partial_json(serde_json::json!({ "method": rpc })))
+                .respond_with(respond(result))
+                .mount(&server)
+                .await;
+        }
+
+        let mut config = Config::default();
+        config.bitcoin.protocol = "liquid".to_string();
+        config.bitcoin.liquid = Some(crate::config::LiquidConfig {
+            rpc_url: Some(server.uri()),
+            private_key: None,
+            liquid_rpc_user: None,
+            liquid_rpc_password: None,
+            liquid_wallet: None,
+            settlement_address: Some("lq1qqsettlement".to_string()),
+            collateral_asset: Some(LBTC_ASSET_ID.to_string()),
+            outcome_assets: vec![],
+            min_confirmations: 1,
+        });
+        let executor = BtcTradeExecutor::new(config.bitcoin.clone()).with_prices(config.treasury.clone());
+
+        let result = executor.execute_arbitrage(&opportunity(), &trade_config()).await.unwrap();
+        assert_eq!(result.btc_tx.as_deref(), Some("liquidtxid"));
+        assert_eq!(result.status, TradeStatus::Success);
+    }
+
+    #[test]
+    fn test_combined_status() {
+        use TradeStatus::{Failed, Partial, Pending, Success};
+        let cases = [
+            (Success, Success, Success),
+            (Failed, Failed, Failed),
+            (Success, Failed, Partial),
+            // The working order may still fill with nothing to hedge it
+            (Pending, Failed, Partial),
+            (Failed, Pending, Partial),
+            // Both legs hold shares in different amounts
+            (Partial, Success, Partial),
+            (Success, Partial, Partial),
+            (Success, Pending, Pending),
+            (Partial, Pending, Pending),
+        ];
+        for (poly, btc, expected) in cases {
+            assert_eq!(combined_status(&poly, &btc), expected, "{:?} + {:?}", poly, btc);
+        }
+    }
+
+    #[test]
+    fn test_stacks_spend_matches_treasury_reservation() {
+        let mut config = Config::default();
+        config.bitcoin.protocol = "stacks".to_string();
+        config.treasury.stx_usd_price = Decimal::new(150, 2);
+        let executor = BtcTradeExecutor::new(config.bitcoin.clone()).with_prices(config.treasury.clone());
+
+        // 100 shares at 0.45 is $45, or 30 STX at $1.50
+        let stx = executor.collateral_amount(&trade_config(), Decimal::new(45, 2)).unwrap();
+        assert_eq!(stx, Decimal::from(30));
+        let reserved = Treasury::from_config(&config).leg_cost(Leg::Btc, &opportunity(), &trade_config(), Decimal::from(100));
+        assert_eq!(reserved, Some(stx));
+    }
+
+    #[tokio::test]
+    async fn test_rsk_balance_is_collateral_token() {
+        use wiremock::matchers::{body_partial_json, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        for (rpc, result) in [
+            // 2.5 tokens at 18 decimals; the 0.01 RBTC gas balance must not be reported
+            ("eth_call", format!("0x{:064x}", 2_500_000_000_000_000_000u128)),
+            ("eth_getBalance", "0x2386f26fc10000".to_string()),
+        ] {
+            Mock::given(method("POST"))
+                .and(body_partial_json(serde_json::json!({ "method": rpc })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0", "id": 1, "result": result
+                })))
+                .mount(&server)
+                .await;
+        }
+
+        let mut config = Config::default();
+        config.bitcoin.protocol = "rsk".to_string();
+        config.bitcoin.rsk = Some(crate::config::RskConfig {
+            rpc_url: Some(server.uri()),
+            private_key: Some("46".repeat(32)),
+            rsk_network: "testnet".to_string(),
+            market_contract: Some("0x0000000000000000000000000000000000000abc".to_string()),
+            collateral_token: Some("0x0000000000000000000000000000000000000def".to_string()),
+            collateral_decimals: 18,
+            market_function: "buyOutcome(string,string,uint256,uint256)".to_string(),
+        });
+        let executor = BtcTradeExecutor::new(config.bitcoin.clone()).with_prices(config.treasury.clone());
+        assert_eq!(executor.get_balance().await.unwrap(), Decimal::new(25, 1));
+
+        // Priced as the collateral, not as BTC
+        let treasury = Treasury::from_config(&config);
+        assert_eq!(treasury.asset(Leg::Btc), Asset::RskCollateral);
+        let cost = treasury.leg_cost(Leg::Btc, &opportunity(), &trade_config(), Decimal::from(100));
+        assert_eq!(cost, Some(executor.collateral_amount(&trade_config(), Decimal::new(45, 2)).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_trade_sends_nothing() {
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let btc_calls = btc.calls.clone();
+        let queue = Arc::new(ApprovalQueue::new(Duration::from_secs(5), Decimal::ZERO));
+        let executor =
+            ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc)).with_approvals(queue.clone());
+
+        let mut requests = queue.subscribe();
+        tokio::spawn(async move {
+            let request = requests.recv().await.unwrap();
+            queue.decide(&request.id, ApprovalDecision::Rejected("too thin".to_string())).unwrap();
+        });
+
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+        assert_eq!(result.status, TradeStatus::Failed);
+        assert_eq!(result.error.as_deref(), Some("Not executed: rejected: too thin"));
+        assert_eq!(poly_calls.load(Ordering::SeqCst) + btc_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_halt_during_approval_sends_nothing() {
+        let path = std::env::temp_dir().join(format!("halt-{}.json", uuid::Uuid::new_v4()));
+        let switch = Arc::new(KillSwitch::open(&path).unwrap());
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let btc_calls = btc.calls.clone();
+        let queue = Arc::new(ApprovalQueue::new(Duration::from_secs(5), Decimal::ZERO));
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_approvals(queue.clone())
+            .with_breakers(CircuitBreakers::new(switch.clone(), crate::config::RiskConfig::default()));
+
+        // The operator approves just after the kill switch trips
+        let mut requests = queue.subscribe();
+        tokio::spawn(async move {
+            let request = requests.recv().await.unwrap();
+            switch.halt(crate::halt::HaltSource::Admin, "venue outage").unwrap();
+            queue.decide(&request.id, ApprovalDecision::Approved).unwrap();
+        });
+
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+        assert_eq!(result.error.as_deref(), Some("Trading halted: venue outage"));
+        assert_eq!(poly_calls.load(Ordering::SeqCst) + btc_calls.load(Ordering::SeqCst), 0);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_halted_executor_sends_nothing_after_restart() {
+        let path = std::env::temp_dir().join(format!("halt-{}.json", uuid::Uuid::new_v4()));
+        KillSwitch::open(&path)
+            .unwrap()
+            .halt(crate::halt::HaltSource::Admin, "maintenance")
+            .unwrap();
+
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let btc_calls = btc.calls.clone();
+        let breakers = CircuitBreakers::new(
+            Arc::new(KillSwitch::open(&path).unwrap()),
+            crate::config::RiskConfig::default(),
+        );
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc)).with_breakers(breakers);
+
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+        assert_eq!(result.status, TradeStatus::Failed);
+        assert_eq!(result.error.as_deref(), Some("Trading halted: maintenance"));
+        assert_eq!(poly_calls.load(Ordering::SeqCst) + btc_calls.load(Ordering::SeqCst), 0);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_treasury_caps_size_and_refuses_unfunded_trades() {
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let btc_calls = btc.calls.clone();
+        let treasury = Treasury::new(crate::treasury::Asset::Usdc, crate::treasury::Asset::Usdc);
+        treasury.set_balance(Leg::Polymarket, Decimal::from(1000));
+        treasury.set_balance(Leg::Btc, Decimal::from(9));
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_treasury(treasury);
+
+        // $9 buys 20 shares at 0.45
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+        assert_eq!(result.status, TradeStatus::Success);
+        assert_eq!(executor.position_manager().get_all_positions()[0].quantity(), Decimal::from(20));
+        let treasury = executor.treasury().unwrap();
+        assert_eq!(treasury.funds(Leg::Btc).free, Decimal::ZERO);
+        assert_eq!(treasury.funds(Leg::Polymarket).free, Decimal::from(991));
+
+        let mut config = trade_config();
+        config.idempotency_key = "second".to_string();
+        let result = executor.execute(&opportunity(), &config).await.unwrap();
+        assert_eq!(result.status, TradeStatus::Failed);
+        assert!(result.error.unwrap().starts_with("Insufficient funds"));
+        assert_eq!(btc_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_held_funds_of_pending_legs_come_back() {
+        // Polymarket can't look its order up; the BTC venue reports a fill
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Pending));
+        let mut btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Pending));
+        btc.lookup = Some(TradeStatus::Success);
+        let treasury = Treasury::new(crate::treasury::Asset::Usdc, crate::treasury::Asset::Usdc).with_prices(&TreasuryConfig {
+            pending_reservation_ttl_secs: 0,
+            ..TreasuryConfig::default()
+        });
+        treasury.set_balance(Leg::Polymarket, Decimal::from(1000));
+        treasury.set_balance(Leg::Btc, Decimal::from(1000));
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_treasury(treasury);
+
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+        assert_eq!(result.status, TradeStatus::Pending);
+        let treasury = executor.treasury().unwrap();
+        assert_eq!(treasury.funds(Leg::Polymarket).reserved, Decimal::from(45));
+        assert_eq!(treasury.funds(Leg::Btc).reserved, Decimal::from(45));
+
+        executor.refresh_treasury().await;
+        let (poly, btc) = (treasury.funds(Leg::Polymarket), treasury.funds(Leg::Btc));
+        assert_eq!((poly.reserved, poly.free), (Decimal::ZERO, Decimal::from(1000)));
+        assert_eq!((btc.reserved, btc.free), (Decimal::ZERO, Decimal::from(955)));
+    }
+
+    struct SpotRate(Arc<std::sync::Mutex<Decimal>>);
+
+    #[async_trait]
+    impl crate::treasury::BtcUsdSource for SpotRate {
+        async fn btc_usd(&self) -> Result<Decimal> {
+            Ok(*self.0.lock().unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_legs_record_live_btc_usd_at_entry_and_exit() {
+        let rate = Arc::new(std::sync::Mutex::new(Decimal::from(50_000)));
+        let mut poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        poly.balance = Decimal::from(1000);
+        let mut btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        btc.balance = Decimal::ONE;
+        // Balances and the rate are re-fetched on every use
+        let treasury = Treasury::new(crate::treasury::Asset::Usdc, crate::treasury::Asset::Btc)
+            .with_prices(&TreasuryConfig { max_balance_age_secs: 0, ..TreasuryConfig::default() })
+            .with_rate_source(Box::new(SpotRate(rate.clone())));
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_treasury(treasury);
+
+        let result = executor.execute(&opportunity(), &trade_config()).await.unwrap();
+        let id = result.position_id.unwrap();
+        *rate.lock().unwrap() = Decimal::from(55_000);
+        let btc_usd = executor.btc_usd(Leg::Btc).await;
+        let closed = executor
+            .close_position(&id, &[(Leg::Polymarket, Decimal::ZERO), (Leg::Btc, Decimal::ONE)], btc_usd)
+            .unwrap();
+
+        let leg = closed.leg(Leg::Btc).unwrap();
+        assert_eq!((leg.entry_btc_usd, leg.exit_btc_usd), (Some(Decimal::from(50_000)), Some(Decimal::from(55_000))));
+        // 100 shares paid out $1 each, worth 10% more in BTC terms
+        assert_eq!(leg.fx_pnl(Decimal::ONE, Decimal::from(55_000)), Decimal::from(10));
+    }
+
+    #[tokio::test]
+    async fn test_paper_mode_never_builds_live_executors() {
+        let dir = std::env::temp_dir().join(format!("paper-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = format!(
+            r#"
+[bitcoin]
+protocol = "ordinals"
+
+[bitcoin.ordinals]
+endpoint = "http://127.0.0.1:1"
+parent_inscription = "abc"
+
+[trading]
+paper_trading = true
+paper_starting_balance = 1234
+journal_path = "{dir}/journal.jsonl"
+positions_db_path = "{dir}/positions.db"
+halt_state_path = "{dir}/halt.json"
+
+[risk]
+pnl_ledger_path = "{dir}/pnl.jsonl"
+"#,
+            dir = dir.display()
+        );
+        let config = Config::layered(Some(&file), vec![]).unwrap();
+        let executor = ArbitrageExecutor::from_config(&config).unwrap();
+
+        // Paper legs answer from their virtual accounts; live ones would go to the venues
+        for leg in [Leg::Polymarket, Leg::Btc] {
+            assert_eq!(executor.executor(leg).get_balance().await.unwrap(), Decimal::from(1234));
+            assert!(matches!(executor.executor(leg).find_order("x").await.unwrap(), OrderLookup::NotFound));
+        }
+
+        let mut config = config;
+        config.bitcoin.protocol = "stacks".to_string();
+        assert!(ArbitrageExecutor::from_config(&config).is_err());
+    }
+
+    fn journal_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("trade-journal-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_journaled_opportunity_is_not_traded_twice() {
+        let path = journal_path();
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let btc_calls = btc.calls.clone();
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_journal(TradeJournal::open(&path).unwrap());
+        executor.execute(&opportunity(), &trade_config()).await.unwrap();
+
+        assert!(executor.execute(&opportunity(), &trade_config()).await.is_err());
+        assert_eq!(btc_calls.load(Ordering::SeqCst), 1);
+        assert!(TradeJournal::open(&path).unwrap().unsettled().is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unwind_retries_are_journaled_with_their_execution() {
+        let path = journal_path();
+        let mut poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        poly.fills_at = Some(Decimal::new(46, 2));
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_unwind(unwind_engine(Arc::new(AtomicUsize::new(0))))
+            .with_journal(TradeJournal::open(&path).unwrap());
+        let config = trade_config();
+        let result = executor.execute(&opportunity(), &config).await.unwrap();
+        assert_eq!(result.status, TradeStatus::Success);
+
+        let entries = executor.journal.as_ref().unwrap().lock().unwrap().entries().unwrap();
+        assert!(entries.iter().all(|entry| entry.key == config.idempotency_key));
+        let order_ids: Vec<String> = entries
+            .iter()
+            .filter_map(|entry| match &entry.event {
+                JournalEvent::Intent { client_order_id, .. } => Some(client_order_id.clone()),
+                _ => None,
+            })
+            .collect();
+        let key = &config.idempotency_key;
+        assert_eq!(
+            order_ids,
+            vec![format!("{}-btc", key), format!("{}-polymarket", key), format!("{}-unwind1-polymarket", key)]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recover_sends_hedge_after_crash() {
+        let path = journal_path();
+        let config = trade_config();
+        let filled = LegOutcome::Completed(TradeResult {
+            polymarket_tx: None,
+            btc_tx: Some("btc-tx".to_string()),
+            status: TradeStatus::Success,
+            executed_at: Utc::now(),
+            error: None,
+            position_id: None,
+            token_id: None,
+            fill_price: None,
+            filled_size: None,
+            fees: None,
+        });
+
+        // The thin leg filled, then the process died before the hedge was sent
+        let mut journal = TradeJournal::open(&path).unwrap();
+        let intent = JournalEvent::Intent {
+            leg: Leg::Btc,
+            client_order_id: config.client_order_id(Leg::Btc),
+            opportunity: Box::new(opportunity()),
+            config: config.clone(),
+        };
+        journal.append(&config.idempotency_key, intent).unwrap();
+        journal
+            .append(&config.idempotency_key, JournalEvent::LegResult { leg: Leg::Btc, outcome: filled })
+            .unwrap();
+
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let btc_calls = btc.calls.clone();
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_journal(journal);
+
+        let recovered = executor.recover().await.unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].status, TradeStatus::Success);
+        assert_eq!(recovered[0].btc_tx.as_deref(), Some("btc-tx"));
+        assert_eq!(poly_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(btc_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(executor.position_manager().get_open_positions().len(), 1);
+
+        // Nothing is left to recover on the next start
+        assert!(executor.recover().await.unwrap().is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recover_escalates_order_venue_cannot_look_up() {
+        let path = journal_path();
+        let config = trade_config();
+
+        // The process died after sending the first leg, before the venue answered
+        let mut journal = TradeJournal::open(&path).unwrap();
+        let intent = JournalEvent::Intent {
+            leg: Leg::Polymarket,
+            client_order_id: config.client_order_id(Leg::Polymarket),
+            opportunity: Box::new(opportunity()),
+            config: config.clone(),
+        };
+        journal.append(&config.idempotency_key, intent).unwrap();
+
+        let poly = MockExecutor::new(Leg::Polymarket, Some(TradeStatus::Success));
+        let poly_calls = poly.calls.clone();
+        let btc = MockExecutor::new(Leg::Btc, Some(TradeStatus::Success));
+        let btc_calls = btc.calls.clone();
+        let alerts = Arc::new(AtomicUsize::new(0));
+        let executor = ArbitrageExecutor::with_executors(Box::new(poly), Box::new(btc))
+            .with_plan(plan(ExecutionStrategy::ThinSideFirst))
+            .with_unwind(unwind_engine(alerts.clone()))
+            .with_journal(journal);
+
+        let recovered = executor.recover().await.unwrap();
+        assert_eq!(recovered[0].status, TradeStatus::Partial);
+        assert!(recovered[0].error.as_deref().unwrap().contains("order state unknown"));
+        // Neither resent nor hedged: the first order may or may not have filled
+        assert_eq!(poly_calls.load(Ordering::SeqCst) + btc_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(alerts.load(Ordering::SeqCst), 1);
+        std::fs::remove_file(path).unwrap();
     }
 }
@@ -0,0 +1,461 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::config::{Config, TreasuryConfig};
+use crate::models::ArbitrageOpportunity;
+use crate::trader::{Leg, TradeConfig, TradeExecutor};
+
+/// Asset a venue's collateral is held in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Asset {
+    /// USDC on Polygon
+    Usdc,
+    /// Sats in Lightning channels
+    Sats,
+    /// On-chain BTC
+    Btc,
+    Rbtc,
+    /// ERC-20 collateral of the RSK market contract (e.g. DOC)
+    RskCollateral,
+    Lbtc,
+    Stx,
+}
+
+impl Asset {
+    /// Collateral of the configured Bitcoin-side protocol
+    pub fn for_protocol(protocol: &str) -> Self {
+        match protocol {
+            "lightning" => Asset::Sats,
+            "rsk" => Asset::RskCollateral,
+            "liquid" => Asset::Lbtc,
+            "stacks" => Asset::Stx,
+            _ => Asset::Btc,
+        }
+    }
+}
+
+impl Asset {
+    /// USD value of one unit at the configured rates
+    pub fn usd_price(self, config: &TreasuryConfig) -> Decimal {
+        match self {
+            Asset::Usdc => Decimal::ONE,
+            Asset::Sats => config.btc_usd_price / Decimal::from(100_000_000),
+            Asset::Btc | Asset::Rbtc | Asset::Lbtc => config.btc_usd_price,
+            Asset::RskCollateral => config.rsk_collateral_usd_price,
+            Asset::Stx => config.stx_usd_price,
+        }
+    }
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Asset::Usdc => "USDC",
+            Asset::Sats => "sats",
+            Asset::Btc => "BTC",
+            Asset::Rbtc => "RBTC",
+            Asset::RskCollateral => "RSK collateral",
+            Asset::Lbtc => "L-BTC",
+            Asset::Stx => "STX",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Where the live BTC/USD rate comes from
+#[async_trait]
+pub trait BtcUsdSource: Send + Sync {
+    async fn btc_usd(&self) -> Result<Decimal>;
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotPrice {
+    data: SpotAmount,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotAmount {
+    amount: Decimal,
+}
+
+/// Spot price endpoint answering `{"data": {"amount": "…"}}`, as Coinbase's does
+pub struct HttpBtcUsdSource {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpBtcUsdSource {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl BtcUsdSource for HttpBtcUsdSource {
+    async fn btc_usd(&self) -> Result<Decimal> {
+        let spot: SpotPrice = self.client.get(&self.url).send().await?.error_for_status()?.json().await?;
+        if spot.data.amount <= Decimal::ZERO {
+            return Err(anyhow::anyhow!("Invalid BTC/USD rate {} from {}", spot.data.amount, self.url));
+        }
+        Ok(spot.data.amount)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Funds {
+    balance: Decimal,
+    refreshed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct Reservation {
+    leg: Leg,
+    amount: Decimal,
+    /// When the order's leg returned with the order still working
+    pending_since: Option<DateTime<Utc>>,
+}
+
+/// Free balance of one venue, net of reservations
+#[derive(Debug, Clone, Serialize)]
+pub struct VenueFunds {
+    pub leg: Leg,
+    pub asset: Asset,
+    pub balance: Decimal,
+    pub reserved: Decimal,
+    pub free: Decimal,
+    pub refreshed_at: Option<DateTime<Utc>>,
+}
+
+/// Cached per-venue balances with reservations for orders in flight.
+///
+/// Funds are reserved when a leg is submitted, spent from the cached balance
+/// when it fills and released when it fails or is cancelled. A refresh from
+/// the venue replaces the cached balance; reservations stay until their order
+/// resolves. Orders still working when their leg returns are marked pending
+/// and settled later from a venue lookup, or released after a timeout.
+pub struct Treasury {
+    assets: HashMap<Leg, Asset>,
+    usd_prices: Mutex<HashMap<Asset, Decimal>>,
+    rate_source: Option<Box<dyn BtcUsdSource>>,
+    /// When the BTC/USD rate was last fetched; `None` while on the configured price
+    rate_refreshed_at: Mutex<Option<DateTime<Utc>>>,
+    max_age: chrono::Duration,
+    pending_ttl: chrono::Duration,
+    funds: Mutex<HashMap<Leg, Funds>>,
+    reservations: Mutex<HashMap<String, Reservation>>,
+}
+
+impl Treasury {
+    pub fn new(polymarket: Asset, btc: Asset) -> Self {
+        Self {
+            assets: HashMap::from([(Leg::Polymarket, polymarket), (Leg::Btc, btc)]),
+            usd_prices: Mutex::new(HashMap::from([(Asset::Usdc, Decimal::ONE)])),
+            rate_source: None,
+            rate_refreshed_at: Mutex::new(None),
+            max_age: chrono::Duration::seconds(60),
+            pending_ttl: chrono::Duration::seconds(3600),
+            funds: Mutex::new(HashMap::new()),
+            reservations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let treasury =
+            Self::new(Asset::Usdc, Asset::for_protocol(&config.bitcoin.protocol)).with_prices(&config.treasury);
+        match config.treasury.btc_usd_url.as_str() {
+            "" => treasury,
+            url => treasury.with_rate_source(Box::new(HttpBtcUsdSource::new(url))),
+        }
+    }
+
+    /// USD prices used to size legs funded in non-dollar assets. The BTC/USD
+    /// price holds until a rate source replaces it.
+    pub fn with_prices(mut self, config: &TreasuryConfig) -> Self {
+        self.usd_prices.get_mut().expect("treasury lock poisoned").extend(
+            [Asset::Sats, Asset::Btc, Asset::Rbtc, Asset::RskCollateral, Asset::Lbtc, Asset::Stx]
+                .map(|asset| (asset, asset.usd_price(config))),
+        );
+        self.max_age = chrono::Duration::seconds(config.max_balance_age_secs as i64);
+        self.pending_ttl = chrono::Duration::seconds(config.pending_reservation_ttl_secs as i64);
+        self
+    }
+
+    /// Fetch the BTC/USD rate from `source` whenever it is older than the
+    /// balance age
+    pub fn with_rate_source(mut self, source: Box<dyn BtcUsdSource>) -> Self {
+        self.rate_source = Some(source);
+        self
+    }
+
+    /// USD price of one unit of `asset`, live for BTC-denominated assets once fetched
+    pub fn usd_price(&self, asset: Asset) -> Option<Decimal> {
+        self.usd_prices.lock().expect("treasury lock poisoned").get(&asset).copied()
+    }
+
+    /// Price every BTC-denominated asset at `btc_usd`
+    pub fn set_btc_usd(&self, btc_usd: Decimal) {
+        let mut prices = self.usd_prices.lock().expect("treasury lock poisoned");
+        for asset in [Asset::Btc, Asset::Rbtc, Asset::Lbtc] {
+            prices.insert(asset, btc_usd);
+        }
+        prices.insert(Asset::Sats, btc_usd / Decimal::from(100_000_000));
+        *self.rate_refreshed_at.lock().expect("treasury lock poisoned") = Some(Utc::now());
+    }
+
+    /// Re-fetch the BTC/USD rate if it is missing or older than the balance
+    /// age. Without a source the configured price stands.
+    pub async fn refresh_btc_usd(&self) -> Result<()> {
+        let Some(source) = self.rate_source.as_ref() else {
+            return Ok(());
+        };
+        let stale = self
+            .rate_refreshed_at
+            .lock()
+            .expect("treasury lock poisoned")
+            .is_none_or(|at| Utc::now() - at > self.max_age);
+        if stale {
+            self.set_btc_usd(source.btc_usd().await?);
+        }
+        Ok(())
+    }
+
+    pub fn asset(&self, leg: Leg) -> Asset {
+        self.assets[&leg]
+    }
+
+    /// BTC/USD rate for a leg whose collateral is BTC-denominated
+    pub fn btc_usd(&self, leg: Leg) -> Option<Decimal> {
+        match self.asset(leg) {
+            Asset::Sats | Asset::Btc | Asset::Rbtc | Asset::Lbtc => self.usd_price(Asset::Btc),
+            Asset::Usdc | Asset::RskCollateral | Asset::Stx => None,
+        }
+    }
+
+    pub fn set_balance(&self, leg: Leg, balance: Decimal) {
+        self.funds.lock().expect("treasury lock poisoned").insert(
+            leg,
+            Funds {
+                balance,
+                refreshed_at: Utc::now(),
+            },
+        );
+    }
+
+    /// Fetch a venue's balance, keeping the cached one if the venue fails
+    pub async fn refresh(&self, leg: Leg, executor: &dyn TradeExecutor) -> Result<Decimal> {
+        let balance = executor.get_balance().await?;
+        self.set_balance(leg, balance);
+        Ok(balance)
+    }
+
+    /// Whether a venue's balance is missing or older than the configured age
+    pub fn is_stale(&self, leg: Leg) -> bool {
+        match self.funds.lock().expect("treasury lock poisoned").get(&leg) {
+            Some(funds) => Utc::now() - funds.refreshed_at > self.max_age,
+            None => true,
+        }
+    }
+
+    pub fn funds(&self, leg: Leg) -> VenueFunds {
+        let cached = self.funds.lock().expect("treasury lock poisoned").get(&leg).cloned();
+        let reserved: Decimal = self
+            .reservations
+            .lock()
+            .expect("treasury lock poisoned")
+            .values()
+            .filter(|r| r.leg == leg)
+            .map(|r| r.amount)
+            .sum();
+        let balance = cached.as_ref().map(|f| f.balance).unwrap_or_default();
+
+        VenueFunds {
+            leg,
+            asset: self.asset(leg),
+            balance,
+            reserved,
+            free: (balance - reserved).max(Decimal::ZERO),
+            refreshed_at: cached.map(|f| f.refreshed_at),
+        }
+    }
+
+    /// A venue's free balance in USD, `None` if its asset has no USD price
+    pub fn free_usd(&self, leg: Leg) -> Option<Decimal> {
+        let price = self.usd_price(self.asset(leg))?;
+        Some(self.funds(leg).free * price)
+    }
+
+    /// Cost of `size` shares on a leg at its limit price, in the venue's asset.
+    /// `None` if the asset has no USD price.
+    pub fn leg_cost(&self, leg: Leg, opportunity: &ArbitrageOpportunity, config: &TradeConfig, size: Decimal) -> Option<Decimal> {
+        let odds = match leg {
+            Leg::Polymarket => opportunity.polymarket_odds.odds,
+            Leg::Btc => opportunity.btc_market_odds.odds,
+        };
+        let usd = size * config.limit_price(odds);
+        let price = self.usd_price(self.asset(leg)).filter(|p| !p.is_zero())?;
+        Some(usd / price)
+    }
+
+    /// Largest size both venues can fund at their limit prices, capped at `config.size`
+    pub fn fundable_size(&self, opportunity: &ArbitrageOpportunity, config: &TradeConfig) -> Decimal {
+        [Leg::Polymarket, Leg::Btc]
+            .into_iter()
+            .map(|leg| match self.leg_cost(leg, opportunity, config, Decimal::ONE) {
+                Some(unit) if !unit.is_zero() => self.funds(leg).free / unit,
+                Some(_) => config.size,
+                None => Decimal::ZERO,
+            })
+            .fold(config.size, Decimal::min)
+            .round_dp_with_strategy(6, rust_decimal::RoundingStrategy::ToZero)
+    }
+
+    /// Hold `amount` of a venue's asset for an order
+    pub fn reserve(&self, client_order_id: &str, leg: Leg, amount: Decimal) -> Result<()> {
+        // Check and insert under one lock so concurrent orders can't both take the same funds
+        let mut reservations = self.reservations.lock().expect("treasury lock poisoned");
+        let reserved: Decimal = reservations.values().filter(|r| r.leg == leg).map(|r| r.amount).sum();
+        let balance = self
+            .funds
+            .lock()
+            .expect("treasury lock poisoned")
+            .get(&leg)
+            .map(|f| f.balance)
+            .unwrap_or_default();
+
+        let free = (balance - reserved).max(Decimal::ZERO);
+        if amount > free {
+            return Err(anyhow::anyhow!(
+                "{} leg needs {} {} but only {} is free",
+                leg,
+                amount,
+                self.asset(leg),
+                free
+            ));
+        }
+        reservations.insert(client_order_id.to_string(), Reservation { leg, amount, pending_since: None });
+        Ok(())
+    }
+
+    /// The order is still working after its leg returned: keep its funds
+    /// until the venue reports how it ended
+    pub fn hold(&self, client_order_id: &str) {
+        if let Some(reservation) = self.reservations.lock().expect("treasury lock poisoned").get_mut(client_order_id) {
+            reservation.pending_since.get_or_insert_with(Utc::now);
+        }
+    }
+
+    /// Client order ids of a venue's held orders
+    pub fn pending(&self, leg: Leg) -> Vec<String> {
+        self.reservations
+            .lock()
+            .expect("treasury lock poisoned")
+            .iter()
+            .filter(|(_, r)| r.leg == leg && r.pending_since.is_some())
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Whether a held order has waited longer than the pending timeout
+    pub fn is_expired(&self, client_order_id: &str) -> bool {
+        self.reservations
+            .lock()
+            .expect("treasury lock poisoned")
+            .get(client_order_id)
+            .and_then(|r| r.pending_since)
+            .is_some_and(|since| Utc::now() - since >= self.pending_ttl)
+    }
+
+    /// The order failed or was cancelled: free its funds
+    pub fn release(&self, client_order_id: &str) {
+        self.reservations.lock().expect("treasury lock poisoned").remove(client_order_id);
+    }
+
+    /// The order filled: take its funds out of the cached balance
+    pub fn spend(&self, client_order_id: &str) {
+        let reservation = self.reservations.lock().expect("treasury lock poisoned").remove(client_order_id);
+        if let Some(reservation) = reservation {
+            if let Some(funds) = self.funds.lock().expect("treasury lock poisoned").get_mut(&reservation.leg) {
+                funds.balance = (funds.balance - reservation.amount).max(Decimal::ZERO);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MarketOdds, MarketSource};
+
+    fn opportunity() -> ArbitrageOpportunity {
+        let odds = |market_id: &str, odds: i64, source| MarketOdds {
+            market_id: market_id.to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(odds, 2),
+            source,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        ArbitrageOpportunity::new(
+            odds("poly", 40, MarketSource::Polymarket),
+            odds("btc", 50, MarketSource::BitcoinPredictionMarket),
+            Decimal::new(10, 2),
+            0.9,
+        )
+    }
+
+    fn trade_config() -> TradeConfig {
+        let config = Config::default();
+        let mut trade = TradeConfig::from_config(&config, &opportunity(), &Default::default()).unwrap();
+        trade.size = Decimal::from(100);
+        trade.limit_tolerance = Decimal::ZERO;
+        trade
+    }
+
+    fn treasury() -> Treasury {
+        Treasury::new(Asset::Usdc, Asset::Sats).with_prices(&TreasuryConfig {
+            btc_usd_price: Decimal::from(50_000),
+            ..TreasuryConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_size_capped_at_smaller_funded_side() {
+        let treasury = treasury();
+        let opportunity = opportunity();
+        let config = trade_config();
+
+        // 20 USDC buys 50 shares at 0.40; 20,000 sats ($10) buys 20 at 0.50
+        treasury.set_balance(Leg::Polymarket, Decimal::from(20));
+        treasury.set_balance(Leg::Btc, Decimal::from(20_000));
+        assert_eq!(treasury.fundable_size(&opportunity, &config), Decimal::from(20));
+
+        treasury.set_balance(Leg::Btc, Decimal::from(1_000_000));
+        assert_eq!(treasury.fundable_size(&opportunity, &config), Decimal::from(50));
+    }
+
+    #[test]
+    fn test_reservations_follow_order_lifecycle() {
+        let treasury = treasury();
+        treasury.set_balance(Leg::Polymarket, Decimal::from(100));
+
+        treasury.reserve("a-polymarket", Leg::Polymarket, Decimal::from(60)).unwrap();
+        assert_eq!(treasury.funds(Leg::Polymarket).free, Decimal::from(40));
+        assert!(treasury.reserve("b-polymarket", Leg::Polymarket, Decimal::from(50)).is_err());
+
+        treasury.release("a-polymarket");
+        treasury.reserve("b-polymarket", Leg::Polymarket, Decimal::from(50)).unwrap();
+        treasury.spend("b-polymarket");
+        let funds = treasury.funds(Leg::Polymarket);
+        assert_eq!((funds.balance, funds.reserved, funds.free), (Decimal::from(50), Decimal::ZERO, Decimal::from(50)));
+    }
+}
@@ -0,0 +1,323 @@
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::{Alert, AlertSink, LogAlertSink};
+use crate::config::RiskConfig;
+use crate::models::ArbitrageOpportunity;
+use crate::trader::{Leg, LegOutcome, TradeConfig, TradeStatus};
+
+/// Loss budget and retry ladder for unwinding a one-legged arbitrage
+#[derive(Debug, Clone)]
+pub struct UnwindPolicy {
+    /// Most the unwind may cost, in USD
+    pub max_loss: Decimal,
+    pub price_step: Decimal,
+    pub max_attempts: u32,
+}
+
+impl UnwindPolicy {
+    pub fn from_config(config: &RiskConfig) -> Self {
+        Self {
+            max_loss: config.unwind_max_loss,
+            price_step: config.unwind_price_step,
+            max_attempts: config.unwind_max_attempts,
+        }
+    }
+
+    /// Extra cost of hedging at `price` instead of the planned `limit`
+    pub fn hedge_cost(&self, limit: Decimal, price: Decimal, size: Decimal) -> Decimal {
+        (price - limit) * size
+    }
+
+    /// Successively worse hedge limits whose extra cost stays within budget
+    pub fn hedge_limits(&self, limit: Decimal, size: Decimal) -> Vec<Decimal> {
+        (1..=self.max_attempts)
+            .map(|k| limit + self.price_step * Decimal::from(k))
+            .take_while(|price| *price < Decimal::ONE && self.hedge_cost(limit, *price, size) <= self.max_loss)
+            .collect()
+    }
+
+    /// Lowest price the filled leg may be sold back at
+    pub fn sell_back_floor(&self, entry: Decimal, size: Decimal) -> Decimal {
+        if size.is_zero() {
+            return entry;
+        }
+        (entry - self.max_loss / size).max(Decimal::ZERO)
+    }
+}
+
+impl Default for UnwindPolicy {
+    fn default() -> Self {
+        Self::from_config(&RiskConfig::default())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UnwindAction {
+    /// The missing leg filled at a worse limit
+    Hedged,
+    /// The filled leg was sold back
+    SoldBack,
+    /// Neither worked; an operator was alerted
+    Escalated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnwindReport {
+    pub action: UnwindAction,
+    /// Leg that had filled when the unwind started
+    pub filled_leg: Leg,
+    /// Realized cost of the unwind in USD (zero when escalated)
+    pub cost: Decimal,
+    /// Hedge or sell-back price
+    pub price: Option<Decimal>,
+    pub tx_id: Option<String>,
+    /// What was tried, in order
+    pub attempts: Vec<String>,
+}
+
+/// Sends the unwind's orders through the same checks as any other leg
+#[async_trait]
+pub trait UnwindVenues: Send + Sync {
+    /// Send a hedge retry as a leg of the execution under `execution_key`
+    async fn hedge(
+        &self,
+        execution_key: &str,
+        leg: Leg,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> LegOutcome;
+
+    /// Sell the filled `leg` back at no less than `min_price`
+    async fn sell_back(
+        &self,
+        leg: Leg,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+        min_price: Decimal,
+    ) -> LegOutcome;
+}
+
+/// The one-legged state an unwind starts from
+pub struct OpenLeg<'a> {
+    pub filled_leg: Leg,
+    pub venues: &'a dyn UnwindVenues,
+    pub position_id: Option<String>,
+}
+
+/// Closes directional exposure left by a failed hedge: retries the hedge at
+/// worse limits within the loss budget, then sells the filled leg back, then
+/// escalates to an operator
+pub struct UnwindEngine {
+    policy: UnwindPolicy,
+    alerts: Box<dyn AlertSink>,
+}
+
+impl UnwindEngine {
+    pub fn new(policy: UnwindPolicy, alerts: Box<dyn AlertSink>) -> Self {
+        Self { policy, alerts }
+    }
+
+    pub fn policy(&self) -> &UnwindPolicy {
+        &self.policy
+    }
+
+    /// Where escalations go
+    pub fn alerts(&self) -> &dyn AlertSink {
+        self.alerts.as_ref()
+    }
+
+    pub async fn unwind(
+        &self,
+        open: OpenLeg<'_>,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+    ) -> UnwindReport {
+        let hedge_leg = open.filled_leg.other();
+        let size = config.size;
+        let hedge_odds = match hedge_leg {
+            Leg::Polymarket => &opportunity.polymarket_odds,
+            Leg::Btc => &opportunity.btc_market_odds,
+        };
+        let mut attempts = Vec::new();
+
+        for (attempt, limit) in self.policy.hedge_limits(hedge_odds.odds, size).into_iter().enumerate() {
+            let mut retry = opportunity.clone();
+            match hedge_leg {
+                Leg::Polymarket => retry.polymarket_odds.odds = limit,
+                Leg::Btc => retry.btc_market_odds.odds = limit,
+            }
+            // Each retry is a new order and needs its own client order id
+            let mut retry_config = config.clone();
+            retry_config.idempotency_key = format!("{}-unwind{}", config.idempotency_key, attempt + 1);
+
+            let result = match open
+                .venues
+                .hedge(&config.idempotency_key, hedge_leg, &retry, &retry_config)
+                .await
+            {
+                LegOutcome::Completed(result) => result,
+                outcome @ (LegOutcome::TimedOut | LegOutcome::Unknown(_)) => {
+                    // The order may be live; retrying could double the hedge
+                    attempts.push(format!("hedge {} at {}: {}", hedge_leg, limit, outcome.error().unwrap_or_default()));
+                    return self.escalate(open, opportunity, config, attempts).await;
+                }
+                outcome => {
+                    attempts.push(format!("hedge {} at {}: {}", hedge_leg, limit, outcome.error().unwrap_or_default()));
+                    continue;
+                }
+            };
+
+            match result.status {
+                TradeStatus::Success => {
+                    let price = result.fill_price.unwrap_or(limit);
+                    let tx_id = match hedge_leg {
+                        Leg::Polymarket => result.polymarket_tx,
+                        Leg::Btc => result.btc_tx,
+                    };
+                    attempts.push(format!("hedge {} at {}: filled", hedge_leg, limit));
+                    tracing::info!("Hedged one-legged position on {} at {}", hedge_leg, price);
+                    return UnwindReport {
+                        action: UnwindAction::Hedged,
+                        filled_leg: open.filled_leg,
+                        cost: self.policy.hedge_cost(hedge_odds.odds, price, size).max(Decimal::ZERO),
+                        price: Some(price),
+                        tx_id,
+                        attempts,
+                    };
+                }
+                TradeStatus::Failed => {
+                    attempts.push(format!(
+                        "hedge {} at {}: {}",
+                        hedge_leg,
+                        limit,
+                        result.error.unwrap_or_else(|| "rejected".to_string())
+                    ));
+                }
+                TradeStatus::Pending | TradeStatus::Partial => {
+                    // Selling back now could leave the hedge naked instead
+                    attempts.push(format!("hedge {} at {}: {:?}", hedge_leg, limit, result.status));
+                    return self.escalate(open, opportunity, config, attempts).await;
+                }
+            }
+        }
+
+        let filled_odds = match open.filled_leg {
+            Leg::Polymarket => &opportunity.polymarket_odds,
+            Leg::Btc => &opportunity.btc_market_odds,
+        };
+        let floor = self.policy.sell_back_floor(filled_odds.odds, size);
+        match open.venues.sell_back(open.filled_leg, opportunity, config, floor).await {
+            LegOutcome::Completed(result) if result.status == TradeStatus::Success => {
+                let price = result.fill_price.unwrap_or(floor);
+                let tx_id = match open.filled_leg {
+                    Leg::Polymarket => result.polymarket_tx,
+                    Leg::Btc => result.btc_tx,
+                };
+                attempts.push(format!("sell back {} at >= {}: filled at {}", open.filled_leg, floor, price));
+                tracing::info!("Sold back {} leg at {}", open.filled_leg, price);
+                return UnwindReport {
+                    action: UnwindAction::SoldBack,
+                    filled_leg: open.filled_leg,
+                    cost: ((filled_odds.odds - price) * size).max(Decimal::ZERO),
+                    price: Some(price),
+                    tx_id,
+                    attempts,
+                };
+            }
+            LegOutcome::Completed(result) => attempts.push(format!(
+                "sell back {} at >= {}: {:?} {}",
+                open.filled_leg,
+                floor,
+                result.status,
+                result.error.unwrap_or_default()
+            )),
+            outcome => attempts.push(format!(
+                "sell back {} at >= {}: {}",
+                open.filled_leg,
+                floor,
+                outcome.error().unwrap_or_default()
+            )),
+        }
+
+        self.escalate(open, opportunity, config, attempts).await
+    }
+
+    /// Alert an operator to the naked leg without trying to hedge it
+    pub async fn escalate(
+        &self,
+        open: OpenLeg<'_>,
+        opportunity: &ArbitrageOpportunity,
+        config: &TradeConfig,
+        attempts: Vec<String>,
+    ) -> UnwindReport {
+        let filled_odds = match open.filled_leg {
+            Leg::Polymarket => &opportunity.polymarket_odds,
+            Leg::Btc => &opportunity.btc_market_odds,
+        };
+        let alert = Alert::critical(
+            "Unwind failed",
+            format!(
+                "Naked {} leg on {} {} (size {}): {}",
+                open.filled_leg,
+                filled_odds.market_id,
+                filled_odds.outcome,
+                config.size,
+                attempts.join("; ")
+            ),
+            open.position_id.clone(),
+        );
+        if let Err(e) = self.alerts.send(&alert).await {
+            tracing::error!("Failed to deliver unwind alert: {}", e);
+        }
+
+        UnwindReport {
+            action: UnwindAction::Escalated,
+            filled_leg: open.filled_leg,
+            cost: Decimal::ZERO,
+            price: None,
+            tx_id: None,
+            attempts,
+        }
+    }
+}
+
+impl Default for UnwindEngine {
+    fn default() -> Self {
+        Self::new(UnwindPolicy::default(), Box::new(LogAlertSink))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> UnwindPolicy {
+        UnwindPolicy {
+            max_loss: Decimal::from(3),
+            price_step: Decimal::new(1, 2),
+            max_attempts: 5,
+        }
+    }
+
+    #[test]
+    fn test_hedge_limits_stay_within_budget() {
+        // 100 shares: each cent worse costs $1, so only three steps fit in $3
+        let limits = policy().hedge_limits(Decimal::new(40, 2), Decimal::from(100));
+        assert_eq!(limits, vec![Decimal::new(41, 2), Decimal::new(42, 2), Decimal::new(43, 2)]);
+    }
+
+    #[test]
+    fn test_hedge_limits_never_reach_one() {
+        let limits = policy().hedge_limits(Decimal::new(98, 2), Decimal::ONE);
+        assert_eq!(limits, vec![Decimal::new(99, 2)]);
+    }
+
+    #[test]
+    fn test_sell_back_floor() {
+        let policy = policy();
+        assert_eq!(policy.sell_back_floor(Decimal::new(60, 2), Decimal::from(100)), Decimal::new(57, 2));
+        assert_eq!(policy.sell_back_floor(Decimal::new(1, 2), Decimal::from(100)), Decimal::ZERO);
+    }
+}
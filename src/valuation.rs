@@ -0,0 +1,455 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::api::ordinals::OrdinalsMarketplace;
+use crate::api::polymarket::PolymarketBookSource;
+use crate::api::OrderBookSource;
+use crate::config::{Config, ValuationConfig};
+use crate::models::polymarket::OrderBook;
+use crate::pnl::{mark_key, Marks};
+use crate::positions::{Position, PositionLeg, PositionStatus};
+use crate::trader::Leg;
+
+/// Price an open leg is marked at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkMethod {
+    /// Best bid: what the next share would sell for
+    Bid,
+    /// Midpoint of best bid and ask
+    Mid,
+    /// Average price from selling the whole leg into the bids; shares the
+    /// book can't absorb are worth nothing
+    Liquidation,
+}
+
+impl MarkMethod {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "bid" => Ok(Self::Bid),
+            "mid" => Ok(Self::Mid),
+            "liquidation" => Ok(Self::Liquidation),
+            other => Err(anyhow::anyhow!("Unknown mark method: {}", other)),
+        }
+    }
+
+    /// Per-share mark of `quantity` shares, `None` if the book has no bids
+    pub fn mark(&self, book: &OrderBook, quantity: Decimal) -> Option<Decimal> {
+        let best_bid = book.bids.iter().map(|l| l.price).max()?;
+        match self {
+            Self::Bid => Some(best_bid),
+            Self::Mid => match book.asks.iter().map(|l| l.price).min() {
+                Some(best_ask) => Some((best_bid + best_ask) / Decimal::TWO),
+                None => Some(best_bid),
+            },
+            Self::Liquidation => {
+                if quantity.is_zero() {
+                    return Some(best_bid);
+                }
+                let mut bids = book.bids.clone();
+                bids.sort_by_key(|level| std::cmp::Reverse(level.price));
+                let mut remaining = quantity;
+                let mut proceeds = Decimal::ZERO;
+                for level in bids {
+                    let filled = remaining.min(level.size);
+                    proceeds += filled * level.price;
+                    remaining -= filled;
+                    if remaining.is_zero() {
+                        break;
+                    }
+                }
+                Some(proceeds / quantity)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedBook {
+    book: OrderBook,
+    at: DateTime<Utc>,
+}
+
+/// Mark of one open leg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegValuation {
+    pub venue: Leg,
+    pub market_id: String,
+    pub outcome: String,
+    pub quantity: Decimal,
+    /// `None` when no book has been seen for the leg
+    pub mark: Option<Decimal>,
+    pub marked_at: Option<DateTime<Utc>>,
+    pub stale: bool,
+    pub value: Decimal,
+    pub unrealized_pnl: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionValuation {
+    pub position_id: String,
+    pub market_id: String,
+    pub legs: Vec<LegValuation>,
+    pub cost: Decimal,
+    pub value: Decimal,
+    pub unrealized_pnl: Decimal,
+}
+
+/// Marked state of the whole book at one instant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub at: DateTime<Utc>,
+    pub method: MarkMethod,
+    pub positions: Vec<PositionValuation>,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    /// Legs marked from an old book, or not marked at all
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl PortfolioSnapshot {
+    /// Realized plus unrealized PnL, the series drawdowns are measured on
+    pub fn equity(&self) -> Decimal {
+        self.realized_pnl + self.unrealized_pnl
+    }
+
+    /// Marks of every marked leg, for `PositionManager::calculate_unrealized_pnl`
+    /// and the risk engine
+    pub fn marks(&self) -> Marks {
+        self.positions
+            .iter()
+            .flat_map(|p| p.legs.iter())
+            .filter_map(|leg| leg.mark.map(|mark| (mark_key(&leg.market_id, &leg.outcome), mark)))
+            .collect()
+    }
+}
+
+/// Marks open legs from the latest order books. Books arrive through
+/// `update_book` as the venues publish them, or are fetched by `refresh`.
+pub struct Valuer {
+    method: MarkMethod,
+    max_age: chrono::Duration,
+    sources: HashMap<Leg, Box<dyn OrderBookSource>>,
+    books: Mutex<HashMap<(String, String), CachedBook>>,
+}
+
+impl Valuer {
+    pub fn new(method: MarkMethod) -> Self {
+        Self {
+            method,
+            max_age: chrono::Duration::seconds(120),
+            sources: HashMap::new(),
+            books: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Book sources as for the price guard: Polymarket, plus the Ordinals
+    /// leg when an ord server is configured
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let valuer = Self::new(MarkMethod::from_name(&config.valuation.mark_method)?)
+            .with_max_age(&config.valuation)
+            .with_source(Leg::Polymarket, Box::new(PolymarketBookSource::new()));
+        let ordinals = config.bitcoin.ordinals.as_ref().filter(|_| config.bitcoin.protocol == "ordinals");
+        Ok(match ordinals.and_then(|o| o.endpoint.clone().zip(o.parent_inscription.clone())) {
+            Some((endpoint, parent)) => {
+                valuer.with_source(Leg::Btc, Box::new(OrdinalsMarketplace::new(endpoint, parent)))
+            }
+            None => valuer,
+        })
+    }
+
+    pub fn with_max_age(mut self, config: &ValuationConfig) -> Self {
+        self.max_age = chrono::Duration::seconds(config.max_mark_age_secs as i64);
+        self
+    }
+
+    pub fn with_source(mut self, leg: Leg, books: Box<dyn OrderBookSource>) -> Self {
+        self.sources.insert(leg, books);
+        self
+    }
+
+    /// Record a book update for a market outcome
+    pub fn update_book(&self, market_id: &str, book: OrderBook) {
+        self.books.lock().expect("valuation lock poisoned").insert(
+            mark_key(market_id, &book.outcome),
+            CachedBook { book, at: Utc::now() },
+        );
+    }
+
+    /// Fetch books for every open leg whose venue has a source. A failed
+    /// fetch keeps the cached book, which turns stale.
+    pub async fn refresh(&self, positions: &[Position]) {
+        for leg in positions.iter().flat_map(|p| p.legs.iter()).filter(|l| l.exit_price.is_none()) {
+            let Some(source) = self.sources.get(&leg.venue) else {
+                continue;
+            };
+            match source.order_book(&leg.market_id, &leg.outcome).await {
+                Ok(book) => self.update_book(&leg.market_id, OrderBook { outcome: leg.outcome.clone(), ..book }),
+                Err(e) => tracing::debug!("No book for {} {}: {}", leg.market_id, leg.outcome, e),
+            }
+        }
+    }
+
+    fn value_leg(&self, leg: &PositionLeg, now: DateTime<Utc>, warnings: &mut Vec<String>) -> LegValuation {
+        let cached = self
+            .books
+            .lock()
+            .expect("valuation lock poisoned")
+            .get(&mark_key(&leg.market_id, &leg.outcome))
+            .cloned();
+        let mark = cached.as_ref().and_then(|c| self.method.mark(&c.book, leg.quantity));
+        let marked_at = cached.as_ref().map(|c| c.at);
+        let stale = match marked_at {
+            Some(at) => now - at > self.max_age,
+            None => true,
+        };
+
+        match (mark, marked_at) {
+            (None, _) => warnings.push(format!("{} {} {} has no mark", leg.venue, leg.market_id, leg.outcome)),
+            (Some(_), Some(at)) if stale => warnings.push(format!(
+                "{} {} {} mark is {}s old",
+                leg.venue,
+                leg.market_id,
+                leg.outcome,
+                (now - at).num_seconds()
+            )),
+            _ => {}
+        }
+
+        LegValuation {
+            venue: leg.venue,
+            market_id: leg.market_id.clone(),
+            outcome: leg.outcome.clone(),
+            quantity: leg.quantity,
+            mark,
+            marked_at,
+            stale,
+            value: mark.map(|m| m * leg.quantity).unwrap_or_default(),
+            unrealized_pnl: mark.map(|m| leg.trading_pnl(m)).unwrap_or(-leg.fees),
+        }
+    }
+
+    /// Value every open position at the cached books
+    pub fn snapshot(&self, positions: &[Position], now: DateTime<Utc>) -> PortfolioSnapshot {
+        let mut warnings = Vec::new();
+        let mut valuations = Vec::new();
+        let mut realized_pnl = Decimal::ZERO;
+
+        for position in positions {
+            if position.status == PositionStatus::Closed {
+                realized_pnl += position.pnl.unwrap_or_default();
+                continue;
+            }
+            let legs: Vec<LegValuation> = position
+                .legs
+                .iter()
+                .filter(|leg| leg.exit_price.is_none())
+                .map(|leg| self.value_leg(leg, now, &mut warnings))
+                .collect();
+            valuations.push(PositionValuation {
+                position_id: position.id.clone(),
+                market_id: position.market_id.clone(),
+                cost: position.total_cost(),
+                value: legs.iter().map(|l| l.value).sum(),
+                unrealized_pnl: legs.iter().map(|l| l.unrealized_pnl).sum(),
+                legs,
+            });
+        }
+
+        PortfolioSnapshot {
+            at: now,
+            method: self.method,
+            unrealized_pnl: valuations.iter().map(|v| v.unrealized_pnl).sum(),
+            positions: valuations,
+            realized_pnl,
+            warnings,
+        }
+    }
+}
+
+/// Largest peak-to-trough fall in equity over a series
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Drawdown {
+    pub amount: Decimal,
+    pub peak: Decimal,
+    pub trough: Decimal,
+    pub peak_at: Option<DateTime<Utc>>,
+    pub trough_at: Option<DateTime<Utc>>,
+}
+
+/// Append-only JSON-lines time series of portfolio snapshots
+pub struct ValuationSeries {
+    path: PathBuf,
+    file: File,
+}
+
+impl ValuationSeries {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create valuation directory {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open valuation series {}", path.display()))?;
+        Ok(Self { path, file })
+    }
+
+    pub fn append(&mut self, snapshot: &PortfolioSnapshot) -> Result<()> {
+        let mut line = serde_json::to_string(snapshot)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Snapshots taken at or after `since`, oldest first
+    pub fn since(&self, since: DateTime<Utc>) -> Result<Vec<PortfolioSnapshot>> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut snapshots = Vec::new();
+        for (n, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<PortfolioSnapshot>(&line) {
+                Ok(snapshot) if snapshot.at >= since => snapshots.push(snapshot),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Skipping unreadable valuation line {}: {}", n + 1, e),
+            }
+        }
+        Ok(snapshots)
+    }
+
+    pub fn max_drawdown(snapshots: &[PortfolioSnapshot]) -> Drawdown {
+        let mut worst = Drawdown::default();
+        let mut peak: Option<(Decimal, DateTime<Utc>)> = None;
+        for snapshot in snapshots {
+            let equity = snapshot.equity();
+            let (peak_equity, peak_at) = match peak {
+                Some((p, at)) if p >= equity => (p, at),
+                _ => {
+                    peak = Some((equity, snapshot.at));
+                    (equity, snapshot.at)
+                }
+            };
+            if peak_equity - equity > worst.amount {
+                worst = Drawdown {
+                    amount: peak_equity - equity,
+                    peak: peak_equity,
+                    trough: equity,
+                    peak_at: Some(peak_at),
+                    trough_at: Some(snapshot.at),
+                };
+            }
+        }
+        worst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::polymarket::PriceLevel;
+    use crate::models::{ArbitrageOpportunity, MarketOdds, MarketSource};
+    use crate::positions::PositionManager;
+
+    fn level(price: i64, size: i64) -> PriceLevel {
+        PriceLevel {
+            price: Decimal::new(price, 2),
+            size: Decimal::from(size),
+        }
+    }
+
+    fn book(outcome: &str) -> OrderBook {
+        OrderBook {
+            outcome: outcome.to_string(),
+            bids: vec![level(40, 50), level(44, 30)],
+            asks: vec![level(48, 100)],
+        }
+    }
+
+    /// 100 Yes shares at 0.40 on Polymarket
+    fn positions() -> Vec<Position> {
+        let odds = MarketOdds {
+            market_id: "poly".to_string(),
+            outcome: "Yes".to_string(),
+            odds: Decimal::new(40, 2),
+            source: MarketSource::Polymarket,
+            timestamp: Utc::now(),
+            resolves_at: None,
+        };
+        let opportunity = ArbitrageOpportunity::new(
+            odds.clone(),
+            MarketOdds { source: MarketSource::BitcoinPredictionMarket, ..odds.clone() },
+            Decimal::new(10, 2),
+            0.9,
+        );
+        let mut manager = PositionManager::new();
+        let leg = PositionLeg::long(Leg::Polymarket, &odds, Decimal::from(100), Decimal::new(40, 2));
+        manager.open_position(&opportunity, vec![leg]).unwrap();
+        manager.get_all_positions()
+    }
+
+    #[test]
+    fn test_mark_methods() {
+        let book = book("Yes");
+        let quantity = Decimal::from(100);
+        assert_eq!(MarkMethod::Bid.mark(&book, quantity), Some(Decimal::new(44, 2)));
+        assert_eq!(MarkMethod::Mid.mark(&book, quantity), Some(Decimal::new(46, 2)));
+        // 30 at 0.44 + 50 at 0.40, the last 20 unsold: 33.2 / 100
+        assert_eq!(MarkMethod::Liquidation.mark(&book, quantity), Some(Decimal::new(332, 3)));
+    }
+
+    #[test]
+    fn test_snapshot_flags_stale_and_missing_marks() {
+        let valuer = Valuer::new(MarkMethod::Mid);
+        let positions = positions();
+        let now = Utc::now();
+
+        let snapshot = valuer.snapshot(&positions, now);
+        assert_eq!(snapshot.warnings.len(), 1);
+        assert_eq!(snapshot.unrealized_pnl, Decimal::ZERO);
+
+        valuer.update_book("poly", book("Yes"));
+        let snapshot = valuer.snapshot(&positions, now);
+        assert!(snapshot.warnings.is_empty());
+        assert_eq!(snapshot.positions[0].unrealized_pnl, Decimal::from(6));
+        assert_eq!(snapshot.marks()[&mark_key("poly", "Yes")], Decimal::new(46, 2));
+
+        let later = valuer.snapshot(&positions, now + chrono::Duration::minutes(5));
+        assert!(later.positions[0].legs[0].stale);
+        assert_eq!(later.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_series_round_trip_and_drawdown() {
+        let path = std::env::temp_dir().join(format!("valuations-{}.jsonl", uuid::Uuid::new_v4()));
+        let valuer = Valuer::new(MarkMethod::Bid);
+        let positions = positions();
+        let start = Utc::now();
+        let mut series = ValuationSeries::open(&path).unwrap();
+
+        // Equity 4, then 0 (bid falls to 0.40), then 2
+        for (i, bid) in [44, 40, 42].into_iter().enumerate() {
+            valuer.update_book("poly", OrderBook { bids: vec![level(bid, 100)], ..book("Yes") });
+            series.append(&valuer.snapshot(&positions, start + chrono::Duration::minutes(i as i64))).unwrap();
+        }
+
+        let snapshots = series.since(start).unwrap();
+        assert_eq!(snapshots.len(), 3);
+        let drawdown = ValuationSeries::max_drawdown(&snapshots);
+        assert_eq!((drawdown.peak, drawdown.trough, drawdown.amount), (Decimal::from(4), Decimal::ZERO, Decimal::from(4)));
+        std::fs::remove_file(&path).ok();
+    }
+}